@@ -1,20 +1,440 @@
+//! Locates MPIR, or GMP with the `backend-gmp` feature, and tells cargo how to link it.
+//!
+//! The library is searched for in this order:
+//!
+//! 1. `MPIR_LIB_DIR`, a directory containing the library. The `MPIR_*` variables apply to GMP too.
+//! 2. `pkg-config`, unless `MPIR_NO_PKG_CONFIG` is set.
+//! 3. The platform's default library paths.
+//! 4. On Windows, the prebuilt libraries checked in under `lib/win`.
+//!
+//! Setting `MPIR_STATIC=1` links the static archive, `MPIR_STATIC=0` the shared library. When it is
+//! unset, the shared library is preferred and the static archive is used if it is all there is. On
+//! Windows only the static archive is supported.
+//!
+//! With the `bundled` feature none of this applies: MPIR is built from the vendored source instead,
+//! see [`bundled`].
+
 use std::env;
-use std::path::Path;
+use std::path::{Path, PathBuf};
+
+/// The library to link. GMP takes precedence, so `backend-gmp` works without disabling defaults.
+const LIB: &str = if cfg!(feature = "backend-gmp") {
+    "gmp"
+} else {
+    "mpir"
+};
+
+/// The name of the library, for messages.
+const NAME: &str = if cfg!(feature = "backend-gmp") {
+    "GMP"
+} else {
+    "MPIR"
+};
 
 fn main() {
-    let dir = env::var("CARGO_MANIFEST_DIR").unwrap();
-
-    #[cfg(windows)]
-    #[cfg(target_pointer_width = "64")]
-    println!(
-        "cargo:rustc-link-search=native={}",
-        Path::new(&dir).join("lib").join("win").join("64").display()
-    );
-
-    #[cfg(windows)]
-    #[cfg(target_pointer_width = "32")]
-    println!(
-        "cargo:rustc-link-search=native={}",
-        Path::new(&dir).join("lib").join("win").join("32").display()
-    );
+    println!("cargo:rerun-if-changed=build.rs");
+    for var in ["MPIR_LIB_DIR", "MPIR_STATIC", "MPIR_NO_PKG_CONFIG"] {
+        println!("cargo:rerun-if-env-changed={}", var);
+    }
+
+    // docs.rs has neither library, and documentation does not need to link.
+    if env::var_os("DOCS_RS").is_some() {
+        return;
+    }
+
+    #[cfg(feature = "bundled")]
+    {
+        if cfg!(feature = "backend-gmp") {
+            panic!("the `bundled` feature builds MPIR and cannot be used with `backend-gmp`");
+        }
+        let dir = bundled::build();
+        return link(&dir, LinkKind::Static);
+    }
+
+    #[allow(unreachable_code)]
+    let target = Target::from_env();
+    let kind = LinkKind::from_env();
+
+    if let Some(dir) = env::var_os("MPIR_LIB_DIR") {
+        let dir = PathBuf::from(dir);
+        match target.find_in(&dir, kind) {
+            Some(kind) => return link(&dir, kind),
+            None => fail(&format!(
+                "MPIR_LIB_DIR is set to {}, but it does not contain {}",
+                dir.display(),
+                target.file_names(kind).join(" or ")
+            )),
+        }
+    }
+
+    if env::var_os("MPIR_NO_PKG_CONFIG").is_none() && probe_pkg_config(kind) {
+        return;
+    }
+
+    for dir in target.default_dirs() {
+        if let Some(kind) = target.find_in(&dir, kind) {
+            return link(&dir, kind);
+        }
+    }
+
+    fail(&format!(
+        "{} was not found by pkg-config or in the default library paths",
+        NAME
+    ));
+}
+
+/// Whether to link the static archive or the shared library.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum LinkKind {
+    Static,
+    Dynamic,
+    /// Not specified: prefer the shared library, but accept the static archive.
+    Any,
+}
+
+impl LinkKind {
+    fn from_env() -> Self {
+        match env::var("MPIR_STATIC").as_deref() {
+            Err(_) => LinkKind::Any,
+            Ok("0") | Ok("false") | Ok("no") => LinkKind::Dynamic,
+            Ok(_) => LinkKind::Static,
+        }
+    }
+}
+
+/// The parts of the target triple that affect where libraries live and what they are called.
+struct Target {
+    os: String,
+    pointer_width: String,
+    /// Debian-style multiarch directory, e.g. `x86_64-linux-gnu`.
+    multiarch: String,
+}
+
+impl Target {
+    fn from_env() -> Self {
+        let var = |name| env::var(name).unwrap_or_default();
+        let arch = var("CARGO_CFG_TARGET_ARCH");
+        let os = var("CARGO_CFG_TARGET_OS");
+        let env = var("CARGO_CFG_TARGET_ENV");
+        let arch = match arch.as_str() {
+            "x86" => "i386",
+            other => other,
+        };
+        Target {
+            multiarch: format!("{}-{}-{}", arch, os, env),
+            pointer_width: var("CARGO_CFG_TARGET_POINTER_WIDTH"),
+            os,
+        }
+    }
+
+    fn static_name(&self) -> String {
+        match self.os.as_str() {
+            "windows" => format!("{}.lib", LIB),
+            _ => format!("lib{}.a", LIB),
+        }
+    }
+
+    fn dynamic_names(&self) -> Vec<String> {
+        match self.os.as_str() {
+            // The import library has the same name as its static archive, so only static linking
+            // is supported on Windows.
+            "windows" => vec![],
+            "macos" | "ios" => vec![format!("lib{}.dylib", LIB)],
+            _ => vec![format!("lib{}.so", LIB)],
+        }
+    }
+
+    fn file_names(&self, kind: LinkKind) -> Vec<String> {
+        match kind {
+            LinkKind::Static => vec![self.static_name()],
+            LinkKind::Dynamic => self.dynamic_names(),
+            LinkKind::Any => {
+                let mut names = self.dynamic_names();
+                names.push(self.static_name());
+                names
+            }
+        }
+    }
+
+    /// Look for the library in `dir`, returning the kind that was found.
+    fn find_in(&self, dir: &Path, kind: LinkKind) -> Option<LinkKind> {
+        let has = |name: &String| dir.join(name).is_file();
+        if kind != LinkKind::Static && self.dynamic_names().iter().any(has) {
+            return Some(LinkKind::Dynamic);
+        }
+        if kind != LinkKind::Dynamic && has(&self.static_name()) {
+            return Some(LinkKind::Static);
+        }
+        None
+    }
+
+    fn default_dirs(&self) -> Vec<PathBuf> {
+        let mut dirs: Vec<PathBuf> = match self.os.as_str() {
+            "windows" => {
+                let dir = env::var("CARGO_MANIFEST_DIR").unwrap();
+                let width = if self.pointer_width == "32" {
+                    "32"
+                } else {
+                    "64"
+                };
+                return vec![Path::new(&dir).join("lib").join("win").join(width)];
+            }
+            "macos" => ["/opt/homebrew/lib", "/usr/local/lib", "/opt/local/lib"]
+                .iter()
+                .map(PathBuf::from)
+                .collect(),
+            _ => vec![
+                PathBuf::from("/usr/local/lib"),
+                Path::new("/usr/lib").join(&self.multiarch),
+                PathBuf::from(if self.pointer_width == "64" {
+                    "/usr/lib64"
+                } else {
+                    "/usr/lib32"
+                }),
+                PathBuf::from("/usr/lib"),
+            ],
+        };
+        dirs.retain(|dir| dir.is_dir());
+        dirs
+    }
+}
+
+fn probe_pkg_config(kind: LinkKind) -> bool {
+    pkg_config::Config::new()
+        .statik(kind == LinkKind::Static)
+        .probe(LIB)
+        .is_ok()
+}
+
+fn link(dir: &Path, kind: LinkKind) {
+    let kind = match kind {
+        LinkKind::Static => "static",
+        _ => "dylib",
+    };
+    println!("cargo:rustc-link-search=native={}", dir.display());
+    println!("cargo:rustc-link-lib={}={}", kind, LIB);
+}
+
+fn fail(reason: &str) -> ! {
+    panic!(
+        "
+
+{reason}.
+
+To fix this, do one of the following:
+
+  - Install {NAME}, including its development files, with your package manager.
+  - Point MPIR_LIB_DIR at the directory containing the library, e.g.
+        MPIR_LIB_DIR=/opt/{LIB}/lib cargo build
+  - Add the directory containing {LIB}.pc to PKG_CONFIG_PATH.
+
+Set MPIR_STATIC=1 to link the static archive, or MPIR_STATIC=0 for the shared library.
+
+"
+    )
+}
+
+#[cfg(feature = "bundled")]
+mod bundled {
+    //! Builds MPIR from the vendored source tarball.
+    //!
+    //! The build is cached in `target/<profile>/mpir-bundled/<key>`, where the key is made from
+    //! the target triple, the profile, whether the `fat` feature is enabled, and a hash of the
+    //! compiler, its flags and the source, so switching between them doesn't rebuild MPIR from
+    //! scratch and changing any of them doesn't reuse a stale build. Delete the directory to force a
+    //! rebuild.
+    //!
+    //! The compiler is found by the `cc` crate, so `CC`, `CC_<target>` and `CFLAGS` are honoured
+    //! and cross-compiling works as it does for any other C dependency.
+    //!
+    //! The tarball is not checked in, and is downloaded into `vendor/` as `vendor/README.md`
+    //! describes. It is checked against [`TARBALL_SHA256`] before it is extracted.
+    //! `MPIR_SOURCE` overrides it with another tarball or an already extracted source directory,
+    //! which is not checked. The contents of a source directory are not part of the cache key.
+    //!
+    //! Without the `fat` feature MPIR is tuned for the CPU of the build machine, and the library may
+    //! not run on older CPUs. With it, x86 builds include code for every CPU and pick at runtime,
+    //! and other architectures are built for the generic target triple.
+
+    use std::env;
+    use std::fs;
+    use std::path::{Path, PathBuf};
+    use std::process::Command;
+
+    use sha2::{Digest, Sha256};
+
+    const TARBALL: &str = "vendor/mpir-3.0.0.tar.bz2";
+
+    /// SHA-256 of the MPIR 3.0.0 release tarball from mpir.org.
+    const TARBALL_SHA256: &str = "52f63459cf3f9478859de29e00357f004050ead70b45913f2c2269d9708675bb";
+
+    /// Where the MPIR source comes from.
+    enum Source {
+        Tarball { path: PathBuf, sha256: String },
+        Dir(PathBuf),
+    }
+
+    /// Build MPIR if there is no cached build, and return the directory containing `libmpir.a`.
+    pub fn build() -> PathBuf {
+        println!("cargo:rerun-if-env-changed=MPIR_SOURCE");
+        println!("cargo:rerun-if-changed={}", TARBALL);
+
+        let var = |name| env::var(name).unwrap();
+        let target = var("TARGET");
+        let host = var("HOST");
+        let profile = var("PROFILE");
+        let fat = cfg!(feature = "fat");
+
+        if var("CARGO_CFG_TARGET_ENV") == "msvc" {
+            panic!("the `bundled` feature needs a GNU toolchain and cannot build MPIR for MSVC")
+        }
+
+        // MPIR is slow without optimisation, even in debug builds.
+        let compiler = cc::Build::new()
+            .opt_level(2)
+            .warnings(false)
+            .extra_warnings(false)
+            .get_compiler();
+        let cflags = compiler
+            .args()
+            .iter()
+            .map(|a| a.to_str().unwrap())
+            .collect::<Vec<_>>();
+        let source = source();
+
+        let mut inputs = Sha256::new();
+        inputs.update(compiler.path().to_string_lossy().as_bytes());
+        for flag in &cflags {
+            inputs.update([0]);
+            inputs.update(flag.as_bytes());
+        }
+        inputs.update([0]);
+        match &source {
+            Source::Tarball { sha256, .. } => inputs.update(sha256.as_bytes()),
+            Source::Dir(dir) => inputs.update(dir.to_string_lossy().as_bytes()),
+        }
+        let key = format!(
+            "{}-{}{}-{}",
+            target,
+            profile,
+            if fat { "-fat" } else { "" },
+            &hex(&inputs.finalize())[..16]
+        );
+        let out_dir = PathBuf::from(var("OUT_DIR"));
+        // OUT_DIR is target/<profile>/build/mpir-<hash>/out.
+        let cache = out_dir
+            .ancestors()
+            .nth(3)
+            .unwrap()
+            .join("mpir-bundled")
+            .join(key);
+        let lib_dir = cache.join("install").join("lib");
+        let done = cache.join(".complete");
+        if done.is_file() {
+            return lib_dir;
+        }
+
+        let _ = fs::remove_dir_all(&cache);
+        let src = extract(source, &cache);
+        let build = cache.join("build");
+        fs::create_dir_all(&build).unwrap();
+
+        let mut configure = Command::new("sh");
+        configure
+            .current_dir(&build)
+            .arg(src.join("configure"))
+            .arg(format!("--prefix={}", cache.join("install").display()))
+            .args(["--enable-static", "--disable-shared", "--with-pic"])
+            .env("CC", compiler.path())
+            .env("CFLAGS", cflags.join(" "));
+        if fat || target != host {
+            // An explicit triple stops configure from detecting, and tuning for, the build CPU.
+            configure.arg(format!("--build={}", host));
+            configure.arg(format!("--host={}", target));
+        }
+        if fat && matches!(var("CARGO_CFG_TARGET_ARCH").as_str(), "x86" | "x86_64") {
+            configure.arg("--enable-fat");
+        }
+        run(&mut configure);
+
+        let jobs = env::var("NUM_JOBS").unwrap_or_else(|_| "1".into());
+        run(Command::new("make")
+            .current_dir(&build)
+            .arg(format!("-j{}", jobs)));
+        run(Command::new("make").current_dir(&build).arg("install"));
+
+        fs::write(&done, "").unwrap();
+        lib_dir
+    }
+
+    /// Find the MPIR source, checking the vendored tarball against its checksum.
+    fn source() -> Source {
+        let path = match env::var_os("MPIR_SOURCE") {
+            Some(path) => PathBuf::from(path),
+            None => {
+                let manifest_dir = PathBuf::from(env::var("CARGO_MANIFEST_DIR").unwrap());
+                let path = manifest_dir.join(TARBALL);
+                let sha256 = sha256(&path);
+                if sha256 != TARBALL_SHA256 {
+                    panic!(
+                        "{} has SHA-256 {}, but MPIR 3.0.0 has {}",
+                        path.display(),
+                        sha256,
+                        TARBALL_SHA256
+                    )
+                }
+                return Source::Tarball { path, sha256 };
+            }
+        };
+        match path.is_dir() {
+            true => Source::Dir(path),
+            false => Source::Tarball {
+                sha256: sha256(&path),
+                path,
+            },
+        }
+    }
+
+    /// Return the source directory, extracting the tarball into `cache` if needed.
+    fn extract(source: Source, cache: &Path) -> PathBuf {
+        let path = match source {
+            Source::Dir(dir) => return dir,
+            Source::Tarball { path, .. } => path,
+        };
+        let dest = cache.join("src");
+        fs::create_dir_all(&dest).unwrap();
+        run(Command::new("tar")
+            .arg("-xf")
+            .arg(&path)
+            .arg("-C")
+            .arg(&dest)
+            .arg("--strip-components=1"));
+        dest
+    }
+
+    fn sha256(path: &Path) -> String {
+        let bytes = fs::read(path).unwrap_or_else(|e| {
+            panic!(
+                "the `bundled` feature needs the MPIR source, but {} could not be read: {}. \
+                 Download it from https://mpir.org/mpir-3.0.0.tar.bz2 as vendor/README.md \
+                 describes, or set MPIR_SOURCE to build from another copy",
+                path.display(),
+                e
+            )
+        });
+        hex(&Sha256::digest(bytes))
+    }
+
+    fn hex(bytes: &[u8]) -> String {
+        bytes.iter().map(|b| format!("{:02x}", b)).collect()
+    }
+
+    fn run(command: &mut Command) {
+        let status = command
+            .status()
+            .unwrap_or_else(|e| panic!("failed to run {:?}: {}", command, e));
+        if !status.success() {
+            panic!("{:?} failed with {}", command, status);
+        }
+    }
 }
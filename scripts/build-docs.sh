@@ -7,4 +7,4 @@ INDEX_PATH="${DOC_PATH}/index.html"
 rm -rf "${DOC_PATH}";
 mkdir "${DOC_PATH}";
 echo "<meta http-equiv=\"refresh\" content=\"0; url=doc/mpir\">" > "${INDEX_PATH}";
-cargo doc --workspace --no-deps --target-dir "${DOC_PATH}";
\ No newline at end of file
+DOCS_RS=1 cargo doc --workspace --no-deps --target-dir "${DOC_PATH}";
\ No newline at end of file
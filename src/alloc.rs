@@ -0,0 +1,231 @@
+//! Memory functions for MPIR
+//!
+//! The first time an [`Mpz`](crate::Mpz) is created, this crate replaces MPIR's memory functions
+//! with its own. They use `malloc`, unless [`use_rust_allocator`] is called first, in which case
+//! they use `std::alloc` and the global allocator sees all of MPIR's memory.
+//!
+//! If an allocation fails, the process is aborted with [`std::alloc::handle_alloc_error`], as it is
+//! for the standard collections. MPIR functions are not exception-safe: they can free an operand's
+//! limbs before allocating new ones, so unwinding out of one would leave that operand dangling.
+//!
+//! With the `zeroize` feature the memory functions overwrite every block with zeros before it is
+//! freed, and reallocate by moving to a new block and wiping the old one, so that no secret stays in
+//! freed memory after MPIR has finished with it.
+//!
+//! Code that calls MPIR through the raw bindings before creating an `Mpz` should call [`init`] or
+//! [`use_rust_allocator`] first, so that every block is allocated by the same functions.
+
+use std::alloc::Layout;
+use std::ptr::null_mut;
+use std::sync::atomic::{self, Ordering};
+use std::sync::Once;
+
+use crate::ctype::{alloc_func, c_void, free_func, realloc_func, size_t};
+use crate::mpz::{mp_get_memory_functions, mp_set_memory_functions};
+
+static INIT: Once = Once::new();
+
+/// Install this crate's memory functions, if they are not installed already.
+///
+/// The functions abort the process if an allocation fails, as MPIR cannot recover from one.
+pub fn init() {
+    INIT.call_once(|| unsafe { install(sys::alloc, sys::realloc, sys::free) });
+}
+
+/// Make MPIR allocate through the Rust global allocator, with `std::alloc`.
+///
+/// This must be called before MPIR allocates any memory, typically at the start of `main`, as blocks
+/// that were allocated with `malloc` cannot be freed by the global allocator.
+///
+/// As with `malloc`, a failed allocation aborts the process through
+/// [`std::alloc::handle_alloc_error`].
+///
+/// # Panics
+///
+/// If an [`Mpz`](crate::Mpz) has already been created, or [`init`] has been called.
+pub fn use_rust_allocator() {
+    let mut installed = false;
+    INIT.call_once(|| {
+        unsafe { install(rust::alloc, rust::realloc, rust::free) };
+        installed = true;
+    });
+    assert!(
+        installed,
+        "use_rust_allocator must be called before MPIR allocates any memory"
+    );
+}
+
+unsafe fn install(alloc: alloc_func, realloc: realloc_func, free: free_func) {
+    mp_set_memory_functions(Some(alloc), Some(realloc), Some(free));
+}
+
+/// Free a block that was allocated by MPIR, such as a string from `mpz_get_str`, with MPIR's
+/// current free function.
+///
+/// # Safety
+///
+/// `ptr` must have been allocated by MPIR with exactly `size` bytes.
+pub(crate) unsafe fn free(ptr: *mut c_void, size: usize) {
+    let mut free: Option<free_func> = None;
+    mp_get_memory_functions(null_mut(), null_mut(), &mut free);
+    let free = free.expect("MPIR has no free function");
+    free(ptr, size as size_t)
+}
+
+/// Whether to wipe blocks before they are freed.
+const WIPE: bool = cfg!(feature = "zeroize");
+
+/// Overwrite `size` bytes at `ptr` with zeros, with writes the compiler cannot remove.
+unsafe fn wipe(ptr: *mut c_void, size: size_t) {
+    let ptr = ptr.cast::<u8>();
+    for i in 0..size {
+        ptr.add(i).write_volatile(0);
+    }
+    atomic::compiler_fence(Ordering::SeqCst);
+}
+
+/// Reallocate by moving the contents to a block from `alloc`, and freeing the old one with `free`,
+/// which wipes it. `realloc` itself may free the old block, or leave part of it behind, unwiped.
+unsafe fn relocate(
+    ptr: *mut c_void,
+    old_size: size_t,
+    new_size: size_t,
+    alloc: alloc_func,
+    free: free_func,
+) -> *mut c_void {
+    let new = alloc(new_size);
+    std::ptr::copy_nonoverlapping(ptr.cast::<u8>(), new.cast::<u8>(), old_size.min(new_size));
+    free(ptr, old_size);
+    new
+}
+
+/// The layout of every block, aligned as `malloc` would.
+fn layout(size: size_t) -> Option<Layout> {
+    let align = std::mem::align_of::<libc::max_align_t>();
+    Layout::from_size_align(size.max(1), align).ok()
+}
+
+/// Abort the process after failing to allocate `size` bytes.
+fn out_of_memory(size: size_t) -> ! {
+    match layout(size) {
+        Some(layout) => std::alloc::handle_alloc_error(layout),
+        None => {
+            eprintln!("MPIR failed to allocate {} bytes", size);
+            std::process::abort()
+        }
+    }
+}
+
+/// Memory functions on `malloc`, `realloc` and `free`.
+mod sys {
+    use super::*;
+
+    pub(super) unsafe extern "C" fn alloc(size: size_t) -> *mut c_void {
+        let ptr = libc::malloc(size);
+        if ptr.is_null() && size != 0 {
+            out_of_memory(size);
+        }
+        ptr
+    }
+
+    pub(super) unsafe extern "C" fn realloc(
+        ptr: *mut c_void,
+        old_size: size_t,
+        new_size: size_t,
+    ) -> *mut c_void {
+        if WIPE {
+            return relocate(ptr, old_size, new_size, alloc, free);
+        }
+        let new = libc::realloc(ptr, new_size);
+        if new.is_null() && new_size != 0 {
+            out_of_memory(new_size);
+        }
+        new
+    }
+
+    pub(super) unsafe extern "C" fn free(ptr: *mut c_void, size: size_t) {
+        if WIPE && !ptr.is_null() {
+            wipe(ptr, size);
+        }
+        libc::free(ptr)
+    }
+}
+
+/// Memory functions on the Rust global allocator.
+///
+/// MPIR passes the size of a block when it is reallocated or freed, which is all `std::alloc`
+/// needs besides the alignment. Every block is aligned as `malloc` would.
+mod rust {
+    use super::*;
+
+    fn layout(size: size_t) -> Layout {
+        super::layout(size).unwrap_or_else(|| out_of_memory(size))
+    }
+
+    pub(super) unsafe extern "C" fn alloc(size: size_t) -> *mut c_void {
+        let ptr = std::alloc::alloc(layout(size));
+        if ptr.is_null() {
+            out_of_memory(size);
+        }
+        ptr.cast()
+    }
+
+    pub(super) unsafe extern "C" fn realloc(
+        ptr: *mut c_void,
+        old_size: size_t,
+        new_size: size_t,
+    ) -> *mut c_void {
+        if WIPE {
+            return relocate(ptr, old_size, new_size, alloc, free);
+        }
+        let new = std::alloc::realloc(ptr.cast(), layout(old_size), layout(new_size).size());
+        if new.is_null() {
+            out_of_memory(new_size);
+        }
+        new.cast()
+    }
+
+    pub(super) unsafe extern "C" fn free(ptr: *mut c_void, size: size_t) {
+        if WIPE {
+            wipe(ptr, size);
+        }
+        std::alloc::dealloc(ptr.cast(), layout(size))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn layout() {
+        let align = std::mem::align_of::<libc::max_align_t>();
+        assert_eq!(
+            super::layout(0).map(|l| (l.size(), l.align())),
+            Some((1, align))
+        );
+        assert_eq!(super::layout(24).map(|l| l.size()), Some(24));
+        assert_eq!(super::layout(usize::MAX), None);
+    }
+
+    #[test]
+    fn wipe() {
+        let mut buf = [0xaau8; 40];
+        unsafe { super::wipe(buf.as_mut_ptr().cast(), 32) };
+        assert_eq!(buf[..32], [0; 32]);
+        assert_eq!(buf[32..], [0xaa; 8]);
+    }
+
+    #[test]
+    fn relocate() {
+        unsafe {
+            let old = sys::alloc(16).cast::<u8>();
+            old.write_bytes(0x5a, 16);
+            let new = super::relocate(old.cast(), 16, 64, sys::alloc, sys::free).cast::<u8>();
+            assert_eq!(std::slice::from_raw_parts(new, 16), [0x5a; 16]);
+            let new = super::relocate(new.cast(), 64, 8, sys::alloc, sys::free).cast::<u8>();
+            assert_eq!(std::slice::from_raw_parts(new, 8), [0x5a; 8]);
+            sys::free(new.cast(), 8);
+        }
+    }
+}
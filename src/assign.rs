@@ -0,0 +1,9 @@
+//! In-place assignment
+//!
+//! [`Assign`] stores a value into an existing object, reusing its allocation where it can. It is
+//! what evaluates the incomplete values returned by operators such as `&a * &b`.
+
+/// Assign `src` to `self`, reusing the existing allocation where possible.
+pub trait Assign<Src = Self> {
+    fn assign(&mut self, src: Src);
+}
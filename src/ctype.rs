@@ -4,12 +4,48 @@ pub use std::ffi::CString;
 
 pub type mp_limb_t = usize;
 pub type mp_bitcnt_t = c_ulong;
+pub type mp_exp_t = c_long;
 pub type mpz_srcptr = *const mpz_struct;
 pub type mpz_ptr = *mut mpz_struct;
 
+pub type alloc_func = unsafe extern "C" fn(size: size_t) -> *mut c_void;
+pub type realloc_func =
+    unsafe extern "C" fn(ptr: *mut c_void, old_size: size_t, new_size: size_t) -> *mut c_void;
+pub type free_func = unsafe extern "C" fn(ptr: *mut c_void, size: size_t);
+
 #[repr(C)]
 pub struct mpz_struct {
     pub _mp_alloc: c_int,
     pub _mp_size: c_int,
     pub _mp_d: *mut c_void,
 }
+
+pub type mpq_srcptr = *const mpq_struct;
+pub type mpq_ptr = *mut mpq_struct;
+
+#[repr(C)]
+pub struct mpq_struct {
+    pub _mp_num: mpz_struct,
+    pub _mp_den: mpz_struct,
+}
+
+pub type mpf_srcptr = *const mpf_struct;
+pub type mpf_ptr = *mut mpf_struct;
+
+#[repr(C)]
+pub struct mpf_struct {
+    pub _mp_prec: c_int,
+    pub _mp_size: c_int,
+    pub _mp_exp: mp_exp_t,
+    pub _mp_d: *mut c_void,
+}
+
+pub type gmp_randstate_srcptr = *const gmp_randstate_struct;
+pub type gmp_randstate_ptr = *mut gmp_randstate_struct;
+
+#[repr(C)]
+pub struct gmp_randstate_struct {
+    pub _mp_seed: mpz_struct,
+    pub _mp_alg: c_int,
+    pub _mp_algdata: *mut c_void,
+}
@@ -0,0 +1,41 @@
+//! Errors returned by the safe API
+
+use std::error::Error;
+use std::fmt;
+
+/// An operation that MPIR cannot perform.
+///
+/// MPIR handles these by raising a signal or aborting the process, so the safe API checks for them
+/// before calling into MPIR. Allocation failure aborts the process, see [`crate::alloc`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum MpirError {
+    /// The divisor or modulus was zero.
+    DivisionByZero,
+    /// A base for converting to or from a string was not in the range 2 to 62.
+    InvalidBase(i32),
+    /// A value has no inverse modulo the given modulus.
+    NotInvertible,
+    /// The lower argument of a Jacobi or Legendre symbol was not odd and positive.
+    InvalidModulus,
+    /// The operand of a square root was negative.
+    SqrtOfNegative,
+}
+
+impl fmt::Display for MpirError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MpirError::DivisionByZero => f.write_str("attempt to divide by zero"),
+            MpirError::InvalidBase(base) => {
+                write!(f, "radix must be in the range 2..=62, got {}", base)
+            }
+            MpirError::NotInvertible => f.write_str("value has no inverse for the modulus"),
+            MpirError::InvalidModulus => f.write_str("modulus must be odd and positive"),
+            MpirError::SqrtOfNegative => {
+                f.write_str("attempt to take the square root of a negative number")
+            }
+        }
+    }
+}
+
+impl Error for MpirError {}
@@ -0,0 +1,639 @@
+//! Integer factorization
+//!
+//! [`factor`] splits an integer into primes with, in turn:
+//!
+//! 1. Trial division by the primes below 2^16, found with a sieve.
+//! 2. Pollard's rho method, in Brent's variant, which quickly finds factors of up to about 12
+//!    digits.
+//! 3. Pollard's p−1 method, which finds a prime factor `p` when `p − 1` has only small factors.
+//! 4. Lenstra's elliptic curve method (ECM), on Montgomery curves with Suyama's parametrization and
+//!    a baby-step giant-step second stage. The bounds are raised while a factor of up to a third of
+//!    the digits of the number is still plausible.
+//! 5. The self-initialising quadratic sieve, for numbers of up to 110 digits. Larger ones stay with
+//!    ECM, with ever larger bounds.
+//!
+//! Every factor is checked with [`Mpz::is_probably_prime`] before it is returned, so factors below
+//! 2^64 are certainly prime, and larger ones prime with overwhelming probability.
+//!
+//! ECM finds factors of up to 20 digits in seconds, and the time it takes grows with the size of
+//! the factor. The quadratic sieve takes the same time whatever the factors, growing with the size
+//! of the number: on one core, a 60-digit semiprime takes about 7 seconds and a 70-digit one about
+//! a minute, and each further 10 digits multiply the time by about 8. That puts 90- to 100-digit
+//! semiprimes, at one to several hours each, out of reach in practice, so [`factor`] should not be
+//! called on them. Use [`factor_with_limit`] there instead: it gives up after a set time, returning
+//! the composites it could not split.
+//!
+//! The individual methods are also available. Each returns a factor of `n` that is neither 1 nor
+//! `n`, but that is not necessarily prime.
+
+use std::collections::BTreeMap;
+use std::time::{Duration, Instant};
+
+use crate::ctype::c_ulong;
+use crate::mpz::{
+    mpz_abs, mpz_add, mpz_divisible_ui_p, mpz_mod, mpz_mul, mpz_perfect_power_p, mpz_root, mpz_sub,
+};
+use crate::Mpz;
+
+mod qs;
+
+pub use qs::quadratic_sieve;
+
+/// Primes below this are found by trial division.
+const TRIAL_LIMIT: u64 = 1 << 16;
+
+/// Iterations of Pollard's rho before moving on.
+const RHO_ITERATIONS: u64 = 1 << 16;
+
+/// Stage 1 bound for Pollard's p−1.
+const PM1_B1: u64 = 100_000;
+
+/// Stage 1 bounds for ECM, and the number of curves to try with each, suited to factors of 15, 20,
+/// 25, 30 and 35 digits. Beyond the table the bound keeps growing by a factor of 4.
+const ECM_LEVELS: [(u64, u32); 5] = [
+    (2_000, 25),
+    (11_000, 90),
+    (50_000, 300),
+    (250_000, 700),
+    (1_000_000, 1_800),
+];
+
+/// Rounds of Miller-Rabin for checking factors above 2^64.
+const REPS: u32 = 25;
+
+/// Factors with their exponents, in increasing order.
+pub type Factors = Vec<(Mpz, u32)>;
+
+/// Numbers of up to this many digits with no small factor are split with the quadratic sieve.
+const QS_DIGITS: usize = 110;
+
+/// The prime factorization of `|n|`, as `(prime, exponent)` pairs in increasing order.
+///
+/// 1 and -1 have no prime factors, and return an empty vector.
+///
+/// # Panics
+///
+/// If `n` is 0.
+pub fn factor(n: &Mpz) -> Factors {
+    factorize(n, Deadline(None)).0
+}
+
+/// Factor `|n|` as [`factor`] does, but stop looking for factors after about `limit`.
+///
+/// Returns the prime factors found, and the composite factors that were not split, both as
+/// `(factor, exponent)` pairs in increasing order. The second vector is empty if the factorization
+/// is complete.
+///
+/// # Panics
+///
+/// If `n` is 0.
+pub fn factor_with_limit(n: &Mpz, limit: Duration) -> (Factors, Factors) {
+    factorize(n, Deadline(Instant::now().checked_add(limit)))
+}
+
+fn factorize(n: &Mpz, deadline: Deadline) -> (Factors, Factors) {
+    assert!(!n.is_zero(), "cannot factor zero");
+    let mut factors = BTreeMap::new();
+    let mut composites = BTreeMap::new();
+    let (small, rest) = trial_division(n, TRIAL_LIMIT);
+    factors.extend(small);
+
+    // Composites still to be split, each with the power to which it divides n.
+    let mut pending = vec![(rest, 1)];
+    while let Some((m, k)) = pending.pop() {
+        if m == 1 {
+            continue;
+        }
+        if m.is_probably_prime(REPS) {
+            *factors.entry(m).or_insert(0) += k;
+        } else if let Some((root, e)) = perfect_power(&m) {
+            pending.push((root, k * e));
+        } else if let Some(d) = find_factor(&m, deadline) {
+            let (q, e) = m.remove(&d).unwrap();
+            pending.push((d, k * e as u32));
+            pending.push((q, k));
+        } else {
+            *composites.entry(m).or_insert(0) += k;
+        }
+    }
+    (
+        factors.into_iter().collect(),
+        composites.into_iter().collect(),
+    )
+}
+
+/// Divide out the primes below `limit` from `|n|`, returning them with their exponents, and what
+/// is left.
+///
+/// The primes are found with a sieve of Eratosthenes, so `limit` is best kept to a few million.
+pub fn trial_division(n: &Mpz, limit: u64) -> (Vec<(Mpz, u32)>, Mpz) {
+    let mut rest = abs(n);
+    let mut factors = Vec::new();
+    for p in primes_below(limit) {
+        if rest < p.saturating_mul(p) {
+            break;
+        }
+        let divisible = match c_ulong::try_from(p) {
+            Ok(p) => unsafe { mpz_divisible_ui_p(rest.as_raw(), p) != 0 },
+            Err(_) => rest.rem_round_u64(p, crate::Round::Trunc) == Ok(0),
+        };
+        if divisible {
+            let (q, e) = rest.remove(&Mpz::from(p)).unwrap();
+            factors.push((Mpz::from(p), e as u32));
+            rest = q;
+        }
+    }
+    if rest > 1 && rest < limit.saturating_mul(limit) {
+        factors.push((rest, 1));
+        rest = Mpz::from(1);
+    }
+    (factors, rest)
+}
+
+/// Find a factor of `n` with Pollard's rho method, using at most about `iterations` steps of
+/// `x -> x^2 + c`.
+pub fn pollard_rho(n: &Mpz, iterations: u64) -> Option<Mpz> {
+    if let Some(d) = trivial(n) {
+        return d;
+    }
+    let n = &abs(n);
+    let m = Modulus(n);
+    // Multiply this many differences together between gcds.
+    const BATCH: u64 = 128;
+
+    for c in 1..=3u32 {
+        let step = |y: &Mpz| m.add(&m.mul(y, y), &Mpz::from(c));
+        let (mut x, mut y, mut ys) = (Mpz::new(), Mpz::from(2), Mpz::new());
+        let (mut q, mut g) = (Mpz::from(1), Mpz::from(1));
+        let (mut r, mut steps) = (1, 0);
+        while g == 1 && steps < iterations {
+            x.clone_from(&y);
+            for _ in 0..r {
+                y = step(&y);
+            }
+            let mut k = 0;
+            while k < r && g == 1 {
+                ys.clone_from(&y);
+                for _ in 0..BATCH.min(r - k) {
+                    y = step(&y);
+                    q = m.mul(&q, &m.sub(&x, &y));
+                }
+                g = q.gcd(n);
+                k += BATCH;
+            }
+            steps += 2 * r;
+            r *= 2;
+        }
+        if g == *n {
+            // The batch went past the factor, so go back over it one step at a time.
+            loop {
+                ys = step(&ys);
+                g = m.sub(&x, &ys).gcd(n);
+                if g != 1 {
+                    break;
+                }
+            }
+        }
+        if g != 1 && g != *n {
+            return Some(g);
+        }
+        if g == 1 {
+            return None;
+        }
+    }
+    None
+}
+
+/// Find a factor `p` of `n` with Pollard's p−1 method, which succeeds when every prime power
+/// dividing `p − 1` is at most `b1`.
+pub fn pollard_pm1(n: &Mpz, b1: u64) -> Option<Mpz> {
+    if let Some(d) = trivial(n) {
+        return d;
+    }
+    let n = &abs(n);
+    let mut a = Mpz::from(2);
+    for (i, p) in primes_below(b1.saturating_add(1)).into_iter().enumerate() {
+        a = a.pow_mod_u64(prime_power(p, b1), n).unwrap();
+        if i % 1024 == 1023 || p > b1 / p {
+            let g = (a.clone() - 1u32).gcd(n);
+            if g == *n {
+                return None;
+            }
+            if g != 1 {
+                return Some(g);
+            }
+        }
+    }
+    let g = (a - 1u32).gcd(n);
+    (g != 1 && g != *n).then_some(g)
+}
+
+/// Find a factor of `n` with the elliptic curve method, trying `curves` curves with stage 1 bound
+/// `b1` and stage 2 bound `100 * b1`.
+///
+/// The curves are chosen deterministically, so calling this again with the same arguments tries
+/// the same curves.
+pub fn ecm(n: &Mpz, b1: u64, curves: u32) -> Option<Mpz> {
+    if let Some(d) = trivial(n) {
+        return d;
+    }
+    let n = abs(n);
+    let primes = primes_below(b1.saturating_add(1));
+    (0..curves as u64).find_map(|i| ecm_curve(&n, b1, &primes, 6 + i))
+}
+
+/// When to give up looking for a factor, if ever.
+#[derive(Clone, Copy)]
+struct Deadline(Option<Instant>);
+
+impl Deadline {
+    fn expired(self) -> bool {
+        self.0.is_some_and(|t| Instant::now() >= t)
+    }
+}
+
+/// Find a non-trivial factor of `m`, which is composite, not a perfect power, and has no factor
+/// below 2^16, or `None` if `deadline` passes first.
+fn find_factor(m: &Mpz, deadline: Deadline) -> Option<Mpz> {
+    if let Some(d) = pollard_rho(m, RHO_ITERATIONS).or_else(|| pollard_pm1(m, PM1_B1)) {
+        return Some(d);
+    }
+    // Run ECM for factors of up to a third of the digits of m, after which the quadratic sieve is
+    // likely to be faster, if m is small enough for it.
+    let digits = m.size_in_base(10);
+    let sieve = digits <= QS_DIGITS;
+    let levels = ECM_LEVELS.into_iter().chain(std::iter::successors(
+        Some((4_000_000, 5_000)),
+        |&(b1, c)| Some((b1 * 4, c * 2)),
+    ));
+    let mut sigma = 6;
+    for ((b1, curves), level) in levels.zip((15..).step_by(5)) {
+        if sieve && level > digits / 3 {
+            break;
+        }
+        let primes = primes_below(b1 + 1);
+        for _ in 0..curves {
+            if deadline.expired() {
+                return None;
+            }
+            if let Some(d) = ecm_curve(m, b1, &primes, sigma) {
+                return Some(d);
+            }
+            sigma += 1;
+        }
+    }
+    qs::quadratic_sieve_until(m, deadline)
+}
+
+/// The factors that need no search: 2 for even `n`, and none for `|n| <= 3`.
+fn trivial(n: &Mpz) -> Option<Option<Mpz>> {
+    if n.cmp_abs_u64(3) != std::cmp::Ordering::Greater {
+        Some(None)
+    } else if unsafe { mpz_divisible_ui_p(n.as_raw(), 2) } != 0 {
+        Some(Some(Mpz::from(2)))
+    } else {
+        None
+    }
+}
+
+/// `m` as `(root, e)` with `m = root^e` and `e > 1`, if it is a perfect power.
+fn perfect_power(m: &Mpz) -> Option<(Mpz, u32)> {
+    if unsafe { mpz_perfect_power_p(m.as_raw()) } == 0 {
+        return None;
+    }
+    let bits = m.size_in_base(2) as u64;
+    let mut root = Mpz::new();
+    primes_below(bits + 1).into_iter().find_map(|e| {
+        let exact = unsafe { mpz_root(root.as_raw_mut(), m.as_raw(), e as c_ulong) } != 0;
+        exact.then(|| (root.clone(), e as u32))
+    })
+}
+
+/// The largest power of the prime `p` that is at most `bound`.
+fn prime_power(p: u64, bound: u64) -> u64 {
+    let mut q = p;
+    while q <= bound / p {
+        q *= p;
+    }
+    q
+}
+
+/// The primes below `limit`, by the sieve of Eratosthenes.
+fn primes_below(limit: u64) -> Vec<u64> {
+    let limit = limit as usize;
+    let mut composite = vec![false; limit];
+    let mut primes = Vec::new();
+    for i in 2..limit {
+        if !composite[i] {
+            primes.push(i as u64);
+            for j in (i * i..limit).step_by(i) {
+                composite[j] = true;
+            }
+        }
+    }
+    primes
+}
+
+fn abs(n: &Mpz) -> Mpz {
+    let mut r = Mpz::new();
+    unsafe { mpz_abs(r.as_raw_mut(), n.as_raw()) };
+    r
+}
+
+/// Arithmetic modulo `n`, on values in the range `0..n`.
+#[derive(Clone, Copy)]
+struct Modulus<'a>(&'a Mpz);
+
+impl Modulus<'_> {
+    fn mul(self, a: &Mpz, b: &Mpz) -> Mpz {
+        let mut r = Mpz::new();
+        unsafe {
+            let p = r.as_raw_mut();
+            mpz_mul(p, a.as_raw(), b.as_raw());
+            mpz_mod(p, p, self.0.as_raw());
+        }
+        r
+    }
+
+    fn add(self, a: &Mpz, b: &Mpz) -> Mpz {
+        let mut r = Mpz::new();
+        unsafe {
+            let p = r.as_raw_mut();
+            mpz_add(p, a.as_raw(), b.as_raw());
+            mpz_mod(p, p, self.0.as_raw());
+        }
+        r
+    }
+
+    fn sub(self, a: &Mpz, b: &Mpz) -> Mpz {
+        let mut r = Mpz::new();
+        unsafe {
+            let p = r.as_raw_mut();
+            mpz_sub(p, a.as_raw(), b.as_raw());
+            mpz_mod(p, p, self.0.as_raw());
+        }
+        r
+    }
+}
+
+/// A point on a Montgomery curve `B y^2 = x^3 + A x^2 + x`, in projective coordinates `(X : Z)`
+/// with `y` dropped.
+#[derive(Clone)]
+struct Point {
+    x: Mpz,
+    z: Mpz,
+}
+
+/// A Montgomery curve modulo `n`, represented by `(A + 2) / 4`.
+struct Curve<'a> {
+    m: Modulus<'a>,
+    a24: Mpz,
+}
+
+impl Curve<'_> {
+    fn double(&self, p: &Point) -> Point {
+        let m = self.m;
+        let s = m.add(&p.x, &p.z);
+        let s = m.mul(&s, &s);
+        let d = m.sub(&p.x, &p.z);
+        let d = m.mul(&d, &d);
+        let t = m.sub(&s, &d);
+        Point {
+            x: m.mul(&s, &d),
+            z: m.mul(&t, &m.add(&d, &m.mul(&self.a24, &t))),
+        }
+    }
+
+    /// `p + q`, given `diff = p - q`.
+    fn add(&self, p: &Point, q: &Point, diff: &Point) -> Point {
+        let m = self.m;
+        let u = m.mul(&m.sub(&p.x, &p.z), &m.add(&q.x, &q.z));
+        let v = m.mul(&m.add(&p.x, &p.z), &m.sub(&q.x, &q.z));
+        let sum = m.add(&u, &v);
+        let dif = m.sub(&u, &v);
+        Point {
+            x: m.mul(&diff.z, &m.mul(&sum, &sum)),
+            z: m.mul(&diff.x, &m.mul(&dif, &dif)),
+        }
+    }
+
+    /// `k * p` for `k >= 1`, with the Montgomery ladder.
+    fn mul(&self, k: u64, p: &Point) -> Point {
+        let (mut r0, mut r1) = (p.clone(), self.double(p));
+        for i in (0..63 - k.leading_zeros()).rev() {
+            if k >> i & 1 == 1 {
+                r0 = self.add(&r1, &r0, p);
+                r1 = self.double(&r1);
+            } else {
+                r1 = self.add(&r1, &r0, p);
+                r0 = self.double(&r0);
+            }
+        }
+        r0
+    }
+}
+
+/// Run ECM on one curve, chosen by Suyama's parametrization with `sigma`, with `primes` the primes
+/// up to `b1`.
+fn ecm_curve(n: &Mpz, b1: u64, primes: &[u64], sigma: u64) -> Option<Mpz> {
+    let m = Modulus(n);
+    let factor = |g: Mpz| (g != 1 && g != *n).then_some(g);
+
+    // u = sigma^2 - 5, v = 4 sigma, x = u^3, z = v^3 and
+    // (A + 2) / 4 = (v - u)^3 (3u + v) / (16 u^3 v).
+    let sigma = Mpz::from(sigma);
+    let u = m.sub(&m.mul(&sigma, &sigma), &Mpz::from(5));
+    let v = m.mul(&sigma, &Mpz::from(4));
+    let u3 = m.mul(&m.mul(&u, &u), &u);
+    let v3 = m.mul(&m.mul(&v, &v), &v);
+    let vu = m.sub(&v, &u);
+    let num = m.mul(
+        &m.mul(&m.mul(&vu, &vu), &vu),
+        &m.add(&m.mul(&u, &Mpz::from(3)), &v),
+    );
+    let den = m.mul(&m.mul(&u3, &v), &Mpz::from(16));
+    let a24 = match den.invert(n) {
+        Some(inv) => m.mul(&num, &inv),
+        None => return factor(den.gcd(n)),
+    };
+    let curve = Curve { m, a24 };
+
+    // Stage 1: multiply by every prime power up to b1.
+    let mut q = Point { x: u3, z: v3 };
+    for &p in primes {
+        q = curve.mul(prime_power(p, b1), &q);
+    }
+    let g = q.z.gcd(n);
+    if g != 1 {
+        return factor(g);
+    }
+
+    // Stage 2: look for one more prime up to b2 as i D ± j, with D = 2310 and j coprime to D.
+    // Then x(i D Q) = x(j Q), and the difference of the two shares the factor.
+    const D: u64 = 2310;
+    let b2 = b1.saturating_mul(100);
+    let double = curve.double(&q);
+    let mut baby = vec![q.clone(), curve.mul(3, &q)];
+    for j in 2..D / 4 {
+        let next = curve.add(&baby[j as usize - 1], &double, &baby[j as usize - 2]);
+        baby.push(next);
+    }
+    let baby: Vec<&Point> = (0..D / 4)
+        .filter(|j| gcd(2 * j + 1, D) == 1)
+        .map(|j| &baby[j as usize])
+        .collect();
+
+    let step = curve.mul(D, &q);
+    let first = (b1 / D).max(1);
+    // prev = i D Q and giant = (i + 1) D Q.
+    let mut prev = curve.mul(first * D, &q);
+    let mut giant = curve.mul((first + 1) * D, &q);
+    let mut acc = Mpz::from(1);
+    let mut i = first;
+    while i * D <= b2 {
+        for b in &baby {
+            let cross = m.sub(&m.mul(&prev.x, &b.z), &m.mul(&b.x, &prev.z));
+            acc = m.mul(&acc, &cross);
+        }
+        let next = curve.add(&giant, &step, &prev);
+        prev = std::mem::replace(&mut giant, next);
+        i += 1;
+    }
+    factor(acc.gcd(n))
+}
+
+fn gcd(mut a: u64, mut b: u64) -> u64 {
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+    a
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn int(v: u64) -> Mpz {
+        Mpz::from(v)
+    }
+
+    fn check(n: &Mpz, factors: &[(Mpz, u32)]) {
+        let mut product = Mpz::from(1);
+        for (p, e) in factors {
+            assert!(p.is_probably_prime(REPS), "{} is not prime", p);
+            product *= p.pow(*e);
+        }
+        assert_eq!(product, abs(n));
+        assert!(factors.windows(2).all(|w| w[0].0 < w[1].0));
+    }
+
+    #[test]
+    fn small() {
+        assert_eq!(factor(&int(1)), []);
+        assert_eq!(factor(&Mpz::from(-1)), []);
+        assert_eq!(factor(&int(2)), [(int(2), 1)]);
+        assert_eq!(
+            factor(&Mpz::from(-360)),
+            [(int(2), 3), (int(3), 2), (int(5), 1)]
+        );
+        for n in 2..2000 {
+            check(&int(n), &factor(&int(n)));
+        }
+        assert_eq!(factor(&int(65537 * 65537)), [(int(65537), 2)]);
+        assert_eq!(factor(&int(4294967291)), [(int(4294967291), 1)]);
+    }
+
+    #[test]
+    #[should_panic(expected = "cannot factor zero")]
+    fn zero() {
+        factor(&Mpz::new());
+    }
+
+    #[test]
+    fn trial() {
+        let n = int(2 * 2 * 3 * 101 * 1_000_003);
+        let (small, rest) = trial_division(&n, 1000);
+        assert_eq!(small, [(int(2), 2), (int(3), 1), (int(101), 1)]);
+        assert_eq!(rest, 1_000_003);
+        let (small, rest) = trial_division(&int(97 * 97), 100);
+        assert_eq!(small, [(int(97), 2)]);
+        assert_eq!(rest, 1);
+    }
+
+    #[test]
+    fn rho() {
+        let n = int(1_000_003) * int(1_000_033);
+        let d = pollard_rho(&n, 1 << 16).unwrap();
+        assert!(d == 1_000_003 || d == 1_000_033);
+        assert_eq!(pollard_rho(&int(1_000_003), 1 << 10), None);
+        assert_eq!(pollard_rho(&int(10), 10), Some(int(2)));
+    }
+
+    #[test]
+    fn pm1() {
+        // p - 1 = 2^4 * 3^3 * 5 * 7 * 11 * 13 * 17 * k for the smallest k giving a prime, and
+        // q - 1 = 2 * (2^88 - 1) has the factor 2931542417.
+        let smooth = int(16 * 27 * 5 * 7 * 11 * 13 * 17);
+        let p = (1u32..)
+            .map(|k| &smooth * k + 1u32)
+            .find(|p| p.is_probably_prime(REPS))
+            .unwrap();
+        let q = (Mpz::from(1) << 89u32) - 1u32;
+        let n = &p * &q;
+        assert_eq!(pollard_pm1(&Mpz::from(n), 1000), Some(p));
+        assert_eq!(pollard_pm1(&q, 1000), None);
+    }
+
+    #[test]
+    fn elliptic_curves() {
+        let (p, q) = (int(7432339208719), Mpz::from(341117531003194129u64));
+        let n = Mpz::from(&p * &q);
+        let d = ecm(&n, 2000, 50).unwrap();
+        assert!(d == p || d == q);
+        assert_eq!(ecm(&q, 2000, 2), None);
+    }
+
+    #[test]
+    fn composite() {
+        // 2^101 - 1 needs ECM; 2^64 + 1 is split by rho.
+        let n = (Mpz::from(1) << 101u32) - 1u32;
+        assert_eq!(
+            factor(&n),
+            [(int(7432339208719), 1), (int(341117531003194129), 1)]
+        );
+        let n = (Mpz::from(1) << 64u32) + 1u32;
+        assert_eq!(factor(&n), [(int(274177), 1), (int(67280421310721), 1)]);
+
+        let n = int(1_000_003).pow(3) * int(65521).pow(2) * 7u32;
+        assert_eq!(
+            factor(&n),
+            [(int(7), 1), (int(65521), 2), (int(1_000_003), 3)]
+        );
+        let n = int(4294967291).pow(2) * int(4294967279);
+        assert_eq!(factor(&n), [(int(4294967279), 1), (int(4294967291), 2)]);
+        check(&n, &factor(&n));
+    }
+
+    #[test]
+    fn balanced() {
+        // Too large for rho, and left to the quadratic sieve.
+        let p = int(1_000_000_000_000_000).next_prime();
+        let q = int(3_000_000_000_000_000).next_prime();
+        let n = Mpz::from(&p * &q);
+        assert_eq!(factor(&n), [(p, 1), (q, 1)]);
+    }
+
+    #[test]
+    fn limit() {
+        let p = Mpz::from(10u32).pow(24).next_prime();
+        let q = Mpz::from(&p * 7u32).next_prime();
+        let pq = Mpz::from(&p * &q);
+        let n = Mpz::from(&pq * 24u32);
+        assert_eq!(
+            factor_with_limit(&n, Duration::ZERO),
+            (vec![(int(2), 3), (int(3), 1)], vec![(pq, 1)])
+        );
+        let n = int(1_000_003).pow(2) * 12u32;
+        assert_eq!(factor_with_limit(&n, Duration::MAX), (factor(&n), vec![]));
+    }
+}
@@ -0,0 +1,866 @@
+//! The self-initialising quadratic sieve
+//!
+//! The sieve looks for many `y` with `y^2 ≡ f (mod n)`, where `f` is a product of small primes, the
+//! factor base, and multiplies some of them together into a congruence of squares `X^2 ≡ Y^2`,
+//! which splits `n` when `X ≢ ±Y`. The squares are found by Gaussian elimination over GF(2) on the
+//! exponent vectors of the `f`.
+//!
+//! The `y` are `A x + B` for `x` in `-M..M`, where `A` is a product of factor base primes close to
+//! `sqrt(2 k n) / M` and `B^2 ≡ k n (mod A)`, so that `f = A * ((A x + B)^2 - k n) / A` has a small
+//! second factor. Each `A` with `s` prime factors gives `2^(s - 1)` values of `B`, and moving from
+//! one to the next only takes an addition per factor base prime (Contini's self-initialisation).
+//! The multiplier `k` is chosen by the Knuth-Schroeppel function, to make many small primes
+//! quadratic residues modulo `k n`.
+//!
+//! A value that is smooth apart from one larger prime is kept until a second one with the same
+//! large prime turns up, and the two are combined into one relation. The polynomials are sieved on
+//! every available thread.
+
+use std::collections::{HashMap, HashSet};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Mutex, PoisonError};
+use std::thread;
+
+use super::{abs, perfect_power, primes_below, trivial, Deadline};
+use crate::ctype::c_ulong;
+use crate::mpz::{mpz_divexact, mpz_divexact_ui, mpz_divisible_ui_p, mpz_fdiv_ui};
+use crate::Mpz;
+
+/// Parameters by the size of `k n` in bits: the number of primes in the factor base, the half
+/// width `M` of the sieve interval, and the multiple of the largest prime in the factor base up to
+/// which a large prime is kept. Sizes in between are interpolated.
+const PARAMS: [(u32, usize, usize, u64); 12] = [
+    (64, 100, 8_192, 30),
+    (128, 450, 16_384, 40),
+    (160, 1_000, 32_768, 40),
+    (183, 2_600, 32_768, 50),
+    (200, 4_500, 65_536, 60),
+    (212, 5_400, 65_536, 70),
+    (233, 10_000, 65_536, 80),
+    (249, 27_000, 65_536, 90),
+    (266, 50_000, 98_304, 100),
+    (298, 60_000, 131_072, 100),
+    (332, 100_000, 196_608, 100),
+    (366, 150_000, 262_144, 120),
+];
+
+/// Candidate multipliers for the Knuth-Schroeppel function: the squarefree odd numbers below 75.
+const MULTIPLIERS: [u64; 31] = [
+    1, 3, 5, 7, 11, 13, 15, 17, 19, 21, 23, 29, 31, 33, 35, 37, 39, 41, 43, 47, 51, 53, 55, 57, 59,
+    61, 65, 67, 69, 71, 73,
+];
+
+/// Factor base primes below this are not sieved with, but tried on every candidate.
+const SMALL_PRIME: u32 = 40;
+
+/// How far below the size of the values the sieve threshold is set, in bits, to make up for the
+/// small primes that are not sieved with.
+const SMALL_PRIME_SLACK: f64 = 3.0;
+
+/// How many more relations than factor base primes to collect.
+const EXTRA: usize = 64;
+
+/// Marks the factor base primes that are not sieved with for the current `A`.
+const SKIP: u32 = u32::MAX;
+
+/// Find a factor of `n` with the self-initialising quadratic sieve.
+///
+/// Returns `None` if `n` is prime. The time taken depends only on the size of `n`: on one core,
+/// about half a second for 50 digits, 7 seconds for 60 and a minute for 70. Numbers of 90 digits or
+/// more take hours, and are out of reach in practice: use
+/// [`factor_with_limit`](super::factor_with_limit) to bound the time spent on them. The sieve is
+/// best kept for numbers with no factor small enough for [`ecm`](super::ecm).
+pub fn quadratic_sieve(n: &Mpz) -> Option<Mpz> {
+    quadratic_sieve_until(n, Deadline(None))
+}
+
+/// [`quadratic_sieve`], giving up with `None` once `deadline` has passed.
+pub(super) fn quadratic_sieve_until(n: &Mpz, deadline: Deadline) -> Option<Mpz> {
+    if let Some(d) = trivial(n) {
+        return d;
+    }
+    let n = &abs(n);
+    if n.is_probably_prime(0) {
+        return None;
+    }
+    if let Some((root, _)) = perfect_power(n) {
+        return Some(root);
+    }
+    if let Ok(small) = u64::try_from(n) {
+        return Some(Mpz::from(rho(small)));
+    }
+    let ctx = match Context::new(n, deadline) {
+        Ok(ctx) => ctx,
+        Err(p) => return Some(Mpz::from(p)),
+    };
+
+    let shared = Mutex::new(Relations::default());
+    let threads = thread::available_parallelism().map_or(1, |t| t.get());
+    let mut needed = ctx.primes.len() + EXTRA;
+    for round in 0.. {
+        let stop = AtomicBool::new(false);
+        thread::scope(|scope| {
+            for t in 0..threads {
+                let (ctx, shared, stop) = (&ctx, &shared, &stop);
+                let seed = (round * threads + t) as u64;
+                scope.spawn(move || ctx.sieve(shared, stop, needed, seed));
+            }
+        });
+        let relations = shared.lock().unwrap_or_else(PoisonError::into_inner);
+        if ctx.deadline.expired() || relations.exhausted {
+            return None;
+        }
+        if let Some(d) = ctx.combine(&relations.full) {
+            return Some(d);
+        }
+        needed += EXTRA;
+    }
+    unreachable!()
+}
+
+/// `y` with `y^2 ≡ large^2 * Π factors (mod n)`.
+struct Relation {
+    y: Mpz,
+    /// The prime factors with multiplicity, as columns of the matrix: 0 for -1, and `i + 1` for
+    /// the factor base prime `i`.
+    factors: Vec<u32>,
+    /// The large prime of two combined partial relations, or 1.
+    large: u64,
+}
+
+/// The relations found so far, shared between the sieving threads.
+#[derive(Default)]
+struct Relations {
+    full: Vec<Relation>,
+    /// Relations with a large prime, by that prime, and with `large` set to 1.
+    partial: HashMap<u64, Relation>,
+    /// Every `y` seen, so that no relation is counted twice.
+    seen: HashSet<Mpz>,
+    /// The sets of factor base indices already used for `A`.
+    used: HashSet<Vec<usize>>,
+    /// Set when no unused `A` could be found.
+    exhausted: bool,
+}
+
+impl Relations {
+    fn add(&mut self, mut rel: Relation) {
+        if !self.seen.insert(rel.y.clone()) {
+            return;
+        }
+        if rel.large == 1 {
+            self.full.push(rel);
+            return;
+        }
+        let large = std::mem::replace(&mut rel.large, 1);
+        match self.partial.get(&large) {
+            Some(other) => {
+                rel.y *= &other.y;
+                rel.factors.extend_from_slice(&other.factors);
+                rel.large = large;
+                self.full.push(rel);
+            }
+            None => {
+                self.partial.insert(large, rel);
+            }
+        }
+    }
+}
+
+/// Everything the sieving threads share.
+struct Context<'a> {
+    n: &'a Mpz,
+    kn: Mpz,
+    primes: Vec<u32>,
+    /// A square root of `k n` modulo each prime, which is 0 for 2 and the primes dividing `k`.
+    roots: Vec<u32>,
+    /// The scaled base 2 logarithm of each prime.
+    logs: Vec<u8>,
+    /// Index of the first prime that is sieved with.
+    first_sieved: usize,
+    m: usize,
+    /// The starting value of each sieve entry, so that an entry reaching 128 marks a candidate.
+    init: u8,
+    large_bound: u64,
+    /// The number of prime factors of `A`, and the range of indices the first `s - 1` are picked
+    /// from.
+    s: usize,
+    window: (usize, usize),
+    /// The base 2 logarithm of the ideal `A`.
+    a_bits: f64,
+    deadline: Deadline,
+}
+
+impl<'a> Context<'a> {
+    /// Choose the multiplier and parameters and build the factor base for `n`, which is odd, not a
+    /// perfect power and above 2^64, or return a small prime factor of `n` found on the way.
+    fn new(n: &'a Mpz, deadline: Deadline) -> Result<Self, u32> {
+        let small = primes_below(1000);
+        if let Some(&p) = small.iter().find(|&&p| fdiv_ui(n, p as u32) == 0) {
+            return Err(p as u32);
+        }
+        let k = multiplier(n, &small);
+        let kn = Mpz::from(n * k);
+        let kn_bits = log2(&kn);
+        let (size, m, large_mult) = params(kn_bits);
+
+        let mut primes = vec![2];
+        let mut roots = vec![0];
+        let mut limit = (size as f64 * (size as f64).ln() * 3.0) as u64 + 1000;
+        while primes.len() < size {
+            primes.truncate(1);
+            roots.truncate(1);
+            for p in primes_below(limit).into_iter().skip(1) {
+                let p = p as u32;
+                if fdiv_ui(n, p) == 0 {
+                    return Err(p);
+                }
+                let r = fdiv_ui(&kn, p);
+                if r == 0 || legendre(r, p) == 1 {
+                    primes.push(p);
+                    roots.push(sqrt_mod(r, p));
+                    if primes.len() == size {
+                        break;
+                    }
+                }
+            }
+            limit *= 2;
+        }
+
+        let large_bound = u64::from(primes[size - 1]) * large_mult;
+        let threshold = log2_usize(m) + kn_bits / 2.0 - 0.5 - (large_bound as f64).log2();
+        let threshold = threshold - SMALL_PRIME_SLACK;
+        let scale = (100.0 / threshold).min(1.0);
+        let logs = primes
+            .iter()
+            .map(|&p| ((p as f64).log2() * scale).round().max(1.0) as u8)
+            .collect();
+        let first_sieved = primes.partition_point(|&p| p < SMALL_PRIME).max(1);
+
+        // A should be sqrt(2 k n) / M. Its primes are picked from around a size that gives plenty
+        // of polynomials for each A without them being too large to sieve with cheaply.
+        let a_bits = (kn_bits + 1.0) / 2.0 - log2_usize(m);
+        let cap = (primes[size * 3 / 4] as f64).min(4000.0).log2();
+        let s = ((a_bits / cap).ceil() as usize).max(1);
+        let ideal = (a_bits / s as f64).exp2();
+        let lo = primes.partition_point(|&p| (p as f64) < ideal / 2.0);
+        let hi = primes.partition_point(|&p| (p as f64) < ideal * 2.0);
+        let width = (2 * s + 10).max(30).min(size - first_sieved);
+        let lo = lo.max(first_sieved).min(size - width);
+        let hi = hi.max(lo + width).min(size);
+
+        Ok(Context {
+            n,
+            kn,
+            primes,
+            roots,
+            logs,
+            first_sieved,
+            m,
+            init: 128 - (threshold * scale).round() as u8,
+            large_bound,
+            s,
+            window: (lo, hi),
+            a_bits,
+            deadline,
+        })
+    }
+
+    /// Sieve polynomials until `needed` relations have been found between all threads, or `stop` is
+    /// set.
+    fn sieve(&self, shared: &Mutex<Relations>, stop: &AtomicBool, needed: usize, seed: u64) {
+        let f = self.primes.len();
+        let mut rng = Rng(seed.wrapping_mul(0x9e37_79b9_7f4a_7c15) | 1);
+        let mut poly = Poly {
+            b: Mpz::new(),
+            bl: Vec::new(),
+            a: Mpz::new(),
+            q: Vec::new(),
+            soln: vec![(SKIP, SKIP); f],
+            bainv: vec![0; f * self.s],
+        };
+        let mut sieve = vec![0u8; 2 * self.m];
+        let mut found = Vec::new();
+
+        while !stop.load(Ordering::Relaxed) {
+            let q = {
+                let mut shared = shared.lock().unwrap_or_else(PoisonError::into_inner);
+                let q = self.choose_a(&mut rng, &shared.used);
+                match q {
+                    Some(q) => shared.used.insert(q.clone()).then_some(q),
+                    None => {
+                        shared.exhausted = true;
+                        stop.store(true, Ordering::Relaxed);
+                        return;
+                    }
+                }
+            };
+            let Some(q) = q else { continue };
+            self.first_poly(&mut poly, q);
+            let mut signs = vec![true; self.s];
+            for i in 1u32..=1 << (self.s - 1) {
+                self.sieve_poly(&poly, &mut sieve, &mut found);
+                if !found.is_empty() {
+                    let mut shared = shared.lock().unwrap_or_else(PoisonError::into_inner);
+                    for rel in found.drain(..) {
+                        shared.add(rel);
+                    }
+                    if shared.full.len() >= needed {
+                        stop.store(true, Ordering::Relaxed);
+                    }
+                }
+                if self.deadline.expired() {
+                    stop.store(true, Ordering::Relaxed);
+                }
+                if stop.load(Ordering::Relaxed) || i == 1 << (self.s - 1) {
+                    break;
+                }
+                let v = i.trailing_zeros() as usize;
+                self.next_poly(&mut poly, v, signs[v]);
+                signs[v] = !signs[v];
+            }
+        }
+    }
+
+    /// Pick `s` factor base primes, as indices, whose product is close to the ideal `A` and has
+    /// not been used. Returns `None` if none can be found.
+    fn choose_a(&self, rng: &mut Rng, used: &HashSet<Vec<usize>>) -> Option<Vec<usize>> {
+        let (lo, hi) = self.window;
+        let usable = |i: usize| self.roots[i] != 0;
+        for attempt in 0..1000 {
+            let mut q = Vec::with_capacity(self.s);
+            let mut bits = 0.0;
+            // After many collisions the last prime is picked at random too.
+            let random = if attempt < 100 { self.s - 1 } else { self.s };
+            while q.len() < random {
+                let i = lo + rng.below(hi - lo);
+                if usable(i) && !q.contains(&i) {
+                    q.push(i);
+                    bits += (self.primes[i] as f64).log2();
+                }
+            }
+            if q.len() < self.s {
+                let want = (self.a_bits - bits).exp2();
+                let at = self.primes.partition_point(|&p| (p as f64) < want);
+                let nearest = (0..self.primes.len())
+                    .flat_map(|d| [at.checked_sub(d + 1), Some(at + d)])
+                    .flatten()
+                    .filter(|&i| i >= self.first_sieved && i < self.primes.len())
+                    .find(|&i| usable(i) && !q.contains(&i));
+                q.push(nearest?);
+            }
+            q.sort_unstable();
+            if !used.contains(&q) {
+                return Some(q);
+            }
+        }
+        None
+    }
+
+    /// Set up `poly` for the first `B` of the `A` with factors `q`.
+    fn first_poly(&self, poly: &mut Poly, q: Vec<usize>) {
+        let mut a = Mpz::from(1);
+        for &i in &q {
+            a *= self.primes[i];
+        }
+        poly.bl.clear();
+        for &i in &q {
+            let p = self.primes[i];
+            let mut rest = Mpz::new();
+            unsafe { mpz_divexact_ui(rest.as_raw_mut(), a.as_raw(), p as c_ulong) };
+            let inv = inverse(fdiv_ui(&rest, p), p);
+            let mut gamma = mul_mod(self.roots[i], inv, p);
+            if gamma > p / 2 {
+                gamma = p - gamma;
+            }
+            poly.bl.push(rest * gamma);
+        }
+        let mut b = Mpz::new();
+        for bl in &poly.bl {
+            b += bl;
+        }
+
+        for i in 0..self.primes.len() {
+            let p = self.primes[i];
+            if i < self.first_sieved || self.roots[i] == 0 || q.contains(&i) {
+                poly.soln[i] = (SKIP, SKIP);
+                continue;
+            }
+            let ainv = inverse(fdiv_ui(&a, p), p);
+            let b = fdiv_ui(&b, p);
+            let t = self.roots[i];
+            let m = (self.m as u64 % p as u64) as u32;
+            let root = |t: u32| (mul_mod((t + p - b) % p, ainv, p) + m) % p;
+            poly.soln[i] = (root(t), root(p - t));
+            for (l, bl) in poly.bl.iter().enumerate() {
+                poly.bainv[l * self.primes.len() + i] = mul_mod(2 * fdiv_ui(bl, p) % p, ainv, p);
+            }
+        }
+        poly.a = a;
+        poly.b = b;
+        poly.q = q;
+    }
+
+    /// Move `poly` to the next `B` in Gray code order, by flipping the sign of `B_v`, which is
+    /// positive in `B` if `plus`.
+    fn next_poly(&self, poly: &mut Poly, v: usize, plus: bool) {
+        let f = self.primes.len();
+        let bainv = &poly.bainv[v * f..(v + 1) * f];
+        if plus {
+            poly.b -= Mpz::from(&poly.bl[v] * 2u32);
+        } else {
+            poly.b += Mpz::from(&poly.bl[v] * 2u32);
+        }
+        for ((soln, &d), &p) in poly.soln.iter_mut().zip(bainv).zip(&self.primes) {
+            if soln.0 == SKIP {
+                continue;
+            }
+            let d = if plus { d } else { p - d };
+            *soln = ((soln.0 + d) % p, (soln.1 + d) % p);
+        }
+    }
+
+    /// Sieve the interval for `poly`, and push the relations found to `found`.
+    fn sieve_poly(&self, poly: &Poly, sieve: &mut [u8], found: &mut Vec<Relation>) {
+        sieve.fill(self.init);
+        for i in self.first_sieved..self.primes.len() {
+            let (r1, r2) = poly.soln[i];
+            if r1 == SKIP {
+                continue;
+            }
+            let (p, log) = (self.primes[i] as usize, self.logs[i]);
+            for r in [r1 as usize, r2 as usize] {
+                if r < sieve.len() {
+                    for e in sieve[r..].iter_mut().step_by(p) {
+                        *e = e.wrapping_add(log);
+                    }
+                }
+            }
+        }
+        for (w, chunk) in sieve.chunks_exact(8).enumerate() {
+            let word = u64::from_le_bytes(chunk.try_into().unwrap());
+            if word & 0x8080_8080_8080_8080 == 0 {
+                continue;
+            }
+            for (j, &e) in chunk.iter().enumerate() {
+                if e & 0x80 != 0 {
+                    found.extend(self.check(poly, w * 8 + j));
+                }
+            }
+        }
+    }
+
+    /// The relation at position `j` of the sieve for `poly`, if the value there factors over the
+    /// factor base with at most one large prime.
+    fn check(&self, poly: &Poly, j: usize) -> Option<Relation> {
+        let x = j as i64 - self.m as i64;
+        let y = Mpz::from(&poly.a * x) + &poly.b;
+        // g = ((A x + B)^2 - k n) / A, and A g = (A x + B)^2 - k n.
+        let mut g = Mpz::from(&y * &y) - &self.kn;
+        unsafe { mpz_divexact(g.as_raw_mut(), g.as_raw(), poly.a.as_raw()) };
+
+        let mut factors: Vec<u32> = poly.q.iter().map(|&i| i as u32 + 1).collect();
+        if g < 0 {
+            factors.push(0);
+            g = -g;
+        }
+        for (i, &p) in self.primes.iter().enumerate() {
+            let (r1, r2) = poly.soln[i];
+            if r1 != SKIP {
+                let r = (j % p as usize) as u32;
+                if r != r1 && r != r2 {
+                    continue;
+                }
+            }
+            while unsafe { mpz_divisible_ui_p(g.as_raw(), p as c_ulong) } != 0 {
+                unsafe { mpz_divexact_ui(g.as_raw_mut(), g.as_raw(), p as c_ulong) };
+                factors.push(i as u32 + 1);
+            }
+        }
+        let large = u64::try_from(&g).ok().filter(|&l| l < self.large_bound)?;
+        Some(Relation {
+            y: abs(&y),
+            factors,
+            large,
+        })
+    }
+
+    /// Look for a factor of `n` among the dependencies between the relations.
+    fn combine(&self, relations: &[Relation]) -> Option<Mpz> {
+        let rows: Vec<Vec<u32>> = relations
+            .iter()
+            .map(|rel| {
+                let mut odd = rel.factors.clone();
+                odd.sort_unstable();
+                let mut row = Vec::new();
+                for c in odd {
+                    if row.last() == Some(&c) {
+                        row.pop();
+                    } else {
+                        row.push(c);
+                    }
+                }
+                row
+            })
+            .collect();
+
+        let n = self.n;
+        for dep in dependencies(&rows, self.primes.len() + 1) {
+            let mut x = Mpz::from(1);
+            let mut y = Mpz::from(1);
+            let mut exps = vec![0u32; self.primes.len() + 1];
+            for &r in &dep {
+                let rel = &relations[r];
+                x = Mpz::from(&x * &rel.y) % n;
+                y = Mpz::from(&y * rel.large) % n;
+                for &c in &rel.factors {
+                    exps[c as usize] += 1;
+                }
+            }
+            for (i, &e) in exps.iter().enumerate().skip(1) {
+                if e > 0 {
+                    let p = Mpz::from(self.primes[i - 1]);
+                    y = &y * p.pow_mod_u64(u64::from(e / 2), n).unwrap() % n;
+                }
+            }
+            let d = (x - y).gcd(n);
+            if d != 1 && d != *n {
+                return Some(d);
+            }
+        }
+        None
+    }
+}
+
+/// The current polynomial `(A x + B)^2 - k n` of a sieving thread.
+struct Poly {
+    a: Mpz,
+    b: Mpz,
+    /// The `B_l`, with `B = Σ ±B_l` and `B_l ≡ 0` modulo every prime of `A` but the `l`th.
+    bl: Vec<Mpz>,
+    /// The factor base indices of the primes of `A`.
+    q: Vec<usize>,
+    /// The positions in the sieve of the roots modulo each prime, or `SKIP`.
+    soln: Vec<(u32, u32)>,
+    /// `2 B_l / A` modulo each prime, for each `l`, by which the roots move when `B_l` changes
+    /// sign.
+    bainv: Vec<u32>,
+}
+
+/// Subsets of `rows`, as indices, in which every column appears an even number of times.
+///
+/// Each row lists the columns, below `columns`, in which it has a 1.
+fn dependencies(rows: &[Vec<u32>], columns: usize) -> Vec<Vec<usize>> {
+    // Rows with a column that no other row has can't be part of a dependency.
+    let mut weight = vec![0u32; columns];
+    for row in rows {
+        for &c in row {
+            weight[c as usize] += 1;
+        }
+    }
+    let mut alive = vec![true; rows.len()];
+    let mut changed = true;
+    while changed {
+        changed = false;
+        for (r, row) in rows.iter().enumerate() {
+            if alive[r] && row.iter().any(|&c| weight[c as usize] == 1) {
+                alive[r] = false;
+                changed = true;
+                for &c in row {
+                    weight[c as usize] -= 1;
+                }
+            }
+        }
+    }
+    let kept: Vec<usize> = (0..rows.len()).filter(|&r| alive[r]).collect();
+    let mut index = vec![usize::MAX; columns];
+    let mut used = 0;
+    for (c, &w) in weight.iter().enumerate() {
+        if w > 0 {
+            index[c] = used;
+            used += 1;
+        }
+    }
+
+    // The matrix is stored by column, with a bit for each kept row, and reduced to row echelon
+    // form on the columns.
+    let words = kept.len().div_ceil(64);
+    let mut matrix = vec![0u64; used * words];
+    for (k, &r) in kept.iter().enumerate() {
+        for &c in &rows[r] {
+            matrix[index[c as usize] * words + k / 64] |= 1 << (k % 64);
+        }
+    }
+    let mut pivots = Vec::new();
+    let mut is_pivot = vec![false; kept.len()];
+    let mut pivot_row = vec![0u64; words];
+    for c in 0..used {
+        let column = &matrix[c * words..(c + 1) * words];
+        let Some(w) = column.iter().position(|&word| word != 0) else {
+            continue;
+        };
+        let k = w * 64 + column[w].trailing_zeros() as usize;
+        is_pivot[k] = true;
+        pivots.push((c, k));
+        pivot_row.copy_from_slice(column);
+        for other in (0..used).filter(|&o| o != c) {
+            let column = &mut matrix[other * words..(other + 1) * words];
+            if column[w] >> (k % 64) & 1 == 1 {
+                for (a, b) in column[w..].iter_mut().zip(&pivot_row[w..]) {
+                    *a ^= b;
+                }
+            }
+        }
+    }
+
+    // Each row that is not a pivot gives a dependency with the pivots of the columns it is in.
+    (0..kept.len())
+        .filter(|&k| !is_pivot[k])
+        .take(64)
+        .map(|k| {
+            let mut dep = vec![kept[k]];
+            for &(c, pivot) in &pivots {
+                if matrix[c * words + k / 64] >> (k % 64) & 1 == 1 {
+                    dep.push(kept[pivot]);
+                }
+            }
+            dep
+        })
+        .collect()
+}
+
+/// The Knuth-Schroeppel multiplier for `n`, which has no factor in `primes`.
+fn multiplier(n: &Mpz, primes: &[u64]) -> u64 {
+    let n8 = fdiv_ui(n, 8) as u64;
+    let residues: Vec<u32> = primes[1..].iter().map(|&p| fdiv_ui(n, p as u32)).collect();
+    let score = |k: u64| {
+        let ln2 = std::f64::consts::LN_2;
+        let mut score = -0.5 * (k as f64).ln();
+        score += match k * n8 % 8 {
+            1 => 2.0 * ln2,
+            5 => ln2,
+            _ => 0.5 * ln2,
+        };
+        for (&p, &r) in primes[1..].iter().zip(&residues) {
+            let kn = (k % p * u64::from(r) % p) as u32;
+            let ln = (p as f64).ln();
+            if kn == 0 {
+                score += ln / p as f64;
+            } else if legendre(kn, p as u32) == 1 {
+                score += 2.0 * ln / (p - 1) as f64;
+            }
+        }
+        score
+    };
+    MULTIPLIERS
+        .into_iter()
+        .map(|k| (k, score(k)))
+        .max_by(|a, b| a.1.total_cmp(&b.1))
+        .unwrap()
+        .0
+}
+
+/// The factor base size, half width of the sieve, and large prime multiple for `k n` of `bits`
+/// bits.
+fn params(bits: f64) -> (usize, usize, u64) {
+    let (first, last) = (PARAMS[0], PARAMS[PARAMS.len() - 1]);
+    if bits <= first.0 as f64 {
+        return (first.1, first.2, first.3);
+    }
+    let Some(i) = PARAMS.iter().position(|p| p.0 as f64 >= bits) else {
+        return (last.1, last.2, last.3);
+    };
+    let (lo, hi) = (PARAMS[i - 1], PARAMS[i]);
+    let t = (bits - lo.0 as f64) / (hi.0 - lo.0) as f64;
+    let lerp = |a: f64, b: f64| a + (b - a) * t;
+    let m = lerp(lo.2 as f64, hi.2 as f64) as usize;
+    (
+        lerp(lo.1 as f64, hi.1 as f64) as usize,
+        // A multiple of 64, so that the sieve can be scanned a word at a time.
+        m.next_multiple_of(64),
+        lerp(lo.3 as f64, hi.3 as f64) as u64,
+    )
+}
+
+/// A factor of `n`, which is odd and composite, by Pollard's rho method in machine words.
+fn rho(n: u64) -> u64 {
+    let mul = |a: u64, b: u64| (a as u128 * b as u128 % n as u128) as u64;
+    for c in 1.. {
+        let step = |x: u64| (mul(x, x) + c) % n;
+        let (mut x, mut y, mut d) = (2, 2, 1);
+        while d == 1 {
+            x = step(x);
+            y = step(step(y));
+            d = gcd(x.abs_diff(y), n);
+        }
+        if d != n {
+            return d;
+        }
+    }
+    unreachable!()
+}
+
+fn gcd(mut a: u64, mut b: u64) -> u64 {
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+    a
+}
+
+fn fdiv_ui(n: &Mpz, p: u32) -> u32 {
+    unsafe { mpz_fdiv_ui(n.as_raw(), p as c_ulong) as u32 }
+}
+
+fn log2(n: &Mpz) -> f64 {
+    let (m, e) = n.to_f64_exp();
+    m.log2() + e as f64
+}
+
+fn log2_usize(m: usize) -> f64 {
+    (m as f64).log2()
+}
+
+fn mul_mod(a: u32, b: u32, p: u32) -> u32 {
+    (u64::from(a) * u64::from(b) % u64::from(p)) as u32
+}
+
+fn pow_mod(a: u32, mut e: u32, p: u32) -> u32 {
+    let (mut a, mut r) = (a % p, 1 % p);
+    while e > 0 {
+        if e & 1 == 1 {
+            r = mul_mod(r, a, p);
+        }
+        a = mul_mod(a, a, p);
+        e >>= 1;
+    }
+    r
+}
+
+/// The inverse of `a` modulo the prime `p`, which does not divide `a`.
+fn inverse(a: u32, p: u32) -> u32 {
+    let (mut r0, mut r1) = (i64::from(p), i64::from(a % p));
+    let (mut t0, mut t1) = (0i64, 1i64);
+    while r1 != 0 {
+        let q = r0 / r1;
+        (r0, r1) = (r1, r0 - q * r1);
+        (t0, t1) = (t1, t0 - q * t1);
+    }
+    t0.rem_euclid(i64::from(p)) as u32
+}
+
+/// 1 if `a` is a nonzero square modulo the odd prime `p`, `p - 1` if it is not, and 0 if `p`
+/// divides it.
+fn legendre(a: u32, p: u32) -> u32 {
+    pow_mod(a, (p - 1) / 2, p)
+}
+
+/// A square root of `a` modulo the prime `p`, by the Tonelli-Shanks algorithm. `a` must be a square.
+fn sqrt_mod(a: u32, p: u32) -> u32 {
+    let a = a % p;
+    if a == 0 || p == 2 {
+        return a;
+    }
+    let (mut q, mut s) = (p - 1, 0);
+    while q % 2 == 0 {
+        q /= 2;
+        s += 1;
+    }
+    let z = (2..p).find(|&z| legendre(z, p) == p - 1).unwrap();
+    let (mut m, mut c, mut t, mut r) = (
+        s,
+        pow_mod(z, q, p),
+        pow_mod(a, q, p),
+        pow_mod(a, q.div_ceil(2), p),
+    );
+    while t != 1 {
+        let mut i = 0;
+        let mut t2 = t;
+        while t2 != 1 {
+            t2 = mul_mod(t2, t2, p);
+            i += 1;
+        }
+        let b = pow_mod(c, 1 << (m - i - 1), p);
+        m = i;
+        c = mul_mod(b, b, p);
+        t = mul_mod(t, c, p);
+        r = mul_mod(r, b, p);
+    }
+    r
+}
+
+/// xorshift64*, which is plenty for picking primes for `A`.
+struct Rng(u64);
+
+impl Rng {
+    fn below(&mut self, n: usize) -> usize {
+        self.0 ^= self.0 >> 12;
+        self.0 ^= self.0 << 25;
+        self.0 ^= self.0 >> 27;
+        (self.0.wrapping_mul(0x2545_f491_4f6c_dd1d) >> 32) as usize % n
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn modular() {
+        for p in [3, 5, 7, 13, 17, 97, 65537, 4294967291] {
+            for a in [1, 2, 3, 10, 12345] {
+                let a = a % p;
+                if a == 0 {
+                    continue;
+                }
+                assert_eq!(mul_mod(inverse(a, p), a, p), 1);
+                if legendre(a, p) == 1 {
+                    let r = sqrt_mod(a, p);
+                    assert_eq!(mul_mod(r, r, p), a, "sqrt({}) mod {}", a, p);
+                }
+            }
+        }
+        assert_eq!(rho(1_000_003 * 999_983) % 999_983, 0);
+    }
+
+    #[test]
+    fn matrix() {
+        // Rows 0 + 1 + 2 and 3 + 4 are even; row 5 has a column of its own.
+        let rows = [
+            vec![0, 1],
+            vec![1, 2],
+            vec![0, 2],
+            vec![3],
+            vec![3],
+            vec![2, 4],
+        ];
+        let mut deps: Vec<Vec<usize>> = dependencies(&rows, 5)
+            .into_iter()
+            .map(|mut d| {
+                d.sort_unstable();
+                d
+            })
+            .collect();
+        deps.sort();
+        assert_eq!(deps, [vec![0, 1, 2], vec![3, 4]]);
+    }
+
+    #[test]
+    fn semiprimes() {
+        let p = Mpz::from(1_000_000_000_039u64);
+        let q = Mpz::from(1_000_000_000_000_000_003u64);
+        for (p, q) in [(&p, &q), (&q, &q), (&Mpz::from(3u32), &q)] {
+            let n = Mpz::from(p * q);
+            let d = quadratic_sieve(&n).unwrap();
+            assert!(d == *p || d == *q, "{} from {}", d, n);
+        }
+        // 2^128 + 1 = 59649589127497217 * 5704689200685129054721
+        let n = (Mpz::from(1) << 128u32) + 1u32;
+        let d = quadratic_sieve(&n).unwrap();
+        assert!(
+            d == 59649589127497217u64 || d == 5704689200685129054721u128,
+            "{}",
+            d
+        );
+        assert_eq!(quadratic_sieve(&q), None);
+        assert_eq!(quadratic_sieve(&Mpz::from(91)), Some(Mpz::from(7)));
+    }
+}
@@ -16,14 +16,53 @@
 //!
 //! ---
 //!
+//! ## Linking
+//!
+//! The build script looks for MPIR in this order:
+//!
+//! 1. `MPIR_LIB_DIR`, a directory containing `libmpir`.
+//! 2. `pkg-config`, unless `MPIR_NO_PKG_CONFIG` is set.
+//! 3. The default library paths, e.g. `/usr/local/lib` and `/usr/lib`.
+//! 4. On Windows, the prebuilt libraries in `lib/win`.
+//!
+//! Set `MPIR_STATIC=1` to link the static archive, or `MPIR_STATIC=0` to link the shared library.
+//! By default the shared library is preferred.
+//!
+//! ```sh
+//! MPIR_LIB_DIR=/opt/mpir/lib MPIR_STATIC=1 cargo build
+//! ```
+//!
+//! The `bundled` feature builds MPIR from source instead, which needs a C compiler, `make` and a
+//! POSIX shell. The MPIR 3.0.0 tarball is not checked in; download it into `vendor/` first, as
+//! `vendor/README.md` describes. The `fat` feature builds it for any CPU of the target architecture,
+//! rather than tuning it for the build machine.
+//!
+//! With the `backend-gmp` feature GMP is linked instead of MPIR, and the `MPIR_*` variables apply to
+//! it. The safe API is the same for both; only the raw bindings to functions that GMP does not
+//! provide are missing, such as `mpir_version`, `mpz_nthroot` and `mpz_next_prime_candidate`.
+//!
+//! For secrets such as private keys, the `zeroize` feature adds `SecretMpz`, which wipes its limbs
+//! when dropped, and makes MPIR wipe every block of memory before freeing it.
+//!
+//! The `rand` feature draws uniform integers from any `rand::RngCore`, such as `OsRng` for
+//! cryptographic use, with `Mpz::random_below_with` and `rng.gen_range(a..b)`, and turns one into a
+//! `RandState` with `RandState::from_rng`.
+//!
+//! The safe API returns `MpirError`, or panics, for the operations MPIR would crash on, such as
+//! dividing by zero. Running out of memory is the exception: MPIR cannot recover from a failed
+//! allocation, so it aborts the process, as the standard collections do. Code handling untrusted
+//! input should bound the sizes it asks for, such as exponents and shift counts, before calling.
+//!
+//! ---
+//!
 //! ## Modules
 //!
 //! | Module | Title                  | Prefix | FFI  | Wrapper |
 //! |--------|------------------------|--------|------|---------|
 //! | mpz    | Integer Numbers        | `mpz_` | WIP  | WIP     |
-//! | mpf    | Floating-point Numbers | `mpf_` | TODO | TODO    |
-//! | mpq    | Rational Numbers       | `mpq_` | TODO | TODO    |
-//! | gmp    | Random Numbers         | `gmp_` | TODO | TODO    |
+//! | mpf    | Floating-point Numbers | `mpf_` | Done | Done    |
+//! | mpq    | Rational Numbers       | `mpq_` | Done | Done    |
+//! | gmp    | Random Numbers         | `gmp_` | Done | Done    |
 //! | mpn    | Low-level Functions    | `mpn_` | TODO | TODO    |
 //!
 //!
@@ -33,9 +72,28 @@
 //! - [MPIR 3.0.0 - C documentation](https://mpir.org/mpir-3.0.0.pdf)
 extern crate core;
 
+#[cfg(not(any(feature = "backend-mpir", feature = "backend-gmp")))]
+compile_error!("one of the `backend-mpir` or `backend-gmp` features must be enabled");
+
+pub mod alloc;
+pub mod assign;
 pub mod ctype;
+pub mod error;
+pub mod factor;
+pub mod mpf;
+pub mod mpq;
 pub mod mpz;
+pub mod rand;
+pub mod round;
 pub mod sign;
 
+pub use assign::Assign;
+pub use error::MpirError;
+pub use mpf::Mpf;
+pub use mpq::Mpq;
 pub use mpz::Mpz;
+#[cfg(feature = "zeroize")]
+pub use mpz::SecretMpz;
+pub use rand::RandState;
+pub use round::Round;
 pub use sign::Sign;
@@ -0,0 +1,771 @@
+//! Floating-point Number
+//!
+//! [MPIR 3.0.0 - C documentation](https://mpir.org/mpir-3.0.0.pdf)
+//!
+//! A floating-point number is a mantissa of whole limbs and an exponent, with a precision chosen
+//! when it is initialised. The precision is the minimum number of bits kept in the mantissa: MPIR
+//! rounds it up to a whole number of limbs, and may use one more limb than that during a
+//! calculation. Results are truncated rather than correctly rounded, and the last few bits of a
+//! result can differ between machines, so these are not a replacement for MPFR where exact
+//! rounding matters.
+//!
+//! Every [`Mpf`] carries its own precision. Arithmetic takes the precision of the left operand, so
+//! `Mpf::from(&a / &b)` has the precision of `a`, while assigning an expression to an existing
+//! `Mpf`, as in `x.assign(&a / &b)`, evaluates it at the precision of `x` instead.
+
+use std::mem::MaybeUninit;
+
+use crate::ctype::{
+    c_char, c_double, c_int, c_long, c_ulong, gmp_randstate_ptr, mp_bitcnt_t, mp_exp_t, mpf_ptr,
+    mpf_srcptr, mpf_struct, mpq_srcptr, mpz_srcptr, size_t,
+};
+use crate::{Assign, MpirError, Sign};
+
+mod cmp;
+mod convert;
+mod fmt;
+mod incomplete;
+mod ops;
+
+pub use fmt::{ParseMpfError, ParseMpfErrorKind};
+pub use incomplete::{AddIncomplete, DivIncomplete, MulIncomplete, SubIncomplete};
+
+/* As for mpz, the symbols are named with link_name, and the bindings use the C ABI. */
+extern "C" {
+
+    // ---------------------------------------------------------------------------------------------
+    // Initialization Functions
+
+    /// Set the default precision to be at least prec bits. All subsequent calls to mpf_init will
+    /// use this precision, but previously initialized variables are unaffected.
+    #[link_name = "__gmpf_set_default_prec"]
+    pub fn mpf_set_default_prec(prec: mp_bitcnt_t);
+
+    /// Return the default precision actually used.
+    #[link_name = "__gmpf_get_default_prec"]
+    pub fn mpf_get_default_prec() -> mp_bitcnt_t;
+
+    /// Initialize x to 0. Normally, a variable should be initialized once only or at least be
+    /// cleared, using mpf_clear, between initializations. The precision of x is undefined unless a
+    /// default precision has already been established by a call to mpf_set_default_prec.
+    #[link_name = "__gmpf_init"]
+    pub fn mpf_init(x: mpf_ptr);
+
+    /// Initialize x to 0 and set its precision to be at least prec bits. Normally, a variable
+    /// should be initialized once only or at least be cleared, using mpf_clear, between
+    /// initializations.
+    #[link_name = "__gmpf_init2"]
+    pub fn mpf_init2(x: mpf_ptr, prec: mp_bitcnt_t);
+
+    /// Free the space occupied by x. Make sure to call this function for all mpf_t variables when
+    /// you are done with them.
+    #[link_name = "__gmpf_clear"]
+    pub fn mpf_clear(x: mpf_ptr);
+
+    /// Return the current precision of op, in bits.
+    #[link_name = "__gmpf_get_prec"]
+    pub fn mpf_get_prec(op: mpf_srcptr) -> mp_bitcnt_t;
+
+    /// Set the precision of rop to be at least prec bits. The value in rop will be truncated to
+    /// the new precision.
+    ///
+    /// This function requires a call to realloc, and so should not be used in a tight loop.
+    #[link_name = "__gmpf_set_prec"]
+    pub fn mpf_set_prec(rop: mpf_ptr, prec: mp_bitcnt_t);
+
+    /// Set the precision of rop to be at least prec bits, without changing the memory allocated.
+    ///
+    /// prec must be no more than the allocated precision for rop, that being the precision when
+    /// rop was initialized, or in the most recent mpf_set_prec.
+    ///
+    /// The value in rop is unchanged, and in particular if it had a higher precision than prec it
+    /// will retain that higher precision. New values written to rop will use the new prec.
+    ///
+    /// Before calling mpf_clear or the full mpf_set_prec, another mpf_set_prec_raw call must be
+    /// made to restore rop to its original allocated precision. Failing to do so will have
+    /// unpredictable results.
+    #[link_name = "__gmpf_set_prec_raw"]
+    pub fn mpf_set_prec_raw(rop: mpf_ptr, prec: mp_bitcnt_t);
+
+    // ---------------------------------------------------------------------------------------------
+    // Assignment Functions
+
+    /// Set the value of rop from op.
+    #[link_name = "__gmpf_set"]
+    pub fn mpf_set(rop: mpf_ptr, op: mpf_srcptr);
+
+    /// Set the value of rop from op.
+    #[link_name = "__gmpf_set_ui"]
+    pub fn mpf_set_ui(rop: mpf_ptr, op: c_ulong);
+
+    /// Set the value of rop from op.
+    #[link_name = "__gmpf_set_si"]
+    pub fn mpf_set_si(rop: mpf_ptr, op: c_long);
+
+    /// Set the value of rop from op.
+    #[link_name = "__gmpf_set_d"]
+    pub fn mpf_set_d(rop: mpf_ptr, op: c_double);
+
+    /// Set the value of rop from op.
+    #[link_name = "__gmpf_set_z"]
+    pub fn mpf_set_z(rop: mpf_ptr, op: mpz_srcptr);
+
+    /// Set the value of rop from op.
+    #[link_name = "__gmpf_set_q"]
+    pub fn mpf_set_q(rop: mpf_ptr, op: mpq_srcptr);
+
+    /// Set the value of rop from the string in str. The string is of the form 'M@N' or, if the
+    /// base is 10 or less, alternatively 'MeN'. 'M' is the mantissa and 'N' is the exponent. The
+    /// mantissa is always in the specified base. The exponent is either in the specified base or,
+    /// if base is negative, in decimal. The decimal point expected is taken from the current
+    /// locale, on systems providing localeconv.
+    ///
+    /// The argument base may be in the ranges 2 to 62, or −62 to −2. Negative values are used to
+    /// specify that the exponent is in decimal.
+    ///
+    /// Unlike the corresponding mpz function, the base will not be determined from the leading
+    /// characters of the string if base is 0. This is so that numbers like '0.23' are not
+    /// interpreted as octal.
+    ///
+    /// White space is allowed in the string, and is simply ignored.
+    ///
+    /// This function returns 0 if the entire string is a valid number in base base. Otherwise it
+    /// returns −1.
+    #[link_name = "__gmpf_set_str"]
+    pub fn mpf_set_str(rop: mpf_ptr, s: *const c_char, base: c_int) -> c_int;
+
+    /// Swap rop1 and rop2 efficiently. Both the values and the precisions of the two variables
+    /// are swapped.
+    #[link_name = "__gmpf_swap"]
+    pub fn mpf_swap(rop1: mpf_ptr, rop2: mpf_ptr);
+
+    // ---------------------------------------------------------------------------------------------
+    // Combined Initialization and Assignment Functions
+
+    /// Initialize rop and set its value from op.
+    ///
+    /// The precision of rop will be taken from the active default precision, as set by
+    /// mpf_set_default_prec.
+    #[link_name = "__gmpf_init_set"]
+    pub fn mpf_init_set(rop: mpf_ptr, op: mpf_srcptr);
+
+    /// Initialize rop and set its value from op.
+    #[link_name = "__gmpf_init_set_ui"]
+    pub fn mpf_init_set_ui(rop: mpf_ptr, op: c_ulong);
+
+    /// Initialize rop and set its value from op.
+    #[link_name = "__gmpf_init_set_si"]
+    pub fn mpf_init_set_si(rop: mpf_ptr, op: c_long);
+
+    /// Initialize rop and set its value from op.
+    #[link_name = "__gmpf_init_set_d"]
+    pub fn mpf_init_set_d(rop: mpf_ptr, op: c_double);
+
+    /// Initialize rop and set its value from the string in str. See mpf_set_str above for details
+    /// on the assignment operation.
+    ///
+    /// Note that rop is initialized even if an error occurs. (I.e., you have to call mpf_clear for
+    /// it.)
+    #[link_name = "__gmpf_init_set_str"]
+    pub fn mpf_init_set_str(rop: mpf_ptr, s: *const c_char, base: c_int) -> c_int;
+
+    // ---------------------------------------------------------------------------------------------
+    // Conversion Functions
+
+    /// Convert op to a double, truncating if necessary (ie. rounding towards zero).
+    ///
+    /// If the exponent in op is too big or too small to fit a double then the result is system
+    /// dependent. For too big an infinity is returned when available. For too small 0.0 is
+    /// normally returned. Hardware overflow, underflow and denorm traps may or may not occur.
+    #[link_name = "__gmpf_get_d"]
+    pub fn mpf_get_d(op: mpf_srcptr) -> c_double;
+
+    /// Convert op to a double, truncating if necessary (ie. rounding towards zero), and with an
+    /// exponent returned separately.
+    ///
+    /// The return value is in the range 0.5 ≤ |d| < 1 and the exponent is stored to *exp.
+    /// d ∗ 2^exp is the (truncated) op value. If op is zero, the return is 0.0 and 0 is stored to
+    /// *exp.
+    ///
+    /// This is similar to the standard C frexp function.
+    #[link_name = "__gmpf_get_d_2exp"]
+    pub fn mpf_get_d_2exp(exp: *mut c_long, op: mpf_srcptr) -> c_double;
+
+    /// Convert op to a long, truncating any fraction part. If op is too big for the return type,
+    /// the result is undefined.
+    ///
+    /// See also mpf_fits_slong_p.
+    #[link_name = "__gmpf_get_si"]
+    pub fn mpf_get_si(op: mpf_srcptr) -> c_long;
+
+    /// Convert op to an unsigned long, truncating any fraction part. If op is too big for the
+    /// return type, the result is undefined.
+    ///
+    /// See also mpf_fits_ulong_p.
+    #[link_name = "__gmpf_get_ui"]
+    pub fn mpf_get_ui(op: mpf_srcptr) -> c_ulong;
+
+    /// Convert op to a string of digits in base base. The base argument may vary from 2 to 62 or
+    /// from −2 to −36. Up to n_digits digits will be generated. Trailing zeros are not returned.
+    /// No more digits than can be accurately represented by op are ever generated. If n_digits is
+    /// 0 then that accurate maximum number of digits are generated.
+    ///
+    /// For base in the range 2..36, digits and lower-case letters are used; for −2..−36, digits
+    /// and upper-case letters are used; for 37..62, digits, upper-case letters, and lower-case
+    /// letters (in that significance order) are used.
+    ///
+    /// If str is NULL, the result string is allocated using the current allocation function. The
+    /// block will be strlen(str)+1 bytes, that being exactly enough for the string and
+    /// null-terminator.
+    ///
+    /// If str is not NULL, it should point to a block of n_digits + 2 bytes, that being enough for
+    /// the mantissa, a possible minus sign, and a null-terminator. When n_digits is 0 to get all
+    /// significant digits, an application won't be able to know the space required, and str
+    /// should be NULL in that case.
+    ///
+    /// The generated string is a fraction, with an implicit radix point immediately to the left of
+    /// the first digit. The applicable exponent is written through the expptr pointer. For
+    /// example, the number 3.1416 would be returned as string "31416" and exponent 1.
+    ///
+    /// When op is zero, an empty string is produced and the exponent returned is 0.
+    ///
+    /// A pointer to the result string is returned, being either the allocated block or the given
+    /// str.
+    #[link_name = "__gmpf_get_str"]
+    pub fn mpf_get_str(
+        s: *mut c_char,
+        expptr: *mut mp_exp_t,
+        base: c_int,
+        n_digits: size_t,
+        op: mpf_srcptr,
+    ) -> *mut c_char;
+
+    // ---------------------------------------------------------------------------------------------
+    // Arithmetic Functions
+
+    /// Set rop to op1 + op2.
+    #[link_name = "__gmpf_add"]
+    pub fn mpf_add(rop: mpf_ptr, op1: mpf_srcptr, op2: mpf_srcptr);
+
+    /// Set rop to op1 + op2.
+    #[link_name = "__gmpf_add_ui"]
+    pub fn mpf_add_ui(rop: mpf_ptr, op1: mpf_srcptr, op2: c_ulong);
+
+    /// Set rop to op1 − op2.
+    #[link_name = "__gmpf_sub"]
+    pub fn mpf_sub(rop: mpf_ptr, op1: mpf_srcptr, op2: mpf_srcptr);
+
+    /// Set rop to op1 − op2.
+    #[link_name = "__gmpf_ui_sub"]
+    pub fn mpf_ui_sub(rop: mpf_ptr, op1: c_ulong, op2: mpf_srcptr);
+
+    /// Set rop to op1 − op2.
+    #[link_name = "__gmpf_sub_ui"]
+    pub fn mpf_sub_ui(rop: mpf_ptr, op1: mpf_srcptr, op2: c_ulong);
+
+    /// Set rop to op1 × op2.
+    #[link_name = "__gmpf_mul"]
+    pub fn mpf_mul(rop: mpf_ptr, op1: mpf_srcptr, op2: mpf_srcptr);
+
+    /// Set rop to op1 × op2.
+    #[link_name = "__gmpf_mul_ui"]
+    pub fn mpf_mul_ui(rop: mpf_ptr, op1: mpf_srcptr, op2: c_ulong);
+
+    /// Set rop to op1/op2.
+    ///
+    /// Division by zero causes an intentional division by zero.
+    #[link_name = "__gmpf_div"]
+    pub fn mpf_div(rop: mpf_ptr, op1: mpf_srcptr, op2: mpf_srcptr);
+
+    /// Set rop to op1/op2.
+    ///
+    /// Division by zero causes an intentional division by zero.
+    #[link_name = "__gmpf_ui_div"]
+    pub fn mpf_ui_div(rop: mpf_ptr, op1: c_ulong, op2: mpf_srcptr);
+
+    /// Set rop to op1/op2.
+    ///
+    /// Division by zero causes an intentional division by zero.
+    #[link_name = "__gmpf_div_ui"]
+    pub fn mpf_div_ui(rop: mpf_ptr, op1: mpf_srcptr, op2: c_ulong);
+
+    /// Set rop to the square root of op.
+    #[link_name = "__gmpf_sqrt"]
+    pub fn mpf_sqrt(rop: mpf_ptr, op: mpf_srcptr);
+
+    /// Set rop to the square root of op.
+    #[link_name = "__gmpf_sqrt_ui"]
+    pub fn mpf_sqrt_ui(rop: mpf_ptr, op: c_ulong);
+
+    /// Set rop to op1^op2.
+    #[link_name = "__gmpf_pow_ui"]
+    pub fn mpf_pow_ui(rop: mpf_ptr, op1: mpf_srcptr, op2: c_ulong);
+
+    /// Set rop to −op.
+    #[link_name = "__gmpf_neg"]
+    pub fn mpf_neg(rop: mpf_ptr, op: mpf_srcptr);
+
+    /// Set rop to the absolute value of op.
+    #[link_name = "__gmpf_abs"]
+    pub fn mpf_abs(rop: mpf_ptr, op: mpf_srcptr);
+
+    /// Set rop to op1 × 2^op2.
+    #[link_name = "__gmpf_mul_2exp"]
+    pub fn mpf_mul_2exp(rop: mpf_ptr, op1: mpf_srcptr, op2: mp_bitcnt_t);
+
+    /// Set rop to op1/2^op2.
+    #[link_name = "__gmpf_div_2exp"]
+    pub fn mpf_div_2exp(rop: mpf_ptr, op1: mpf_srcptr, op2: mp_bitcnt_t);
+
+    // ---------------------------------------------------------------------------------------------
+    // Comparison Functions
+
+    /// Compare op1 and op2. Return a positive value if op1 > op2, zero if op1 = op2, and a negative
+    /// value if op1 < op2.
+    #[link_name = "__gmpf_cmp"]
+    pub fn mpf_cmp(op1: mpf_srcptr, op2: mpf_srcptr) -> c_int;
+
+    /// Compare op1 and op2. Return a positive value if op1 > op2, zero if op1 = op2, and a negative
+    /// value if op1 < op2.
+    ///
+    /// mpf_cmp_d can be called with an infinity, but results are undefined for a NaN.
+    #[link_name = "__gmpf_cmp_d"]
+    pub fn mpf_cmp_d(op1: mpf_srcptr, op2: c_double) -> c_int;
+
+    /// Compare op1 and op2. Return a positive value if op1 > op2, zero if op1 = op2, and a negative
+    /// value if op1 < op2.
+    #[link_name = "__gmpf_cmp_ui"]
+    pub fn mpf_cmp_ui(op1: mpf_srcptr, op2: c_ulong) -> c_int;
+
+    /// Compare op1 and op2. Return a positive value if op1 > op2, zero if op1 = op2, and a negative
+    /// value if op1 < op2.
+    #[link_name = "__gmpf_cmp_si"]
+    pub fn mpf_cmp_si(op1: mpf_srcptr, op2: c_long) -> c_int;
+
+    /// Return non-zero if the first op3 bits of op1 and op2 are equal, zero otherwise. I.e., test
+    /// if op1 and op2 are approximately equal.
+    ///
+    /// Caution: Currently only bit complete limbs are compared, and only the mantissas, so numbers
+    /// of different signs or exponents are never equal by this function.
+    #[link_name = "__gmpf_eq"]
+    pub fn mpf_eq(op1: mpf_srcptr, op2: mpf_srcptr, op3: mp_bitcnt_t) -> c_int;
+
+    /// Compute the relative difference between op1 and op2 and store the result in rop. This is
+    /// |op1 − op2|/op1.
+    #[link_name = "__gmpf_reldiff"]
+    pub fn mpf_reldiff(rop: mpf_ptr, op1: mpf_srcptr, op2: mpf_srcptr);
+
+    // mpf_sgn is implemented in Rust, see below.
+
+    // ---------------------------------------------------------------------------------------------
+    // Miscellaneous Functions
+
+    /// Set rop to op rounded to the next higher integer.
+    #[link_name = "__gmpf_ceil"]
+    pub fn mpf_ceil(rop: mpf_ptr, op: mpf_srcptr);
+
+    /// Set rop to op rounded to the next lower integer.
+    #[link_name = "__gmpf_floor"]
+    pub fn mpf_floor(rop: mpf_ptr, op: mpf_srcptr);
+
+    /// Set rop to op rounded to the integer towards zero.
+    #[link_name = "__gmpf_trunc"]
+    pub fn mpf_trunc(rop: mpf_ptr, op: mpf_srcptr);
+
+    /// Return non-zero if op is an integer.
+    #[link_name = "__gmpf_integer_p"]
+    pub fn mpf_integer_p(op: mpf_srcptr) -> c_int;
+
+    /// Return non-zero if op would fit in an unsigned long, when truncated to an integer.
+    #[link_name = "__gmpf_fits_ulong_p"]
+    pub fn mpf_fits_ulong_p(op: mpf_srcptr) -> c_int;
+
+    /// Return non-zero if op would fit in a long, when truncated to an integer.
+    #[link_name = "__gmpf_fits_slong_p"]
+    pub fn mpf_fits_slong_p(op: mpf_srcptr) -> c_int;
+
+    /// Return non-zero if op would fit in an unsigned int, when truncated to an integer.
+    #[link_name = "__gmpf_fits_uint_p"]
+    pub fn mpf_fits_uint_p(op: mpf_srcptr) -> c_int;
+
+    /// Return non-zero if op would fit in an int, when truncated to an integer.
+    #[link_name = "__gmpf_fits_sint_p"]
+    pub fn mpf_fits_sint_p(op: mpf_srcptr) -> c_int;
+
+    /// Return non-zero if op would fit in an unsigned short, when truncated to an integer.
+    #[link_name = "__gmpf_fits_ushort_p"]
+    pub fn mpf_fits_ushort_p(op: mpf_srcptr) -> c_int;
+
+    /// Return non-zero if op would fit in a short, when truncated to an integer.
+    #[link_name = "__gmpf_fits_sshort_p"]
+    pub fn mpf_fits_sshort_p(op: mpf_srcptr) -> c_int;
+
+    /// Generate a uniformly distributed random float in rop, such that 0 ≤ rop < 1, with nbits
+    /// significant bits in the mantissa.
+    ///
+    /// The variable state must be initialized by calling one of the gmp_randinit functions before
+    /// invoking this function.
+    #[link_name = "__gmpf_urandomb"]
+    pub fn mpf_urandomb(rop: mpf_ptr, state: gmp_randstate_ptr, nbits: mp_bitcnt_t);
+}
+
+/// Return +1 if op > 0, 0 if op = 0, and −1 if op < 0.
+///
+/// # Safety
+///
+/// `op` must point to an initialised `mpf_struct`.
+pub unsafe fn mpf_sgn(op: mpf_srcptr) -> c_int {
+    (*op)._mp_size.signum()
+}
+
+/// Arbitrary precision floating-point number.
+///
+/// Owns an initialised `mpf_struct`, which is cleared when the value is dropped. There is no
+/// `Default`, as every value needs a precision; see [`Mpf::new`].
+///
+/// A failed allocation aborts the process, as for [`Mpz`](crate::Mpz).
+pub struct Mpf(mpf_struct);
+
+// As for Mpz, the storage is uniquely owned and MPIR never mutates through a `mpf_srcptr`.
+unsafe impl Send for Mpf {}
+unsafe impl Sync for Mpf {}
+
+impl Mpf {
+    /// Create a new float with the value 0 and a precision of at least `prec` bits.
+    pub fn new(prec: mp_bitcnt_t) -> Self {
+        unsafe { Self::from_init(prec, |_| {}) }
+    }
+
+    /// Create a float with a precision of at least `prec` bits, set to `val` truncated to that
+    /// precision.
+    ///
+    /// `val` can be anything that can be assigned to an `Mpf`: another float, an [`Mpz`](crate::Mpz),
+    /// an [`Mpq`](crate::Mpq), a primitive number or an expression such as `&a * &b`.
+    pub fn with_val<T>(prec: mp_bitcnt_t, val: T) -> Self
+    where
+        Self: Assign<T>,
+    {
+        let mut x = Mpf::new(prec);
+        x.assign(val);
+        x
+    }
+
+    /// The precision in bits.
+    ///
+    /// This is at least the precision the float was created with, as MPIR rounds it up to a whole
+    /// number of limbs.
+    pub fn prec(&self) -> mp_bitcnt_t {
+        unsafe { mpf_get_prec(self.as_raw()) }
+    }
+
+    /// Change the precision to at least `prec` bits, truncating the value if the precision is
+    /// reduced.
+    pub fn set_prec(&mut self, prec: mp_bitcnt_t) {
+        unsafe { mpf_set_prec(self.as_raw_mut(), prec) }
+    }
+
+    /// Pointer to the underlying `mpf_struct`, for passing as a source operand to MPIR.
+    pub fn as_raw(&self) -> mpf_srcptr {
+        &self.0
+    }
+
+    /// Mutable pointer to the underlying `mpf_struct`, for passing as an output operand to MPIR.
+    pub fn as_raw_mut(&mut self) -> mpf_ptr {
+        &mut self.0
+    }
+
+    /// Return the sign of the float.
+    pub fn sign(&self) -> Sign {
+        match self.0._mp_size {
+            0 => Sign::Zero,
+            s if s < 0 => Sign::Negative,
+            _ => Sign::Positive,
+        }
+    }
+
+    /// Return `true` if the float is zero.
+    pub fn is_zero(&self) -> bool {
+        self.0._mp_size == 0
+    }
+
+    /// Return `true` if the float is greater than zero.
+    pub fn is_positive(&self) -> bool {
+        self.0._mp_size > 0
+    }
+
+    /// Return `true` if the float is less than zero.
+    pub fn is_negative(&self) -> bool {
+        self.0._mp_size < 0
+    }
+
+    /// Return `true` if the float is an integer.
+    pub fn is_integer(&self) -> bool {
+        unsafe { mpf_integer_p(self.as_raw()) != 0 }
+    }
+
+    /// The absolute value, with the same precision.
+    pub fn abs(&self) -> Mpf {
+        self.map(|r, x| unsafe { mpf_abs(r, x) })
+    }
+
+    /// The square root, with the same precision.
+    ///
+    /// MPIR raises a signal for a negative operand, so this returns
+    /// [`MpirError::SqrtOfNegative`] instead.
+    pub fn sqrt(&self) -> Result<Mpf, MpirError> {
+        if self.is_negative() {
+            return Err(MpirError::SqrtOfNegative);
+        }
+        Ok(self.map(|r, x| unsafe { mpf_sqrt(r, x) }))
+    }
+
+    /// Raise to the power `exp`, with the same precision.
+    ///
+    /// The result is computed by repeated squaring, so the error in the last bits grows slowly with
+    /// `exp`.
+    pub fn pow(&self, exp: u32) -> Mpf {
+        self.map(|r, x| unsafe { mpf_pow_ui(r, x, exp as c_ulong) })
+    }
+
+    /// The largest integer less than or equal to the float, with the same precision.
+    pub fn floor(&self) -> Mpf {
+        self.map(|r, x| unsafe { mpf_floor(r, x) })
+    }
+
+    /// The smallest integer greater than or equal to the float, with the same precision.
+    pub fn ceil(&self) -> Mpf {
+        self.map(|r, x| unsafe { mpf_ceil(r, x) })
+    }
+
+    /// The integer part, rounding towards zero, with the same precision.
+    pub fn trunc(&self) -> Mpf {
+        self.map(|r, x| unsafe { mpf_trunc(r, x) })
+    }
+
+    /// The relative difference `|self - other| / self`, with the precision of `self`.
+    ///
+    /// If `self` is zero, the result is 0 if `other` is also zero and 1 otherwise.
+    pub fn reldiff(&self, other: &Mpf) -> Mpf {
+        self.map(|r, x| unsafe { mpf_reldiff(r, x, other.as_raw()) })
+    }
+
+    /// Apply `f` to a new float with the precision of `self`, and `self`.
+    fn map<F: FnOnce(mpf_ptr, mpf_srcptr)>(&self, f: F) -> Mpf {
+        let mut r = Mpf::new(self.prec());
+        f(r.as_raw_mut(), self.as_raw());
+        r
+    }
+
+    /// Run `mpf_init2` on fresh storage, then `set` on the result, and take ownership of it.
+    unsafe fn from_init<F: FnOnce(mpf_ptr)>(prec: mp_bitcnt_t, set: F) -> Self {
+        crate::alloc::init();
+        let mut x = MaybeUninit::<mpf_struct>::uninit();
+        mpf_init2(x.as_mut_ptr(), prec);
+        set(x.as_mut_ptr());
+        Mpf(x.assume_init())
+    }
+}
+
+impl Clone for Mpf {
+    fn clone(&self) -> Self {
+        unsafe { Self::from_init(self.prec(), |x| mpf_set(x, self.as_raw())) }
+    }
+
+    fn clone_from(&mut self, source: &Self) {
+        if self.0._mp_prec != source.0._mp_prec {
+            self.set_prec(source.prec());
+        }
+        unsafe { mpf_set(self.as_raw_mut(), source.as_raw()) }
+    }
+}
+
+impl Drop for Mpf {
+    fn drop(&mut self) {
+        unsafe { mpf_clear(self.as_raw_mut()) }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{Mpq, Mpz, RandState};
+    use std::ffi::{CStr, CString};
+
+    fn f(v: f64) -> Mpf {
+        Mpf::with_val(128, v)
+    }
+
+    fn raw<F: FnOnce(mpf_ptr)>(f: F) -> Mpf {
+        let mut r = Mpf::new(128);
+        f(r.as_raw_mut());
+        r
+    }
+
+    #[test]
+    fn new() {
+        let x = Mpf::new(100);
+        assert!(x.is_zero());
+        assert_eq!(x.sign(), Sign::Zero);
+        assert!(x.prec() >= 100);
+        assert_eq!(Mpf::new(x.prec()).prec(), x.prec());
+        assert!(Mpf::new(1000).prec() >= 1000);
+    }
+
+    #[test]
+    fn precision() {
+        let third = Mpf::with_val(64, 1) / 3u32;
+        let mut x = Mpf::with_val(512, 1) / 3u32;
+        assert_ne!(x, third);
+        x.set_prec(64);
+        assert_eq!(x.prec(), third.prec());
+        assert!(x.reldiff(&third) < Mpf::with_val(64, 1e-18));
+        let mut y = Mpf::new(1024);
+        y.clone_from(&x);
+        assert_eq!((y.prec(), &y), (x.prec(), &x));
+        assert_eq!(x.clone().prec(), x.prec());
+    }
+
+    #[test]
+    fn methods() {
+        let x = f(-7.5);
+        assert!(x.is_negative() && !x.is_positive());
+        assert_eq!(x.sign(), Sign::Negative);
+        assert_eq!(x.abs(), 7.5);
+        assert_eq!(x.floor(), -8);
+        assert_eq!(x.ceil(), -7);
+        assert_eq!(x.trunc(), -7);
+        assert!(!x.is_integer() && x.trunc().is_integer());
+        assert_eq!(f(1.5).pow(3), 3.375);
+        assert_eq!(f(6.25).sqrt(), Ok(f(2.5)));
+        assert_eq!(x.sqrt(), Err(MpirError::SqrtOfNegative));
+        assert_eq!(f(4.0).reldiff(&f(5.0)), 0.25);
+        assert_eq!(Mpf::new(64).reldiff(&f(1.0)), 1);
+    }
+
+    #[test]
+    fn sqrt_two() {
+        let root = Mpf::with_val(512, 2).sqrt().unwrap();
+        let square = Mpf::from(&root * &root);
+        assert!(square.reldiff(&Mpf::with_val(512, 2)) < Mpf::with_val(64, 1e-150));
+        assert_eq!(
+            root.to_string_radix(10, 50).unwrap(),
+            "1.4142135623730950488016887242096980785696718753769"
+        );
+    }
+
+    #[test]
+    fn ffi_assignment() {
+        unsafe {
+            assert_eq!(raw(|r| mpf_set_ui(r, 7)), 7);
+            assert_eq!(raw(|r| mpf_set_si(r, -7)), -7);
+            assert_eq!(raw(|r| mpf_set_d(r, 0.375)), 0.375);
+            assert_eq!(raw(|r| mpf_set_z(r, Mpz::from(-12).as_raw())), -12);
+            let q = Mpq::from_parts(Mpz::from(3), Mpz::from(8)).unwrap();
+            assert_eq!(raw(|r| mpf_set_q(r, q.as_raw())), 0.375);
+            let mut x = raw(|r| mpf_set(r, f(2.5).as_raw()));
+            assert_eq!(x, 2.5);
+            let s = CString::new("-1.25e2").unwrap();
+            assert_eq!(mpf_set_str(x.as_raw_mut(), s.as_ptr(), 10), 0);
+            assert_eq!(x, -125);
+            let s = CString::new("f.8@1").unwrap();
+            assert_eq!(mpf_set_str(x.as_raw_mut(), s.as_ptr(), 16), 0);
+            assert_eq!(x, 248);
+            let s = CString::new("1.2.3").unwrap();
+            assert_eq!(mpf_set_str(x.as_raw_mut(), s.as_ptr(), 10), -1);
+            let mut y = Mpf::new(512);
+            mpf_swap(x.as_raw_mut(), y.as_raw_mut());
+            assert!(x.is_zero() && x.prec() >= 512);
+            assert_eq!(y, 248);
+        }
+    }
+
+    #[test]
+    fn ffi_conversion() {
+        unsafe {
+            let x = f(-0.375);
+            assert_eq!(mpf_get_d(x.as_raw()), -0.375);
+            let mut exp: c_long = 0;
+            assert_eq!(mpf_get_d_2exp(&mut exp, f(12.0).as_raw()), 0.75);
+            assert_eq!(exp, 4);
+            assert_eq!(mpf_get_si(f(-7.9).as_raw()), -7);
+            assert_eq!(mpf_get_ui(f(7.9).as_raw()), 7);
+            let mut exp: mp_exp_t = 0;
+            let ptr = mpf_get_str(std::ptr::null_mut(), &mut exp, 10, 0, f(-31.416).as_raw());
+            let s = CStr::from_ptr(ptr);
+            assert!(s.to_str().unwrap().starts_with("-31416"));
+            assert_eq!(exp, 2);
+            crate::alloc::free(ptr as *mut _, s.to_bytes_with_nul().len());
+            let mut z = Mpz::new();
+            crate::mpz::mpz_set_f(z.as_raw_mut(), f(-7.9).as_raw());
+            assert_eq!(z, -7);
+            let mut q = Mpq::new();
+            crate::mpq::mpq_set_f(q.as_raw_mut(), f(-7.5).as_raw());
+            assert_eq!(q, Mpq::from_parts(Mpz::from(-15), Mpz::from(2)).unwrap());
+        }
+    }
+
+    #[test]
+    fn ffi_arithmetic() {
+        unsafe {
+            let (a, b) = (f(1.5), f(-0.25));
+            assert_eq!(raw(|r| mpf_add(r, a.as_raw(), b.as_raw())), 1.25);
+            assert_eq!(raw(|r| mpf_add_ui(r, a.as_raw(), 2)), 3.5);
+            assert_eq!(raw(|r| mpf_sub(r, a.as_raw(), b.as_raw())), 1.75);
+            assert_eq!(raw(|r| mpf_sub_ui(r, a.as_raw(), 2)), -0.5);
+            assert_eq!(raw(|r| mpf_ui_sub(r, 2, a.as_raw())), 0.5);
+            assert_eq!(raw(|r| mpf_mul(r, a.as_raw(), b.as_raw())), -0.375);
+            assert_eq!(raw(|r| mpf_mul_ui(r, a.as_raw(), 3)), 4.5);
+            assert_eq!(raw(|r| mpf_div(r, a.as_raw(), b.as_raw())), -6);
+            assert_eq!(raw(|r| mpf_div_ui(r, a.as_raw(), 3)), 0.5);
+            assert_eq!(raw(|r| mpf_ui_div(r, 3, a.as_raw())), 2);
+            assert_eq!(raw(|r| mpf_sqrt(r, f(2.25).as_raw())), 1.5);
+            assert_eq!(raw(|r| mpf_sqrt_ui(r, 49)), 7);
+            assert_eq!(raw(|r| mpf_pow_ui(r, b.as_raw(), 3)), -0.015625);
+            assert_eq!(raw(|r| mpf_neg(r, b.as_raw())), 0.25);
+            assert_eq!(raw(|r| mpf_abs(r, b.as_raw())), 0.25);
+            assert_eq!(raw(|r| mpf_mul_2exp(r, a.as_raw(), 3)), 12);
+            assert_eq!(raw(|r| mpf_div_2exp(r, a.as_raw(), 1)), 0.75);
+        }
+    }
+
+    #[test]
+    fn ffi_comparison() {
+        unsafe {
+            let (a, b) = (f(1.5), f(-0.25));
+            assert!(mpf_cmp(a.as_raw(), b.as_raw()) > 0);
+            assert!(mpf_cmp_d(a.as_raw(), 1.5) == 0);
+            assert!(mpf_cmp_d(a.as_raw(), f64::INFINITY) < 0);
+            assert!(mpf_cmp_ui(a.as_raw(), 2) < 0);
+            assert!(mpf_cmp_si(b.as_raw(), -1) > 0);
+            assert_eq!(mpf_sgn(b.as_raw()), -1);
+            assert_eq!(mpf_sgn(Mpf::new(64).as_raw()), 0);
+            assert_ne!(mpf_eq(a.as_raw(), f(1.5).as_raw(), 64), 0);
+            assert_eq!(mpf_eq(a.as_raw(), b.as_raw(), 64), 0);
+            assert_eq!(
+                raw(|r| mpf_reldiff(r, f(2.0).as_raw(), f(1.5).as_raw())),
+                0.25
+            );
+        }
+    }
+
+    #[test]
+    fn ffi_miscellaneous() {
+        unsafe {
+            let x = f(-2.5);
+            assert_eq!(raw(|r| mpf_ceil(r, x.as_raw())), -2);
+            assert_eq!(raw(|r| mpf_floor(r, x.as_raw())), -3);
+            assert_eq!(raw(|r| mpf_trunc(r, x.as_raw())), -2);
+            assert_eq!(mpf_integer_p(x.as_raw()), 0);
+            assert_ne!(mpf_integer_p(f(-2.0).as_raw()), 0);
+            assert_ne!(mpf_fits_slong_p(x.as_raw()), 0);
+            assert_eq!(mpf_fits_ulong_p(x.as_raw()), 0);
+            assert_ne!(mpf_fits_sint_p(x.as_raw()), 0);
+            assert_eq!(mpf_fits_uint_p(x.as_raw()), 0);
+            assert_ne!(mpf_fits_sshort_p(f(-32768.0).as_raw()), 0);
+            assert_eq!(mpf_fits_ushort_p(f(65536.0).as_raw()), 0);
+            let mut state = RandState::new();
+            let r = raw(|r| mpf_urandomb(r, state.as_raw_mut(), 100));
+            assert!(!r.is_negative() && r < 1);
+        }
+    }
+}
@@ -0,0 +1,228 @@
+//! Comparison for [`Mpf`]
+//!
+//! Floats are totally ordered by value with `mpf_cmp`, whatever their precisions, so a float
+//! compares equal to a more precise one holding the same value. They compare with [`Mpz`], [`Mpq`],
+//! the primitive integer types and `f64` / `f32` on either side of the operator. Comparisons with
+//! a NaN are always false.
+//!
+//! Floats are not hashed: equal values can have different representations.
+
+use std::cmp::Ordering;
+
+use super::ops::exact;
+use super::*;
+use crate::mpz::ops::{Primitive, Small};
+use crate::{Mpq, Mpz};
+
+/// Compare `x` with a primitive operand.
+fn cmp_small(x: &Mpf, v: Small) -> Ordering {
+    let c = match v {
+        Small::Pos(u) => unsafe { mpf_cmp_ui(x.as_raw(), u) },
+        Small::Neg(u) if u <= c_long::MIN.unsigned_abs() => unsafe {
+            mpf_cmp_si(x.as_raw(), (u as c_long).wrapping_neg())
+        },
+        v => return x.cmp(&exact(&v.into_mpz())),
+    };
+    c.cmp(&0)
+}
+
+/// Compare `x` with a double, or `None` if it is NaN.
+fn cmp_f64(x: &Mpf, d: f64) -> Option<Ordering> {
+    match d {
+        d if d.is_nan() => None,
+        d if d.is_infinite() => Some(match d > 0.0 {
+            true => Ordering::Less,
+            false => Ordering::Greater,
+        }),
+        d => Some(unsafe { mpf_cmp_d(x.as_raw(), d) }.cmp(&0)),
+    }
+}
+
+impl PartialEq for Mpf {
+    fn eq(&self, other: &Mpf) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Mpf {}
+
+impl PartialOrd for Mpf {
+    fn partial_cmp(&self, other: &Mpf) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Mpf {
+    fn cmp(&self, other: &Mpf) -> Ordering {
+        unsafe { mpf_cmp(self.as_raw(), other.as_raw()) }.cmp(&0)
+    }
+}
+
+impl PartialEq<Mpz> for Mpf {
+    fn eq(&self, other: &Mpz) -> bool {
+        *self == exact(other)
+    }
+}
+
+impl PartialEq<Mpf> for Mpz {
+    fn eq(&self, other: &Mpf) -> bool {
+        other == self
+    }
+}
+
+impl PartialOrd<Mpz> for Mpf {
+    fn partial_cmp(&self, other: &Mpz) -> Option<Ordering> {
+        Some(self.cmp(&exact(other)))
+    }
+}
+
+impl PartialOrd<Mpf> for Mpz {
+    fn partial_cmp(&self, other: &Mpf) -> Option<Ordering> {
+        Some(other.cmp(&exact(self)).reverse())
+    }
+}
+
+impl PartialEq<Mpq> for Mpf {
+    fn eq(&self, other: &Mpq) -> bool {
+        self.partial_cmp(other) == Some(Ordering::Equal)
+    }
+}
+
+impl PartialEq<Mpf> for Mpq {
+    fn eq(&self, other: &Mpf) -> bool {
+        other == self
+    }
+}
+
+impl PartialOrd<Mpq> for Mpf {
+    fn partial_cmp(&self, other: &Mpq) -> Option<Ordering> {
+        Some(Mpq::from(self).cmp(other))
+    }
+}
+
+impl PartialOrd<Mpf> for Mpq {
+    fn partial_cmp(&self, other: &Mpf) -> Option<Ordering> {
+        Some(self.cmp(&Mpq::from(other)))
+    }
+}
+
+macro_rules! cmp_prim {
+    ($($t:ty)*) => {$(
+        impl PartialEq<$t> for Mpf {
+            fn eq(&self, other: &$t) -> bool {
+                cmp_small(self, other.small()) == Ordering::Equal
+            }
+        }
+
+        impl PartialEq<Mpf> for $t {
+            fn eq(&self, other: &Mpf) -> bool {
+                other == self
+            }
+        }
+
+        impl PartialOrd<$t> for Mpf {
+            fn partial_cmp(&self, other: &$t) -> Option<Ordering> {
+                Some(cmp_small(self, other.small()))
+            }
+        }
+
+        impl PartialOrd<Mpf> for $t {
+            fn partial_cmp(&self, other: &Mpf) -> Option<Ordering> {
+                Some(cmp_small(other, self.small()).reverse())
+            }
+        }
+    )*};
+}
+
+cmp_prim! { u8 u16 u32 u64 u128 usize i8 i16 i32 i64 i128 isize }
+
+macro_rules! cmp_float {
+    ($($t:ty)*) => {$(
+        impl PartialEq<$t> for Mpf {
+            fn eq(&self, other: &$t) -> bool {
+                cmp_f64(self, *other as f64) == Some(Ordering::Equal)
+            }
+        }
+
+        impl PartialEq<Mpf> for $t {
+            fn eq(&self, other: &Mpf) -> bool {
+                other == self
+            }
+        }
+
+        impl PartialOrd<$t> for Mpf {
+            fn partial_cmp(&self, other: &$t) -> Option<Ordering> {
+                cmp_f64(self, *other as f64)
+            }
+        }
+
+        impl PartialOrd<Mpf> for $t {
+            fn partial_cmp(&self, other: &Mpf) -> Option<Ordering> {
+                cmp_f64(other, *self as f64).map(Ordering::reverse)
+            }
+        }
+    )*};
+}
+
+cmp_float! { f32 f64 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn f(v: f64) -> Mpf {
+        Mpf::with_val(128, v)
+    }
+
+    #[test]
+    fn ord() {
+        assert_eq!(f(0.5), Mpf::with_val(1024, 0.5));
+        assert!(f(-1.5) < f(-1.25));
+        assert!(Mpf::with_val(64, 1) / 3u8 < Mpf::with_val(1024, 1) / 3u8);
+        let mut xs = vec![f(2.0), f(-3.5), f(0.0), f(1e-30)];
+        xs.sort();
+        assert_eq!(xs, [f(-3.5), f(0.0), f(1e-30), f(2.0)]);
+    }
+
+    #[test]
+    fn integers() {
+        let x = f(3.5);
+        let (three, four) = (Mpz::from(3), Mpz::from(4));
+        assert!(x > three && x < four);
+        assert!(four > x);
+        assert_ne!(x, three);
+        assert_eq!(f(4.0), four);
+        assert!(x > 3 && x < 4u8);
+        assert!(3i64 < x);
+        assert_eq!(f(-3.0), -3);
+        assert_eq!(-3i32, f(-3.0));
+        assert!(f(-0.5) > i64::MIN);
+        assert!(f(-0.5) > i128::MIN);
+        assert!(f(0.5) < u128::MAX);
+        assert!(Mpf::with_val(256, u128::MAX) + f(0.5) > u128::MAX);
+    }
+
+    #[test]
+    fn rationals() {
+        let half = Mpq::from_parts(Mpz::from(1), Mpz::from(2)).unwrap();
+        let third = Mpq::from_parts(Mpz::from(1), Mpz::from(3)).unwrap();
+        assert_eq!(f(0.5), half);
+        assert_eq!(half, f(0.5));
+        assert!(f(0.5) > third);
+        assert!(third < f(0.5));
+        assert_ne!(Mpf::with_val(1024, 1) / 3u8, third);
+    }
+
+    #[test]
+    fn floats() {
+        let x = f(1.5);
+        assert_eq!(x, 1.5);
+        assert_eq!(1.5f32, x);
+        assert!(x < 1.75 && x > 1.25f32);
+        assert!(x < f64::INFINITY && x > f64::NEG_INFINITY);
+        assert!(f64::INFINITY > x);
+        assert_eq!(x.partial_cmp(&f64::NAN), None);
+        assert_eq!(f64::NAN.partial_cmp(&x), None);
+        assert!(!x.eq(&f64::NAN) && !x.ge(&f64::NAN) && !x.le(&f64::NAN));
+    }
+}
@@ -0,0 +1,97 @@
+//! Conversions from [`Mpf`] to floats, integers and rationals
+//!
+//! Values go into an `Mpf` with [`Mpf::with_val`] or `assign`, which take the precision from the
+//! target. Coming out, conversion to an [`Mpq`] is exact, while conversion to an [`Mpz`] or an `f64`
+//! truncates towards zero.
+
+use super::*;
+use crate::{Mpq, Mpz};
+
+impl Mpf {
+    /// Convert to an `f64`, truncating if necessary (i.e. rounding towards zero).
+    ///
+    /// If the value is too large for an `f64`, the result is system dependent, usually infinity.
+    pub fn to_f64(&self) -> f64 {
+        unsafe { mpf_get_d(self.as_raw()) }
+    }
+
+    /// Convert to an `f64` mantissa and exponent, truncating if necessary.
+    ///
+    /// Returns `(d, exp)` where `0.5 <= |d| < 1` and `d * 2^exp` is the truncated value. For zero
+    /// the result is `(0.0, 0)`. Unlike [`Mpf::to_f64`] this cannot overflow.
+    pub fn to_f64_exp(&self) -> (f64, i64) {
+        let mut exp: c_long = 0;
+        let d = unsafe { mpf_get_d_2exp(&mut exp, self.as_raw()) };
+        (d, exp as i64)
+    }
+}
+
+impl From<&Mpf> for Mpz {
+    /// Convert to an integer, truncating any fractional part.
+    fn from(x: &Mpf) -> Self {
+        let mut z = Mpz::new();
+        unsafe { crate::mpz::mpz_set_f(z.as_raw_mut(), x.as_raw()) };
+        z
+    }
+}
+
+impl From<Mpf> for Mpz {
+    /// Convert to an integer, truncating any fractional part.
+    fn from(x: Mpf) -> Self {
+        Mpz::from(&x)
+    }
+}
+
+impl From<&Mpf> for Mpq {
+    /// Convert to a rational exactly.
+    fn from(x: &Mpf) -> Self {
+        let mut q = Mpq::new();
+        unsafe { crate::mpq::mpq_set_f(q.as_raw_mut(), x.as_raw()) };
+        q
+    }
+}
+
+impl From<Mpf> for Mpq {
+    /// Convert to a rational exactly.
+    fn from(x: Mpf) -> Self {
+        Mpq::from(&x)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn floats() {
+        let x = Mpf::with_val(128, -0.375);
+        assert_eq!(x.to_f64(), -0.375);
+        assert_eq!(x.to_f64_exp(), (-0.75, -1));
+        assert_eq!(Mpf::new(64).to_f64_exp(), (0.0, 0));
+        let big = Mpf::with_val(64, 1.5) * Mpf::with_val(64, Mpz::from(1) << 5000u32);
+        assert_eq!(big.to_f64(), f64::INFINITY);
+        assert_eq!(big.to_f64_exp(), (0.75, 5001));
+        let third = Mpf::with_val(1024, 1) / 3u8;
+        assert_eq!(third.to_f64(), 0.3333333333333333);
+    }
+
+    #[test]
+    fn integers() {
+        assert_eq!(Mpz::from(&Mpf::with_val(64, -7.9)), -7);
+        assert_eq!(Mpz::from(Mpf::with_val(64, 7.9)), 7);
+        let big = Mpz::from(3).pow(200);
+        assert_eq!(Mpz::from(Mpf::with_val(512, &big)), big);
+        assert_eq!(Mpf::with_val(512, 2u8).pow(300), Mpz::from(1) << 300u32);
+    }
+
+    #[test]
+    fn rationals() {
+        let q = Mpq::from_parts(Mpz::from(-15), Mpz::from(8)).unwrap();
+        assert_eq!(Mpq::from(Mpf::with_val(64, &q)), q);
+        let third = Mpq::from_parts(Mpz::from(1), Mpz::from(3)).unwrap();
+        let approx = Mpq::from(Mpf::with_val(128, &third));
+        assert!(approx < third);
+        assert!(*approx.denom() > Mpz::from(1) << 100u32);
+        assert!(approx.denom().size_in_base(2) > 128);
+    }
+}
@@ -0,0 +1,269 @@
+//! String conversion for [`Mpf`]
+//!
+//! Formatting goes through `mpf_get_str`, which gives the significant digits and an exponent, and
+//! writes them positionally when the exponent is small, like `1234.5` or `0.00012345`, and in
+//! scientific notation otherwise, like `1.2345e-12`. For radixes above 10 the exponent marker is
+//! `@`, since `e` is a digit, and the exponent is always in decimal.
+//!
+//! There is no `FromStr`, as the precision has to be given: see [`Mpf::from_str_prec`].
+
+use std::error::Error;
+use std::ffi::CStr;
+use std::fmt;
+use std::ptr::null_mut;
+
+use super::*;
+use crate::ctype::{c_void, CString};
+
+/// Scientific notation is used when the exponent of the leading digit is outside
+/// `MIN_EXP..MAX_EXP`, as for JavaScript numbers.
+const MIN_EXP: i64 = -6;
+const MAX_EXP: i64 = 21;
+
+fn check_radix(radix: i32) -> Result<(), MpirError> {
+    match (2..=62).contains(&radix) {
+        true => Ok(()),
+        false => Err(MpirError::InvalidBase(radix)),
+    }
+}
+
+impl Mpf {
+    /// Convert to significant digits in `radix` and an exponent, such that the value is
+    /// `0.digits * radix^exp`, with a leading `-` on the digits if negative.
+    ///
+    /// At most `n_digits` digits are produced, or as many as the precision holds if it is 0.
+    /// Trailing zeros are removed, and zero gives no digits and an exponent of 0.
+    ///
+    /// Returns [`MpirError::InvalidBase`] if `radix` is not in the range 2 to 62.
+    pub fn to_digits(&self, radix: i32, n_digits: usize) -> Result<(String, i64), MpirError> {
+        check_radix(radix)?;
+        let mut exp: mp_exp_t = 0;
+        unsafe {
+            let ptr = mpf_get_str(null_mut(), &mut exp, radix, n_digits, self.as_raw());
+            let s = CStr::from_ptr(ptr);
+            let len = s.to_bytes_with_nul().len();
+            let out = String::from(s.to_str().expect("MPIR produced a non-ASCII digit"));
+            crate::alloc::free(ptr as *mut c_void, len);
+            Ok((out, exp as i64))
+        }
+    }
+
+    /// Convert to a string in `radix`, with at most `n_digits` significant digits, or as many as
+    /// the precision holds if it is 0.
+    ///
+    /// The digits are as for [`Mpz::to_string_radix`](crate::Mpz::to_string_radix), and it
+    /// returns [`MpirError::InvalidBase`] for the same radixes.
+    pub fn to_string_radix(&self, radix: i32, n_digits: usize) -> Result<String, MpirError> {
+        let (digits, exp) = self.to_digits(radix, n_digits)?;
+        let (sign, digits) = match digits.strip_prefix('-') {
+            Some(digits) => ("-", digits),
+            None => ("", digits.as_str()),
+        };
+        Ok(format!("{}{}", sign, layout(digits, exp, radix)))
+    }
+
+    /// Parse a float in `radix` with a precision of at least `prec` bits, truncating it to that
+    /// precision.
+    ///
+    /// The mantissa is digits with an optional point and an optional leading `+` or `-`. It can be
+    /// followed by an exponent in decimal after `@`, or after `e` or `E` if `radix` is 10 or less,
+    /// so `"-1.5e3"` and `"f.8@1"` in radix 16 are both accepted. Like the primitive float types,
+    /// no whitespace is accepted. A `radix` outside the range 2 to 62 gives
+    /// [`ParseMpfErrorKind::InvalidBase`].
+    pub fn from_str_radix(s: &str, radix: i32, prec: mp_bitcnt_t) -> Result<Self, ParseMpfError> {
+        let error = |kind| Err(ParseMpfError { kind });
+        if check_radix(radix).is_err() {
+            return error(ParseMpfErrorKind::InvalidBase(radix));
+        }
+        if s.is_empty() {
+            return error(ParseMpfErrorKind::Empty);
+        }
+        // MPIR ignores whitespace and accepts a leading `-` but not `+`.
+        let s = match s.strip_prefix('+') {
+            Some(rest) if rest.starts_with('-') => return error(ParseMpfErrorKind::Invalid),
+            Some(rest) => rest,
+            None => s,
+        };
+        if s.bytes().any(|c| c.is_ascii_whitespace()) {
+            return error(ParseMpfErrorKind::Invalid);
+        }
+        let c = match CString::new(s) {
+            Ok(c) => c,
+            Err(_) => return error(ParseMpfErrorKind::Invalid),
+        };
+        let mut x = Mpf::new(prec);
+        // A negative base reads the exponent in decimal.
+        match unsafe { mpf_set_str(x.as_raw_mut(), c.as_ptr(), -radix) } {
+            0 => Ok(x),
+            _ => error(ParseMpfErrorKind::Invalid),
+        }
+    }
+
+    /// Parse a decimal float with a precision of at least `prec` bits, as for
+    /// [`Mpf::from_str_radix`].
+    pub fn from_str_prec(s: &str, prec: mp_bitcnt_t) -> Result<Self, ParseMpfError> {
+        Mpf::from_str_radix(s, 10, prec)
+    }
+}
+
+/// Lay out unsigned significant digits with the value `0.digits * radix^exp`.
+fn layout(digits: &str, exp: i64, radix: i32) -> String {
+    if digits.is_empty() {
+        return String::from("0");
+    }
+    let len = digits.len() as i64;
+    // Exponent of the leading digit.
+    let e = exp - 1;
+    if !(MIN_EXP..MAX_EXP).contains(&e) {
+        let marker = if radix <= 10 { 'e' } else { '@' };
+        let (first, rest) = digits.split_at(1);
+        return match rest.is_empty() {
+            true => format!("{}{}{}", first, marker, e),
+            false => format!("{}.{}{}{}", first, rest, marker, e),
+        };
+    }
+    if exp <= 0 {
+        format!("0.{}{}", "0".repeat(-exp as usize), digits)
+    } else if exp >= len {
+        format!("{}{}", digits, "0".repeat((exp - len) as usize))
+    } else {
+        let (int, frac) = digits.split_at(exp as usize);
+        format!("{}.{}", int, frac)
+    }
+}
+
+impl fmt::Display for Mpf {
+    /// Write the value in decimal. A precision, as in `{:.10}`, limits the number of significant
+    /// digits.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let s = self
+            .to_string_radix(10, f.precision().unwrap_or(0))
+            .expect("10 is a valid radix");
+        match s.strip_prefix('-') {
+            Some(digits) => f.pad_integral(false, "", digits),
+            None => f.pad_integral(true, "", &s),
+        }
+    }
+}
+
+impl fmt::Debug for Mpf {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(self, f)
+    }
+}
+
+/// Reason a string could not be parsed as an [`Mpf`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ParseMpfErrorKind {
+    /// The string was empty.
+    Empty,
+    /// The string was not a valid float in the given base.
+    Invalid,
+    /// The base was not in the range 2 to 62.
+    InvalidBase(i32),
+}
+
+/// Error returned when parsing an [`Mpf`] from a string fails.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ParseMpfError {
+    kind: ParseMpfErrorKind,
+}
+
+impl ParseMpfError {
+    /// The reason parsing failed.
+    pub fn kind(&self) -> &ParseMpfErrorKind {
+        &self.kind
+    }
+}
+
+impl fmt::Display for ParseMpfError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.kind {
+            ParseMpfErrorKind::Empty => f.write_str("cannot parse float from empty string"),
+            ParseMpfErrorKind::Invalid => f.write_str("invalid float literal"),
+            ParseMpfErrorKind::InvalidBase(radix) => MpirError::InvalidBase(radix).fmt(f),
+        }
+    }
+}
+
+impl Error for ParseMpfError {}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn f(v: f64) -> Mpf {
+        Mpf::with_val(128, v)
+    }
+
+    #[test]
+    fn display() {
+        assert_eq!(f(0.0).to_string(), "0");
+        assert_eq!(f(1234.5).to_string(), "1234.5");
+        assert_eq!(f(-0.000244140625).to_string(), "-0.000244140625");
+        assert_eq!(f(100.0).to_string(), "100");
+        assert_eq!(f(1.5e20).to_string(), "150000000000000000000");
+        assert_eq!(f(1.5e21).to_string(), "1.5e21");
+        assert_eq!(f(-2f64.powi(-20)).to_string(), "-9.5367431640625e-7");
+        assert_eq!(
+            f(2f64.powi(100)).to_string(),
+            "1.267650600228229401496703205376e30"
+        );
+        assert!((Mpf::with_val(64, 2) / 3u8)
+            .to_string()
+            .starts_with("0.6666666666666666666"));
+        assert_eq!(format!("{:.5}", Mpf::with_val(64, 2) / 3u8), "0.66667");
+        assert_eq!(format!("{:+}", f(1.5)), "+1.5");
+        assert_eq!(format!("{:>6}", f(-0.5)), "  -0.5");
+        assert_eq!(format!("{:?}", f(0.25)), "0.25");
+    }
+
+    #[test]
+    fn radix() {
+        let s = |v, radix| f(v).to_string_radix(radix, 0).unwrap();
+        assert_eq!(s(-255.5, 16), "-ff.8");
+        assert_eq!(s(2f64.powi(100), 16), "1@25");
+        assert_eq!(s(0.375, 2), "0.011");
+        assert_eq!(f(1234.5).to_digits(10, 3), Ok((String::from("123"), 4)));
+        assert_eq!(f(-0.5).to_digits(10, 0), Ok((String::from("-5"), 0)));
+        assert_eq!(Mpf::new(64).to_digits(10, 0), Ok((String::new(), 0)));
+        assert_eq!(f(1.0).to_digits(1, 0), Err(MpirError::InvalidBase(1)));
+        assert_eq!(f(1.0).to_string_radix(63, 0), Err(MpirError::InvalidBase(63)));
+    }
+
+    #[test]
+    fn from_str() {
+        let p = |s: &str| Mpf::from_str_prec(s, 128).unwrap();
+        assert_eq!(p("1.5"), 1.5);
+        assert_eq!(p("-1.5e3"), -1500);
+        assert_eq!(p("+.25"), 0.25);
+        assert_eq!(p("25E-2"), 0.25);
+        assert_eq!(p("7"), 7);
+        assert_eq!(Mpf::from_str_radix("-ff.8", 16, 64), Ok(f(-255.5)));
+        assert_eq!(Mpf::from_str_radix("1@25", 16, 64), Ok(f(2f64.powi(100))));
+        assert_eq!(Mpf::from_str_radix("0.011", 2, 64), Ok(f(0.375)));
+        let x = Mpf::with_val(256, 1) / 3u8;
+        let y = Mpf::from_str_prec(&x.to_string(), 256).unwrap();
+        assert!(x.reldiff(&y) < Mpf::with_val(64, 1e-75));
+        assert_eq!(p("0.1").prec(), 128);
+        assert_ne!(p("0.1"), Mpf::from_str_prec("0.1", 512).unwrap());
+    }
+
+    #[test]
+    fn parse_errors() {
+        let kind = |s: &str| *Mpf::from_str_prec(s, 64).unwrap_err().kind();
+        assert_eq!(kind(""), ParseMpfErrorKind::Empty);
+        assert_eq!(kind(" 1.5"), ParseMpfErrorKind::Invalid);
+        assert_eq!(kind("1. 5"), ParseMpfErrorKind::Invalid);
+        assert_eq!(kind("1.2.3"), ParseMpfErrorKind::Invalid);
+        assert_eq!(kind("+-1"), ParseMpfErrorKind::Invalid);
+        assert_eq!(kind("1e"), ParseMpfErrorKind::Invalid);
+        assert_eq!(kind("0x10"), ParseMpfErrorKind::Invalid);
+        assert_eq!(kind("1\0"), ParseMpfErrorKind::Invalid);
+        assert_eq!(kind("nan"), ParseMpfErrorKind::Invalid);
+        assert_eq!(
+            *Mpf::from_str_radix("1", 63, 64).unwrap_err().kind(),
+            ParseMpfErrorKind::InvalidBase(63)
+        );
+    }
+}
@@ -0,0 +1,495 @@
+//! Lazily evaluated expressions on borrowed [`Mpf`] values
+//!
+//! `&a + &b`, `&a - &b`, `&a * &b` and `&a / &b` (and the same with a primitive on either side) do
+//! not compute anything. They return an incomplete value holding references to their operands,
+//! which is only evaluated when it is assigned somewhere, and the target decides the precision:
+//!
+//! - `x.assign(&a / &b)` evaluates at the precision of `x`, in `x`'s existing allocation.
+//! - `Mpf::with_val(prec, &a / &b)` evaluates at `prec`.
+//! - `Mpf::from(&a / &b)` evaluates at the precision of `a`, or of the `Mpf` operand if the left
+//!   one is a primitive.
+//!
+//! Incomplete values can be combined further with the operators, in which case they are evaluated
+//! into a new `Mpf` first and the rest of the expression continues in place. An incomplete value
+//! on the right of an `Mpf` is evaluated at the precision of that `Mpf`.
+
+use std::ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Sub, SubAssign};
+
+use super::ops::{add_small, div, div_small, mul_small, small_div, small_sub, sub_small};
+use super::*;
+use crate::mpz::ops::{Primitive, Small};
+use crate::{Mpq, Mpz};
+
+/// The second operand of an incomplete expression.
+enum Operand<'a> {
+    Mpf(&'a Mpf),
+    Small(Small),
+}
+
+/// Incomplete `&a + &b`, evaluated when assigned to an [`Mpf`].
+pub struct AddIncomplete<'a> {
+    lhs: &'a Mpf,
+    rhs: Operand<'a>,
+}
+
+/// Incomplete `&a - &b`, evaluated when assigned to an [`Mpf`].
+pub struct SubIncomplete<'a> {
+    lhs: &'a Mpf,
+    rhs: Operand<'a>,
+    /// Whether the operands were written the other way round, i.e. this is `rhs - lhs`.
+    reversed: bool,
+}
+
+/// Incomplete `&a * &b`, evaluated when assigned to an [`Mpf`].
+pub struct MulIncomplete<'a> {
+    lhs: &'a Mpf,
+    rhs: Operand<'a>,
+}
+
+/// Incomplete `&a / &b`, evaluated when assigned to an [`Mpf`].
+pub struct DivIncomplete<'a> {
+    lhs: &'a Mpf,
+    rhs: Operand<'a>,
+    /// Whether the operands were written the other way round, i.e. this is `rhs / lhs`.
+    reversed: bool,
+}
+
+// -------------------------------------------------------------------------------------------------
+// Evaluation
+
+impl AddIncomplete<'_> {
+    unsafe fn eval(&self, rop: mpf_ptr) {
+        match &self.rhs {
+            Operand::Mpf(b) => mpf_add(rop, self.lhs.as_raw(), b.as_raw()),
+            Operand::Small(s) => add_small(rop, self.lhs.as_raw(), s),
+        }
+    }
+}
+
+impl SubIncomplete<'_> {
+    unsafe fn eval(&self, rop: mpf_ptr) {
+        match (&self.rhs, self.reversed) {
+            (Operand::Mpf(b), false) => mpf_sub(rop, self.lhs.as_raw(), b.as_raw()),
+            (Operand::Mpf(b), true) => mpf_sub(rop, b.as_raw(), self.lhs.as_raw()),
+            (Operand::Small(s), false) => sub_small(rop, self.lhs.as_raw(), s),
+            (Operand::Small(s), true) => small_sub(rop, s, self.lhs.as_raw()),
+        }
+    }
+}
+
+impl MulIncomplete<'_> {
+    unsafe fn eval(&self, rop: mpf_ptr) {
+        match &self.rhs {
+            Operand::Mpf(b) => mpf_mul(rop, self.lhs.as_raw(), b.as_raw()),
+            Operand::Small(s) => mul_small(rop, self.lhs.as_raw(), s),
+        }
+    }
+}
+
+impl DivIncomplete<'_> {
+    unsafe fn eval(&self, rop: mpf_ptr) {
+        match (&self.rhs, self.reversed) {
+            (Operand::Mpf(b), false) => div(rop, self.lhs.as_raw(), b.as_raw()),
+            (Operand::Mpf(b), true) => div(rop, b.as_raw(), self.lhs.as_raw()),
+            (Operand::Small(s), false) => div_small(rop, self.lhs.as_raw(), s),
+            (Operand::Small(s), true) => small_div(rop, s, self.lhs.as_raw()),
+        }
+    }
+}
+
+macro_rules! complete {
+    ($($Ty:ident)*) => {$(
+        impl Assign<$Ty<'_>> for Mpf {
+            fn assign(&mut self, src: $Ty<'_>) {
+                unsafe { src.eval(self.as_raw_mut()) }
+            }
+        }
+
+        impl From<$Ty<'_>> for Mpf {
+            fn from(src: $Ty<'_>) -> Self {
+                let mut r = Mpf::new(src.lhs.prec());
+                r.assign(src);
+                r
+            }
+        }
+    )*};
+}
+
+complete! { AddIncomplete SubIncomplete MulIncomplete DivIncomplete }
+
+// -------------------------------------------------------------------------------------------------
+// Assignment of complete values
+//
+// The value is truncated to the precision of the target, which keeps its precision.
+
+impl Assign<&Mpf> for Mpf {
+    fn assign(&mut self, src: &Mpf) {
+        unsafe { mpf_set(self.as_raw_mut(), src.as_raw()) }
+    }
+}
+
+impl Assign<Mpf> for Mpf {
+    /// Take over the allocation of `src` if it has the same precision as `self`.
+    fn assign(&mut self, mut src: Mpf) {
+        match self.0._mp_prec == src.0._mp_prec {
+            true => unsafe { mpf_swap(self.as_raw_mut(), src.as_raw_mut()) },
+            false => self.assign(&src),
+        }
+    }
+}
+
+impl Assign<&Mpz> for Mpf {
+    fn assign(&mut self, src: &Mpz) {
+        unsafe { mpf_set_z(self.as_raw_mut(), src.as_raw()) }
+    }
+}
+
+impl Assign<Mpz> for Mpf {
+    fn assign(&mut self, src: Mpz) {
+        self.assign(&src);
+    }
+}
+
+impl Assign<&Mpq> for Mpf {
+    fn assign(&mut self, src: &Mpq) {
+        unsafe { mpf_set_q(self.as_raw_mut(), src.as_raw()) }
+    }
+}
+
+impl Assign<Mpq> for Mpf {
+    fn assign(&mut self, src: Mpq) {
+        self.assign(&src);
+    }
+}
+
+impl Assign<f64> for Mpf {
+    /// # Panics
+    ///
+    /// If `src` is NaN or infinite.
+    fn assign(&mut self, src: f64) {
+        assert!(
+            src.is_finite(),
+            "cannot assign NaN or infinite float to an Mpf"
+        );
+        unsafe { mpf_set_d(self.as_raw_mut(), src) }
+    }
+}
+
+impl Assign<f32> for Mpf {
+    /// # Panics
+    ///
+    /// If `src` is NaN or infinite.
+    fn assign(&mut self, src: f32) {
+        self.assign(src as f64);
+    }
+}
+
+macro_rules! assign_prim {
+    ($($t:ty)*) => {$(
+        impl Assign<$t> for Mpf {
+            fn assign(&mut self, src: $t) {
+                let p = self.as_raw_mut();
+                match src.small() {
+                    Small::Pos(u) => unsafe { mpf_set_ui(p, u) },
+                    Small::Neg(u) => unsafe {
+                        mpf_set_ui(p, u);
+                        mpf_neg(p, p);
+                    },
+                    Small::Big(z) => unsafe { mpf_set_z(p, z.as_raw()) },
+                }
+            }
+        }
+    )*};
+}
+
+assign_prim! { u8 u16 u32 u64 u128 usize i8 i16 i32 i64 i128 isize }
+
+// -------------------------------------------------------------------------------------------------
+// Building incomplete values
+
+impl<'a> Add<&'a Mpf> for &'a Mpf {
+    type Output = AddIncomplete<'a>;
+    fn add(self, rhs: &'a Mpf) -> AddIncomplete<'a> {
+        AddIncomplete {
+            lhs: self,
+            rhs: Operand::Mpf(rhs),
+        }
+    }
+}
+
+impl<'a> Sub<&'a Mpf> for &'a Mpf {
+    type Output = SubIncomplete<'a>;
+    fn sub(self, rhs: &'a Mpf) -> SubIncomplete<'a> {
+        SubIncomplete {
+            lhs: self,
+            rhs: Operand::Mpf(rhs),
+            reversed: false,
+        }
+    }
+}
+
+impl<'a> Mul<&'a Mpf> for &'a Mpf {
+    type Output = MulIncomplete<'a>;
+    fn mul(self, rhs: &'a Mpf) -> MulIncomplete<'a> {
+        MulIncomplete {
+            lhs: self,
+            rhs: Operand::Mpf(rhs),
+        }
+    }
+}
+
+impl<'a> Div<&'a Mpf> for &'a Mpf {
+    type Output = DivIncomplete<'a>;
+    fn div(self, rhs: &'a Mpf) -> DivIncomplete<'a> {
+        DivIncomplete {
+            lhs: self,
+            rhs: Operand::Mpf(rhs),
+            reversed: false,
+        }
+    }
+}
+
+macro_rules! incomplete_prim {
+    ($($t:ty)*) => {$(
+        impl<'a> Add<$t> for &'a Mpf {
+            type Output = AddIncomplete<'a>;
+            fn add(self, rhs: $t) -> AddIncomplete<'a> {
+                AddIncomplete { lhs: self, rhs: Operand::Small(rhs.small()) }
+            }
+        }
+
+        impl<'a> Add<&'a Mpf> for $t {
+            type Output = AddIncomplete<'a>;
+            fn add(self, rhs: &'a Mpf) -> AddIncomplete<'a> {
+                AddIncomplete { lhs: rhs, rhs: Operand::Small(self.small()) }
+            }
+        }
+
+        impl<'a> Sub<$t> for &'a Mpf {
+            type Output = SubIncomplete<'a>;
+            fn sub(self, rhs: $t) -> SubIncomplete<'a> {
+                SubIncomplete { lhs: self, rhs: Operand::Small(rhs.small()), reversed: false }
+            }
+        }
+
+        impl<'a> Sub<&'a Mpf> for $t {
+            type Output = SubIncomplete<'a>;
+            fn sub(self, rhs: &'a Mpf) -> SubIncomplete<'a> {
+                SubIncomplete { lhs: rhs, rhs: Operand::Small(self.small()), reversed: true }
+            }
+        }
+
+        impl<'a> Mul<$t> for &'a Mpf {
+            type Output = MulIncomplete<'a>;
+            fn mul(self, rhs: $t) -> MulIncomplete<'a> {
+                MulIncomplete { lhs: self, rhs: Operand::Small(rhs.small()) }
+            }
+        }
+
+        impl<'a> Mul<&'a Mpf> for $t {
+            type Output = MulIncomplete<'a>;
+            fn mul(self, rhs: &'a Mpf) -> MulIncomplete<'a> {
+                MulIncomplete { lhs: rhs, rhs: Operand::Small(self.small()) }
+            }
+        }
+
+        impl<'a> Div<$t> for &'a Mpf {
+            type Output = DivIncomplete<'a>;
+            fn div(self, rhs: $t) -> DivIncomplete<'a> {
+                DivIncomplete { lhs: self, rhs: Operand::Small(rhs.small()), reversed: false }
+            }
+        }
+
+        impl<'a> Div<&'a Mpf> for $t {
+            type Output = DivIncomplete<'a>;
+            fn div(self, rhs: &'a Mpf) -> DivIncomplete<'a> {
+                DivIncomplete { lhs: rhs, rhs: Operand::Small(self.small()), reversed: true }
+            }
+        }
+    )*};
+}
+
+incomplete_prim! { u8 u16 u32 u64 u128 usize i8 i16 i32 i64 i128 isize }
+
+// -------------------------------------------------------------------------------------------------
+// Incomplete values on the right of an Mpf
+//
+// These are evaluated at the precision of the left operand, and the operation continues as for a
+// complete value.
+
+macro_rules! incomplete_rhs {
+    ($Op:ident $op:ident $OpAssign:ident $op_assign:ident; $($Ty:ident)*) => {$(
+        impl $OpAssign<$Ty<'_>> for Mpf {
+            fn $op_assign(&mut self, rhs: $Ty<'_>) {
+                self.$op_assign(Mpf::with_val(self.prec(), rhs));
+            }
+        }
+
+        impl $Op<$Ty<'_>> for Mpf {
+            type Output = Mpf;
+            fn $op(mut self, rhs: $Ty<'_>) -> Mpf {
+                self.$op_assign(rhs);
+                self
+            }
+        }
+
+        impl $Op<$Ty<'_>> for &Mpf {
+            type Output = Mpf;
+            fn $op(self, rhs: $Ty<'_>) -> Mpf {
+                self.$op(Mpf::with_val(self.prec(), rhs))
+            }
+        }
+    )*};
+}
+
+macro_rules! incomplete_rhs_all {
+    ($($Op:ident $op:ident $OpAssign:ident $op_assign:ident;)*) => {$(
+        incomplete_rhs! {
+            $Op $op $OpAssign $op_assign;
+            AddIncomplete SubIncomplete MulIncomplete DivIncomplete
+        }
+    )*};
+}
+
+incomplete_rhs_all! {
+    Add add AddAssign add_assign;
+    Sub sub SubAssign sub_assign;
+    Mul mul MulAssign mul_assign;
+    Div div DivAssign div_assign;
+}
+
+// -------------------------------------------------------------------------------------------------
+// Chaining
+//
+// An incomplete value on the left is evaluated into a new `Mpf`, and the operation then continues
+// in place on that value.
+
+macro_rules! chain {
+    ($Ty:ident $Op:ident $op:ident; $($Rhs:ty)*) => {$(
+        impl $Op<$Rhs> for $Ty<'_> {
+            type Output = Mpf;
+            fn $op(self, rhs: $Rhs) -> Mpf {
+                Mpf::from(self).$op(rhs)
+            }
+        }
+    )*};
+}
+
+macro_rules! chain_all {
+    ($($Ty:ident $Op:ident $op:ident;)*) => {$(
+        chain! {
+            $Ty $Op $op;
+            Mpf &Mpf u8 u16 u32 u64 u128 usize i8 i16 i32 i64 i128 isize
+            AddIncomplete<'_> SubIncomplete<'_> MulIncomplete<'_> DivIncomplete<'_>
+        }
+    )*};
+}
+
+chain_all! {
+    AddIncomplete Add add;
+    AddIncomplete Sub sub;
+    AddIncomplete Mul mul;
+    AddIncomplete Div div;
+    SubIncomplete Add add;
+    SubIncomplete Sub sub;
+    SubIncomplete Mul mul;
+    SubIncomplete Div div;
+    MulIncomplete Add add;
+    MulIncomplete Sub sub;
+    MulIncomplete Mul mul;
+    MulIncomplete Div div;
+    DivIncomplete Add add;
+    DivIncomplete Sub sub;
+    DivIncomplete Mul mul;
+    DivIncomplete Div div;
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn f(v: f64) -> Mpf {
+        Mpf::with_val(128, v)
+    }
+
+    #[test]
+    fn evaluate() {
+        let (a, b) = (f(1.5), f(-0.25));
+        assert_eq!(Mpf::from(&a + &b), 1.25);
+        assert_eq!(Mpf::from(&a - &b), 1.75);
+        assert_eq!(Mpf::from(&a * &b), -0.375);
+        assert_eq!(Mpf::from(&a / &b), -6);
+        assert_eq!(Mpf::from(&a - 2u8), -0.5);
+        assert_eq!(Mpf::from(2u8 - &a), 0.5);
+        assert_eq!(Mpf::from(-3i32 * &a), -4.5);
+        assert_eq!(Mpf::from(3u64 / &a), 2);
+        assert_eq!(Mpf::from(&a / -3i64), -0.5);
+        assert_eq!(&a + i128::MIN - i128::MIN, 1.5);
+    }
+
+    #[test]
+    fn chained() {
+        let (a, b, c) = (f(1.5), f(-0.25), f(4.0));
+        assert_eq!(&a + &b + &c, 5.25);
+        assert_eq!(&a * &c - &b, 6.25);
+        assert_eq!(&a * &c / (&c - &b * 8u8), 1);
+        assert_eq!(&c - &a * &b, 4.375);
+        assert_eq!(c.clone() / (&a - &b * 2u8), 2);
+        let mut x = f(1.0);
+        x += &a * &c;
+        x -= &b / 2u8;
+        assert_eq!(x, 7.125);
+    }
+
+    #[test]
+    fn precision() {
+        let one = Mpf::with_val(64, 1);
+        let three = Mpf::with_val(1024, 3);
+        let third = Mpf::from(&one / &three);
+        assert_eq!(third.prec(), one.prec());
+        assert_eq!(Mpf::from(1u8 / &three).prec(), three.prec());
+        let mut precise = Mpf::new(1024);
+        precise.assign(&one / &three);
+        assert_eq!(precise.prec(), three.prec());
+        assert_ne!(precise, third);
+        assert_eq!(precise, Mpf::with_val(1024, 1u8 / &three));
+        assert_eq!((&one + &three / 7u8).prec(), one.prec());
+        assert_eq!((&three / 7u8 + &one).prec(), three.prec());
+    }
+
+    #[test]
+    fn assign() {
+        let mut x = Mpf::new(64);
+        let limbs = x.0._mp_d;
+        x.assign(-5i64);
+        assert_eq!(x, -5);
+        x.assign(u128::MAX);
+        assert_eq!(x, Mpz::from(u128::MAX));
+        x.assign(&Mpz::from(-7));
+        assert_eq!(x, -7);
+        x.assign(Mpq::from_parts(Mpz::from(1), Mpz::from(4)).unwrap());
+        assert_eq!(x, 0.25);
+        x.assign(0.5f32);
+        assert_eq!(x, 0.5);
+        x.assign(&f(2.0) * &f(3.0));
+        assert_eq!(x, 6);
+        assert_eq!(x.0._mp_d, limbs);
+        x.assign(Mpf::with_val(1024, 3) / 7u8);
+        assert_eq!(x.prec(), Mpf::new(64).prec());
+        x.assign(Mpf::with_val(64, 9));
+        assert_eq!(x, 9);
+    }
+
+    #[test]
+    #[should_panic(expected = "NaN or infinite")]
+    fn assign_nan() {
+        Mpf::new(64).assign(f64::NAN);
+    }
+
+    #[test]
+    #[should_panic(expected = "divide by zero")]
+    fn div_zero() {
+        let (a, b) = (f(1.0), Mpf::new(64));
+        let _ = Mpf::from(&a / &b);
+    }
+}
@@ -0,0 +1,305 @@
+//! Operator overloading for [`Mpf`]
+//!
+//! Operators are implemented for owned and borrowed floats, and for primitive integers on either
+//! side. Where an operand is a primitive that fits in an `mpir_ui`, the `_ui` variant of the MPIR
+//! function is used; negative primitives use the same functions and fix up the sign.
+//!
+//! `&Mpf + x`, `&Mpf - x`, `&Mpf * x` and `&Mpf / x` return incomplete values, see
+//! [`AddIncomplete`](super::AddIncomplete). Every other combination computes its result straight
+//! away in an owned operand, so it has that operand's precision. `&a + b` with an owned `b` still
+//! has the precision of `a`, and only reuses `b` when the precisions are the same.
+//!
+//! Dividing by zero panics.
+
+use std::ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Sub, SubAssign};
+
+use super::*;
+use crate::mpz::ops::{Primitive, Small};
+use crate::Mpz;
+
+/// `z` as an `Mpf` with enough precision to hold it exactly.
+pub(super) fn exact(z: &Mpz) -> Mpf {
+    Mpf::with_val(z.size_in_base(2) as mp_bitcnt_t, z)
+}
+
+// -------------------------------------------------------------------------------------------------
+// Raw operations
+//
+// Each of these writes its result to `rop`, which may alias any mpf operand.
+
+pub(super) fn check_divisor(d: mpf_srcptr) {
+    if unsafe { mpf_sgn(d) } == 0 {
+        panic!("attempt to divide by zero");
+    }
+}
+
+pub(super) unsafe fn div(rop: mpf_ptr, n: mpf_srcptr, d: mpf_srcptr) {
+    check_divisor(d);
+    mpf_div(rop, n, d);
+}
+
+pub(super) unsafe fn add_small(rop: mpf_ptr, op1: mpf_srcptr, op2: &Small) {
+    match op2 {
+        Small::Pos(u) => mpf_add_ui(rop, op1, *u),
+        Small::Neg(u) => mpf_sub_ui(rop, op1, *u),
+        Small::Big(z) => mpf_add(rop, op1, exact(z).as_raw()),
+    }
+}
+
+pub(super) unsafe fn sub_small(rop: mpf_ptr, op1: mpf_srcptr, op2: &Small) {
+    match op2 {
+        Small::Pos(u) => mpf_sub_ui(rop, op1, *u),
+        Small::Neg(u) => mpf_add_ui(rop, op1, *u),
+        Small::Big(z) => mpf_sub(rop, op1, exact(z).as_raw()),
+    }
+}
+
+pub(super) unsafe fn small_sub(rop: mpf_ptr, op1: &Small, op2: mpf_srcptr) {
+    match op1 {
+        Small::Pos(u) => mpf_ui_sub(rop, *u, op2),
+        // -u - x = -(x + u)
+        Small::Neg(u) => {
+            mpf_add_ui(rop, op2, *u);
+            mpf_neg(rop, rop);
+        }
+        Small::Big(z) => mpf_sub(rop, exact(z).as_raw(), op2),
+    }
+}
+
+pub(super) unsafe fn mul_small(rop: mpf_ptr, op1: mpf_srcptr, op2: &Small) {
+    match op2 {
+        Small::Pos(u) => mpf_mul_ui(rop, op1, *u),
+        Small::Neg(u) => {
+            mpf_mul_ui(rop, op1, *u);
+            mpf_neg(rop, rop);
+        }
+        Small::Big(z) => mpf_mul(rop, op1, exact(z).as_raw()),
+    }
+}
+
+pub(super) unsafe fn div_small(rop: mpf_ptr, op1: mpf_srcptr, op2: &Small) {
+    match op2 {
+        Small::Pos(0) => panic!("attempt to divide by zero"),
+        Small::Pos(u) => mpf_div_ui(rop, op1, *u),
+        Small::Neg(u) => {
+            mpf_div_ui(rop, op1, *u);
+            mpf_neg(rop, rop);
+        }
+        Small::Big(z) => mpf_div(rop, op1, exact(z).as_raw()),
+    }
+}
+
+pub(super) unsafe fn small_div(rop: mpf_ptr, op1: &Small, op2: mpf_srcptr) {
+    check_divisor(op2);
+    match op1 {
+        Small::Pos(u) => mpf_ui_div(rop, *u, op2),
+        Small::Neg(u) => {
+            mpf_ui_div(rop, *u, op2);
+            mpf_neg(rop, rop);
+        }
+        Small::Big(z) => mpf_div(rop, exact(z).as_raw(), op2),
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+// Mpf op Mpf
+
+macro_rules! arith_mpf {
+    ($Op:ident $op:ident $OpAssign:ident $op_assign:ident $func:ident) => {
+        impl $Op<Mpf> for Mpf {
+            type Output = Mpf;
+            fn $op(mut self, rhs: Mpf) -> Mpf {
+                self.$op_assign(&rhs);
+                self
+            }
+        }
+
+        impl $Op<&Mpf> for Mpf {
+            type Output = Mpf;
+            fn $op(mut self, rhs: &Mpf) -> Mpf {
+                self.$op_assign(rhs);
+                self
+            }
+        }
+
+        impl $Op<Mpf> for &Mpf {
+            type Output = Mpf;
+            fn $op(self, mut rhs: Mpf) -> Mpf {
+                if rhs.0._mp_prec != self.0._mp_prec {
+                    return Mpf::from(self.$op(&rhs));
+                }
+                let p = rhs.as_raw_mut();
+                unsafe { $func(p, self.as_raw(), p) };
+                rhs
+            }
+        }
+
+        impl $OpAssign<Mpf> for Mpf {
+            fn $op_assign(&mut self, rhs: Mpf) {
+                self.$op_assign(&rhs);
+            }
+        }
+
+        impl $OpAssign<&Mpf> for Mpf {
+            fn $op_assign(&mut self, rhs: &Mpf) {
+                let p = self.as_raw_mut();
+                unsafe { $func(p, p, rhs.as_raw()) };
+            }
+        }
+    };
+}
+
+arith_mpf! { Add add AddAssign add_assign mpf_add }
+arith_mpf! { Sub sub SubAssign sub_assign mpf_sub }
+arith_mpf! { Mul mul MulAssign mul_assign mpf_mul }
+arith_mpf! { Div div DivAssign div_assign div }
+
+// -------------------------------------------------------------------------------------------------
+// Mpf op primitive, primitive op Mpf
+
+macro_rules! arith_prim {
+    ($Op:ident $op:ident $OpAssign:ident $op_assign:ident $func:ident $rfunc:ident; $($t:ty)*) => {$(
+        impl $Op<$t> for Mpf {
+            type Output = Mpf;
+            fn $op(mut self, rhs: $t) -> Mpf {
+                self.$op_assign(rhs);
+                self
+            }
+        }
+
+        impl $Op<Mpf> for $t {
+            type Output = Mpf;
+            fn $op(self, mut rhs: Mpf) -> Mpf {
+                let p = rhs.as_raw_mut();
+                unsafe { $rfunc(p, &self.small(), p) };
+                rhs
+            }
+        }
+
+        impl $OpAssign<$t> for Mpf {
+            fn $op_assign(&mut self, rhs: $t) {
+                let p = self.as_raw_mut();
+                unsafe { $func(p, p, &rhs.small()) };
+            }
+        }
+    )*};
+}
+
+/// `small op x` for the commutative operations.
+unsafe fn small_add(rop: mpf_ptr, op1: &Small, op2: mpf_srcptr) {
+    add_small(rop, op2, op1)
+}
+
+unsafe fn small_mul(rop: mpf_ptr, op1: &Small, op2: mpf_srcptr) {
+    mul_small(rop, op2, op1)
+}
+
+macro_rules! arith_prims {
+    ($($Op:ident $op:ident $OpAssign:ident $op_assign:ident $func:ident $rfunc:ident;)*) => {$(
+        arith_prim! {
+            $Op $op $OpAssign $op_assign $func $rfunc;
+            u8 u16 u32 u64 u128 usize i8 i16 i32 i64 i128 isize
+        }
+    )*};
+}
+
+arith_prims! {
+    Add add AddAssign add_assign add_small small_add;
+    Sub sub SubAssign sub_assign sub_small small_sub;
+    Mul mul MulAssign mul_assign mul_small small_mul;
+    Div div DivAssign div_assign div_small small_div;
+}
+
+// -------------------------------------------------------------------------------------------------
+// Unary operators
+
+impl Neg for Mpf {
+    type Output = Mpf;
+    fn neg(mut self) -> Mpf {
+        let p = self.as_raw_mut();
+        unsafe { mpf_neg(p, p) };
+        self
+    }
+}
+
+impl Neg for &Mpf {
+    type Output = Mpf;
+    fn neg(self) -> Mpf {
+        self.map(|r, x| unsafe { mpf_neg(r, x) })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn f(v: f64) -> Mpf {
+        Mpf::with_val(128, v)
+    }
+
+    #[test]
+    fn mpf_mpf() {
+        let (a, b) = (f(1.5), f(-0.25));
+        assert_eq!(a.clone() + b.clone(), 1.25);
+        assert_eq!(a.clone() - &b, 1.75);
+        assert_eq!(&a * b.clone(), -0.375);
+        assert_eq!(&a / Mpf::with_val(512, -0.25), -6);
+        assert_eq!(-&a, -1.5);
+        assert_eq!(-b, 0.25);
+    }
+
+    #[test]
+    fn primitives() {
+        let a = f(1.5);
+        assert_eq!(a.clone() + 2u8, 3.5);
+        assert_eq!(a.clone() + -2i64, -0.5);
+        assert_eq!(a.clone() - -2i32, 3.5);
+        assert_eq!(a.clone() * -3i16, -4.5);
+        assert_eq!(a.clone() / -3i8, -0.5);
+        assert_eq!(2u32 - a.clone(), 0.5);
+        assert_eq!(-2i32 - a.clone(), -3.5);
+        assert_eq!(-3i64 / a.clone(), -2);
+        assert_eq!(u128::MAX * f(0.5) * 2u8, Mpz::from(u128::MAX));
+        assert_eq!(a.clone() - i128::MIN + i128::MIN, 1.5);
+    }
+
+    #[test]
+    fn assign_ops() {
+        let mut x = f(1.0);
+        x += f(0.5);
+        x -= &f(0.25);
+        x *= 4u8;
+        x /= -2i32;
+        assert_eq!(x, -2.5);
+        x /= f(0.5);
+        assert_eq!(x, -5);
+    }
+
+    #[test]
+    fn precision() {
+        let a = Mpf::with_val(64, 1);
+        let b = Mpf::with_val(512, 3);
+        assert_eq!((a.clone() / &b).prec(), a.prec());
+        assert_eq!((&a / b.clone()).prec(), a.prec());
+        assert_eq!((1u8 / b.clone()).prec(), b.prec());
+        assert_eq!((b.clone() / 7u8).prec(), b.prec());
+    }
+
+    #[test]
+    #[should_panic(expected = "divide by zero")]
+    fn div_zero() {
+        let _ = f(1.0) / Mpf::new(64);
+    }
+
+    #[test]
+    #[should_panic(expected = "divide by zero")]
+    fn div_zero_primitive() {
+        let _ = f(1.0) / 0u8;
+    }
+
+    #[test]
+    #[should_panic(expected = "divide by zero")]
+    fn primitive_div_zero() {
+        let _ = 1u8 / Mpf::new(64);
+    }
+}
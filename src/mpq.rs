@@ -0,0 +1,576 @@
+//! Rational Number
+//!
+//! [MPIR 3.0.0 - C documentation](https://mpir.org/mpir-3.0.0.pdf)
+//!
+//! Rational numbers are stored as a numerator and denominator in canonical form: the denominator is
+//! positive and shares no factors with the numerator, and zero is `0/1`. All of the MPIR functions
+//! except `mpq_set_num`, `mpq_set_den`, `mpq_set_ui`, `mpq_set_si` and direct writes to the parts
+//! expect canonical operands and return canonical results, so [`Mpq`] canonicalizes whenever a
+//! value is built from parts.
+
+use std::mem::{ManuallyDrop, MaybeUninit};
+use std::ptr;
+
+use crate::ctype::{
+    c_char, c_double, c_int, c_long, c_ulong, mp_bitcnt_t, mpf_srcptr, mpq_ptr, mpq_srcptr,
+    mpq_struct, mpz_ptr, mpz_srcptr, mpz_struct,
+};
+use crate::{MpirError, Mpz, Sign};
+
+mod cmp;
+mod convert;
+mod fmt;
+mod ops;
+
+pub use convert::TryFromFloatError;
+pub use fmt::{ParseMpqError, ParseMpqErrorKind};
+
+/* As for mpz, the symbols are named with link_name, and the bindings use the C ABI. */
+extern "C" {
+
+    // ---------------------------------------------------------------------------------------------
+    // Canonicalization
+
+    /// Remove any factors that are common to the numerator and denominator of op, and make the
+    /// denominator positive.
+    #[link_name = "__gmpq_canonicalize"]
+    pub fn mpq_canonicalize(op: mpq_ptr);
+
+    // ---------------------------------------------------------------------------------------------
+    // Initialization and Assignment Functions
+
+    /// Initialize x and set it to 0/1. Each variable should normally only be initialized once, or
+    /// at least cleared out (using the function mpq_clear) between each initialization.
+    #[link_name = "__gmpq_init"]
+    pub fn mpq_init(x: mpq_ptr);
+
+    /// Free the space occupied by x. Make sure to call this function for all mpq_t variables when
+    /// you are done with them.
+    #[link_name = "__gmpq_clear"]
+    pub fn mpq_clear(x: mpq_ptr);
+
+    /// Assign rop from op.
+    #[link_name = "__gmpq_set"]
+    pub fn mpq_set(rop: mpq_ptr, op: mpq_srcptr);
+
+    /// Assign rop from op.
+    #[link_name = "__gmpq_set_z"]
+    pub fn mpq_set_z(rop: mpq_ptr, op: mpz_srcptr);
+
+    /// Set the value of rop to op1/op2. Note that if op1 and op2 have common factors, rop has to be
+    /// passed to mpq_canonicalize before any operations are performed on rop.
+    #[link_name = "__gmpq_set_ui"]
+    pub fn mpq_set_ui(rop: mpq_ptr, op1: c_ulong, op2: c_ulong);
+
+    /// Set the value of rop to op1/op2. Note that if op1 and op2 have common factors, rop has to be
+    /// passed to mpq_canonicalize before any operations are performed on rop.
+    #[link_name = "__gmpq_set_si"]
+    pub fn mpq_set_si(rop: mpq_ptr, op1: c_long, op2: c_ulong);
+
+    /// Set rop from a null-terminated string str in the given base.
+    ///
+    /// The string can be an integer like "41" or a fraction like "41/152". The fraction must be in
+    /// canonical form (see Chapter 6 [Rational Number Functions], page 49), or if not then
+    /// mpq_canonicalize must be called.
+    ///
+    /// The numerator and optional denominator are parsed the same as in mpz_set_str (see Section
+    /// 5.2 [Assigning Integers], page 29). White space is allowed in the string, and is simply
+    /// ignored. The base can vary from 2 to 62, or if base is 0 then the leading characters are
+    /// used: 0x or 0X for hex, 0b or 0B for binary, 0 for octal, or decimal otherwise. Note that
+    /// this is done separately for the numerator and denominator, so for instance 0xEF/100 is 239/100,
+    /// whereas 0xEF/0x100 is 239/256.
+    ///
+    /// The return value is 0 if the entire string is a valid number, or −1 if not.
+    #[link_name = "__gmpq_set_str"]
+    pub fn mpq_set_str(rop: mpq_ptr, s: *const c_char, base: c_int) -> c_int;
+
+    /// Swap the values rop1 and rop2 efficiently.
+    #[link_name = "__gmpq_swap"]
+    pub fn mpq_swap(rop1: mpq_ptr, rop2: mpq_ptr);
+
+    // ---------------------------------------------------------------------------------------------
+    // Conversion Functions
+
+    /// Convert op to a double, truncating if necessary (ie. rounding towards zero).
+    ///
+    /// If the exponent from the conversion is too big or too small to fit a double then the
+    /// result is system dependent. For too big an infinity is returned when available. For too
+    /// small 0.0 is normally returned. Hardware overflow, underflow and denorm traps may or may not
+    /// occur.
+    #[link_name = "__gmpq_get_d"]
+    pub fn mpq_get_d(op: mpq_srcptr) -> c_double;
+
+    /// Set rop to the value of op. There is no rounding, this conversion is exact.
+    #[link_name = "__gmpq_set_d"]
+    pub fn mpq_set_d(rop: mpq_ptr, op: c_double);
+
+    /// Set rop to the value of op. There is no rounding, this conversion is exact.
+    #[link_name = "__gmpq_set_f"]
+    pub fn mpq_set_f(rop: mpq_ptr, op: mpf_srcptr);
+
+    /// Convert op to a string of digits in base base. The base may vary from 2 to 36. The string
+    /// will be of the form "num/den", or if the denominator is 1 then just "num".
+    ///
+    /// If str is NULL, the result string is allocated using the current allocation function (see
+    /// Chapter 13 [Custom Allocation], page 86). The block will be strlen(str)+1 bytes, that being
+    /// exactly enough for the string and null-terminator.
+    ///
+    /// If str is not NULL, it should point to a block of storage large enough for the result, that
+    /// being
+    ///
+    /// mpz_sizeinbase (mpq_numref(op), base) + mpz_sizeinbase (mpq_denref(op), base) + 3
+    ///
+    /// The three extra bytes are for a possible minus sign, possible slash, and the
+    /// null-terminator.
+    ///
+    /// A pointer to the result string is returned, being either the allocated block, or the given
+    /// str.
+    #[link_name = "__gmpq_get_str"]
+    pub fn mpq_get_str(s: *mut c_char, base: c_int, op: mpq_srcptr) -> *mut c_char;
+
+    // ---------------------------------------------------------------------------------------------
+    // Arithmetic Functions
+
+    /// Set sum to addend1 + addend2.
+    #[link_name = "__gmpq_add"]
+    pub fn mpq_add(sum: mpq_ptr, addend1: mpq_srcptr, addend2: mpq_srcptr);
+
+    /// Set difference to minuend − subtrahend.
+    #[link_name = "__gmpq_sub"]
+    pub fn mpq_sub(difference: mpq_ptr, minuend: mpq_srcptr, subtrahend: mpq_srcptr);
+
+    /// Set product to multiplier × multiplicand.
+    #[link_name = "__gmpq_mul"]
+    pub fn mpq_mul(product: mpq_ptr, multiplier: mpq_srcptr, multiplicand: mpq_srcptr);
+
+    /// Set rop to op1 × 2^op2.
+    #[link_name = "__gmpq_mul_2exp"]
+    pub fn mpq_mul_2exp(rop: mpq_ptr, op1: mpq_srcptr, op2: mp_bitcnt_t);
+
+    /// Set quotient to dividend/divisor.
+    ///
+    /// Division by zero is not allowed.
+    #[link_name = "__gmpq_div"]
+    pub fn mpq_div(quotient: mpq_ptr, dividend: mpq_srcptr, divisor: mpq_srcptr);
+
+    /// Set rop to op1/2^op2.
+    #[link_name = "__gmpq_div_2exp"]
+    pub fn mpq_div_2exp(rop: mpq_ptr, op1: mpq_srcptr, op2: mp_bitcnt_t);
+
+    /// Set negated_operand to −operand.
+    #[link_name = "__gmpq_neg"]
+    pub fn mpq_neg(negated_operand: mpq_ptr, operand: mpq_srcptr);
+
+    /// Set rop to the absolute value of op.
+    #[link_name = "__gmpq_abs"]
+    pub fn mpq_abs(rop: mpq_ptr, op: mpq_srcptr);
+
+    /// Set inverted_number to 1/number. If the new denominator is zero, this routine will divide by
+    /// zero.
+    #[link_name = "__gmpq_inv"]
+    pub fn mpq_inv(inverted_number: mpq_ptr, number: mpq_srcptr);
+
+    // ---------------------------------------------------------------------------------------------
+    // Comparison Functions
+
+    /// Compare op1 and op2. Return a positive value if op1 > op2, zero if op1 = op2, and a negative
+    /// value if op1 < op2.
+    ///
+    /// To determine if two rationals are equal, mpq_equal is faster than mpq_cmp.
+    #[link_name = "__gmpq_cmp"]
+    pub fn mpq_cmp(op1: mpq_srcptr, op2: mpq_srcptr) -> c_int;
+
+    /// Compare op1 and num2/den2. Return a positive value if op1 > num2/den2, zero if
+    /// op1 = num2/den2, and a negative value if op1 < num2/den2.
+    ///
+    /// num2 and den2 are allowed to have common factors.
+    #[link_name = "__gmpq_cmp_ui"]
+    pub fn mpq_cmp_ui(op1: mpq_srcptr, num2: c_ulong, den2: c_ulong) -> c_int;
+
+    /// Compare op1 and num2/den2. Return a positive value if op1 > num2/den2, zero if
+    /// op1 = num2/den2, and a negative value if op1 < num2/den2.
+    ///
+    /// num2 and den2 are allowed to have common factors.
+    #[link_name = "__gmpq_cmp_si"]
+    pub fn mpq_cmp_si(op1: mpq_srcptr, num2: c_long, den2: c_ulong) -> c_int;
+
+    // mpq_sgn is implemented in Rust, see below.
+
+    /// Return non-zero if op1 and op2 are equal, zero if they are non-equal. Although mpq_cmp can
+    /// be used for the same purpose, this function is much faster.
+    #[link_name = "__gmpq_equal"]
+    pub fn mpq_equal(op1: mpq_srcptr, op2: mpq_srcptr) -> c_int;
+
+    // ---------------------------------------------------------------------------------------------
+    // Applying Integer Functions to Rationals
+
+    // mpq_numref and mpq_denref are implemented in Rust, see below.
+
+    /// Set numerator to the numerator of rational.
+    #[link_name = "__gmpq_get_num"]
+    pub fn mpq_get_num(numerator: mpz_ptr, rational: mpq_srcptr);
+
+    /// Set denominator to the denominator of rational.
+    #[link_name = "__gmpq_get_den"]
+    pub fn mpq_get_den(denominator: mpz_ptr, rational: mpq_srcptr);
+
+    /// Set the numerator of rational to numerator.
+    ///
+    /// If the new value has common factors with the denominator, mpq_canonicalize must be called
+    /// before any further operations.
+    #[link_name = "__gmpq_set_num"]
+    pub fn mpq_set_num(rational: mpq_ptr, numerator: mpz_srcptr);
+
+    /// Set the denominator of rational to denominator.
+    ///
+    /// If the new value is not positive or has common factors with the numerator,
+    /// mpq_canonicalize must be called before any further operations.
+    #[link_name = "__gmpq_set_den"]
+    pub fn mpq_set_den(rational: mpq_ptr, denominator: mpz_srcptr);
+}
+
+/// Return +1 if op > 0, 0 if op = 0, and −1 if op < 0.
+///
+/// # Safety
+///
+/// `op` must point to an initialised, canonical `mpq_struct`.
+pub unsafe fn mpq_sgn(op: mpq_srcptr) -> c_int {
+    (*op)._mp_num._mp_size.signum()
+}
+
+/// Return a reference to the numerator of op. The mpz functions can be used on the result of
+/// this macro.
+///
+/// # Safety
+///
+/// `op` must point to an initialised `mpq_struct`.
+pub unsafe fn mpq_numref(op: mpq_ptr) -> mpz_ptr {
+    ptr::addr_of_mut!((*op)._mp_num)
+}
+
+/// Return a reference to the denominator of op. The mpz functions can be used on the result of
+/// this macro.
+///
+/// # Safety
+///
+/// `op` must point to an initialised `mpq_struct`.
+pub unsafe fn mpq_denref(op: mpq_ptr) -> mpz_ptr {
+    ptr::addr_of_mut!((*op)._mp_den)
+}
+
+/// Arbitrary precision rational number.
+///
+/// Owns an initialised `mpq_struct` in canonical form, which is cleared when the value is dropped.
+/// A failed allocation aborts the process, as for [`Mpz`].
+pub struct Mpq(mpq_struct);
+
+// As for Mpz, the storage is uniquely owned and MPIR never mutates through a `mpq_srcptr`.
+unsafe impl Send for Mpq {}
+unsafe impl Sync for Mpq {}
+
+impl Mpq {
+    /// Create a new rational with the value 0.
+    pub fn new() -> Self {
+        unsafe { Self::from_init(|x| mpq_init(x)) }
+    }
+
+    /// Create the rational `num/den`, in lowest terms with a positive denominator.
+    ///
+    /// Returns [`MpirError::DivisionByZero`] if `den` is 0.
+    pub fn from_parts(num: Mpz, den: Mpz) -> Result<Self, MpirError> {
+        if den.is_zero() {
+            return Err(MpirError::DivisionByZero);
+        }
+        let (num, den) = (ManuallyDrop::new(num), ManuallyDrop::new(den));
+        let mut q = unsafe {
+            Mpq(mpq_struct {
+                _mp_num: ptr::read(num.as_raw()),
+                _mp_den: ptr::read(den.as_raw()),
+            })
+        };
+        unsafe { mpq_canonicalize(q.as_raw_mut()) };
+        Ok(q)
+    }
+
+    /// Split into the numerator and the denominator, which is positive.
+    pub fn into_parts(self) -> (Mpz, Mpz) {
+        let q = ManuallyDrop::new(self);
+        unsafe {
+            (
+                Mpz::from_init(|x| x.write(ptr::read(&q.0._mp_num))),
+                Mpz::from_init(|x| x.write(ptr::read(&q.0._mp_den))),
+            )
+        }
+    }
+
+    /// The numerator, which carries the sign.
+    pub fn numer(&self) -> &Mpz {
+        unsafe { &*(&self.0._mp_num as *const mpz_struct as *const Mpz) }
+    }
+
+    /// The denominator, which is always positive.
+    pub fn denom(&self) -> &Mpz {
+        unsafe { &*(&self.0._mp_den as *const mpz_struct as *const Mpz) }
+    }
+
+    /// Pointer to the underlying `mpq_struct`, for passing as a source operand to MPIR.
+    pub fn as_raw(&self) -> mpq_srcptr {
+        &self.0
+    }
+
+    /// Mutable pointer to the underlying `mpq_struct`, for passing as an output operand to MPIR.
+    ///
+    /// The value must be left in canonical form.
+    pub fn as_raw_mut(&mut self) -> mpq_ptr {
+        &mut self.0
+    }
+
+    /// Return the sign of the rational.
+    pub fn sign(&self) -> Sign {
+        self.numer().sign()
+    }
+
+    /// Return `true` if the rational is zero.
+    pub fn is_zero(&self) -> bool {
+        self.numer().is_zero()
+    }
+
+    /// Return `true` if the rational is greater than zero.
+    pub fn is_positive(&self) -> bool {
+        self.numer().is_positive()
+    }
+
+    /// Return `true` if the rational is less than zero.
+    pub fn is_negative(&self) -> bool {
+        self.numer().is_negative()
+    }
+
+    /// Return `true` if the denominator is 1.
+    pub fn is_integer(&self) -> bool {
+        *self.denom() == 1
+    }
+
+    /// The absolute value.
+    pub fn abs(&self) -> Mpq {
+        let mut r = Mpq::new();
+        unsafe { mpq_abs(r.as_raw_mut(), self.as_raw()) };
+        r
+    }
+
+    /// The reciprocal `1/self`.
+    ///
+    /// MPIR divides by zero when `self` is 0, so this returns [`MpirError::DivisionByZero`]
+    /// instead.
+    pub fn recip(&self) -> Result<Mpq, MpirError> {
+        if self.is_zero() {
+            return Err(MpirError::DivisionByZero);
+        }
+        let mut r = Mpq::new();
+        unsafe { mpq_inv(r.as_raw_mut(), self.as_raw()) };
+        Ok(r)
+    }
+
+    /// The integer part, rounding towards zero.
+    pub fn trunc(&self) -> Mpz {
+        let mut r = Mpz::new();
+        unsafe { crate::mpz::mpz_set_q(r.as_raw_mut(), self.as_raw()) };
+        r
+    }
+
+    /// The largest integer less than or equal to the rational.
+    pub fn floor(&self) -> Mpz {
+        let mut r = Mpz::new();
+        unsafe {
+            crate::mpz::mpz_fdiv_q(r.as_raw_mut(), self.numer().as_raw(), self.denom().as_raw())
+        };
+        r
+    }
+
+    /// The smallest integer greater than or equal to the rational.
+    pub fn ceil(&self) -> Mpz {
+        let mut r = Mpz::new();
+        unsafe {
+            crate::mpz::mpz_cdiv_q(r.as_raw_mut(), self.numer().as_raw(), self.denom().as_raw())
+        };
+        r
+    }
+
+    /// Run `mpq_init` on fresh storage, then `set` on the result, and take ownership of it.
+    ///
+    /// # Safety
+    ///
+    /// `set` must leave the `mpq_struct` in canonical form.
+    pub(crate) unsafe fn from_init<F: FnOnce(mpq_ptr)>(set: F) -> Self {
+        crate::alloc::init();
+        let mut x = MaybeUninit::<mpq_struct>::uninit();
+        mpq_init(x.as_mut_ptr());
+        set(x.as_mut_ptr());
+        Mpq(x.assume_init())
+    }
+}
+
+impl Default for Mpq {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Clone for Mpq {
+    fn clone(&self) -> Self {
+        unsafe { Self::from_init(|x| mpq_set(x, self.as_raw())) }
+    }
+
+    fn clone_from(&mut self, source: &Self) {
+        unsafe { mpq_set(self.as_raw_mut(), source.as_raw()) }
+    }
+}
+
+impl Drop for Mpq {
+    fn drop(&mut self) {
+        unsafe { mpq_clear(self.as_raw_mut()) }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::ffi::{CStr, CString};
+
+    fn q(num: i64, den: i64) -> Mpq {
+        Mpq::from_parts(Mpz::from(num), Mpz::from(den)).unwrap()
+    }
+
+    fn raw<F: FnOnce(mpq_ptr)>(f: F) -> Mpq {
+        let mut r = Mpq::new();
+        f(r.as_raw_mut());
+        r
+    }
+
+    #[test]
+    fn new() {
+        let x = Mpq::new();
+        assert!(x.is_zero());
+        assert_eq!(*x.numer(), 0);
+        assert_eq!(*x.denom(), 1);
+        assert_eq!(Mpq::default(), x);
+    }
+
+    #[test]
+    fn parts() {
+        let x = q(6, -4);
+        assert_eq!(*x.numer(), -3);
+        assert_eq!(*x.denom(), 2);
+        assert_eq!(x.sign(), Sign::Negative);
+        let (num, den) = x.clone().into_parts();
+        assert_eq!((num, den), (Mpz::from(-3), Mpz::from(2)));
+        assert_eq!(q(0, -5), Mpq::new());
+        assert!(q(10, 5).is_integer());
+        assert_eq!(
+            Mpq::from_parts(Mpz::from(1), Mpz::new()),
+            Err(MpirError::DivisionByZero)
+        );
+    }
+
+    #[test]
+    fn methods() {
+        let x = q(-7, 2);
+        assert_eq!(x.abs(), q(7, 2));
+        assert_eq!(x.recip(), Ok(q(-2, 7)));
+        assert_eq!(Mpq::new().recip(), Err(MpirError::DivisionByZero));
+        assert_eq!(x.trunc(), -3);
+        assert_eq!(x.floor(), -4);
+        assert_eq!(x.ceil(), -3);
+        assert_eq!(q(7, 2).floor(), 3);
+        assert_eq!(q(7, 2).ceil(), 4);
+        assert_eq!(q(4, 1).floor(), 4);
+        assert_eq!(q(4, 1).ceil(), 4);
+        assert!(q(1, 3).is_positive() && !q(1, 3).is_negative());
+    }
+
+    #[test]
+    fn ffi_assignment() {
+        unsafe {
+            let mut x = raw(|r| mpq_set_si(r, -6, 4));
+            assert_eq!(*x.numer(), -6);
+            mpq_canonicalize(x.as_raw_mut());
+            assert_eq!(x, q(-3, 2));
+            assert_eq!(raw(|r| mpq_set_ui(r, 3, 4)), q(3, 4));
+            assert_eq!(raw(|r| mpq_set(r, x.as_raw())), x);
+            assert_eq!(raw(|r| mpq_set_z(r, Mpz::from(5).as_raw())), q(5, 1));
+            let s = CString::new("-10/4").unwrap();
+            let mut y = Mpq::new();
+            assert_eq!(mpq_set_str(y.as_raw_mut(), s.as_ptr(), 10), 0);
+            mpq_canonicalize(y.as_raw_mut());
+            assert_eq!(y, q(-5, 2));
+            let s = CString::new("1/x").unwrap();
+            assert_eq!(mpq_set_str(Mpq::new().as_raw_mut(), s.as_ptr(), 10), -1);
+            mpq_swap(x.as_raw_mut(), y.as_raw_mut());
+            assert_eq!((x, y), (q(-5, 2), q(-3, 2)));
+        }
+    }
+
+    #[test]
+    fn ffi_conversion() {
+        unsafe {
+            assert_eq!(mpq_get_d(q(-1, 4).as_raw()), -0.25);
+            assert_eq!(raw(|r| mpq_set_d(r, 0.375)), q(3, 8));
+            let ptr = mpq_get_str(std::ptr::null_mut(), 16, q(-255, 16).as_raw());
+            let s = CStr::from_ptr(ptr);
+            assert_eq!(s.to_str(), Ok("-ff/10"));
+            crate::alloc::free(ptr as *mut _, s.to_bytes_with_nul().len());
+        }
+    }
+
+    #[test]
+    fn ffi_arithmetic() {
+        unsafe {
+            let (a, b) = (q(1, 2), q(-1, 3));
+            assert_eq!(raw(|r| mpq_add(r, a.as_raw(), b.as_raw())), q(1, 6));
+            assert_eq!(raw(|r| mpq_sub(r, a.as_raw(), b.as_raw())), q(5, 6));
+            assert_eq!(raw(|r| mpq_mul(r, a.as_raw(), b.as_raw())), q(-1, 6));
+            assert_eq!(raw(|r| mpq_div(r, a.as_raw(), b.as_raw())), q(-3, 2));
+            assert_eq!(raw(|r| mpq_mul_2exp(r, b.as_raw(), 3)), q(-8, 3));
+            assert_eq!(raw(|r| mpq_div_2exp(r, a.as_raw(), 2)), q(1, 8));
+            assert_eq!(raw(|r| mpq_neg(r, b.as_raw())), q(1, 3));
+            assert_eq!(raw(|r| mpq_abs(r, b.as_raw())), q(1, 3));
+            assert_eq!(raw(|r| mpq_inv(r, b.as_raw())), q(-3, 1));
+        }
+    }
+
+    #[test]
+    fn ffi_comparison() {
+        unsafe {
+            let (a, b) = (q(1, 2), q(-1, 3));
+            assert!(mpq_cmp(a.as_raw(), b.as_raw()) > 0);
+            assert!(mpq_cmp_ui(a.as_raw(), 2, 4) == 0);
+            assert!(mpq_cmp_si(b.as_raw(), -1, 2) > 0);
+            assert_eq!(mpq_sgn(b.as_raw()), -1);
+            assert_eq!(mpq_sgn(Mpq::new().as_raw()), 0);
+            assert_ne!(mpq_equal(a.as_raw(), q(2, 4).as_raw()), 0);
+            assert_eq!(mpq_equal(a.as_raw(), b.as_raw()), 0);
+        }
+    }
+
+    #[test]
+    fn ffi_integer_functions() {
+        unsafe {
+            let mut x = q(3, 4);
+            let mut z = Mpz::new();
+            mpq_get_num(z.as_raw_mut(), x.as_raw());
+            assert_eq!(z, 3);
+            mpq_get_den(z.as_raw_mut(), x.as_raw());
+            assert_eq!(z, 4);
+            mpq_set_num(x.as_raw_mut(), Mpz::from(6).as_raw());
+            mpq_set_den(x.as_raw_mut(), Mpz::from(-8).as_raw());
+            mpq_canonicalize(x.as_raw_mut());
+            assert_eq!(x, q(-3, 4));
+            crate::mpz::mpz_mul_ui(mpq_numref(x.as_raw_mut()), mpq_numref(x.as_raw_mut()), 5);
+            assert_eq!(x, q(-15, 4));
+            crate::mpz::mpz_set_ui(mpq_denref(x.as_raw_mut()), 7);
+            assert_eq!(x, q(-15, 7));
+            let mut t = Mpz::new();
+            crate::mpz::mpz_set_q(t.as_raw_mut(), x.as_raw());
+            assert_eq!(t, -2);
+        }
+    }
+}
@@ -0,0 +1,198 @@
+//! Comparison and hashing for [`Mpq`]
+//!
+//! Rationals are totally ordered by `mpq_cmp`, and tested for equality with the faster
+//! `mpq_equal`. They compare with [`Mpz`] and the primitive integer types on either side of the
+//! operator, through `mpq_cmp_ui` / `mpq_cmp_si` where the primitive fits.
+//!
+//! Being canonical, equal rationals have the same numerator and denominator, which are hashed.
+
+use std::cmp::Ordering;
+use std::hash::{Hash, Hasher};
+
+use super::*;
+use crate::mpz::ops::{Primitive, Small};
+
+/// Compare `q` with an integer.
+fn cmp_mpz(q: &Mpq, z: &Mpz) -> Ordering {
+    if *q.denom() == 1 {
+        return q.numer().cmp(z);
+    }
+    // Compare num with z * den, as den is positive.
+    let mut t = Mpz::new();
+    unsafe { crate::mpz::mpz_mul(t.as_raw_mut(), z.as_raw(), q.denom().as_raw()) };
+    q.numer().cmp(&t)
+}
+
+/// Compare `q` with a primitive operand.
+fn cmp_small(q: &Mpq, v: Small) -> Ordering {
+    let c = match v {
+        Small::Pos(u) => unsafe { mpq_cmp_ui(q.as_raw(), u, 1) },
+        Small::Neg(u) if u <= c_long::MIN.unsigned_abs() => unsafe {
+            mpq_cmp_si(q.as_raw(), (u as c_long).wrapping_neg(), 1)
+        },
+        v => return cmp_mpz(q, &v.into_mpz()),
+    };
+    c.cmp(&0)
+}
+
+impl Mpq {
+    /// Compare the absolute values of `self` and `other`.
+    pub fn cmp_abs(&self, other: &Mpq) -> Ordering {
+        let mut a = Mpz::new();
+        let mut b = Mpz::new();
+        unsafe {
+            crate::mpz::mpz_mul(
+                a.as_raw_mut(),
+                self.numer().as_raw(),
+                other.denom().as_raw(),
+            );
+            crate::mpz::mpz_mul(
+                b.as_raw_mut(),
+                other.numer().as_raw(),
+                self.denom().as_raw(),
+            );
+        }
+        a.cmp_abs(&b)
+    }
+}
+
+impl PartialEq for Mpq {
+    fn eq(&self, other: &Mpq) -> bool {
+        unsafe { mpq_equal(self.as_raw(), other.as_raw()) != 0 }
+    }
+}
+
+impl Eq for Mpq {}
+
+impl PartialOrd for Mpq {
+    fn partial_cmp(&self, other: &Mpq) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Mpq {
+    fn cmp(&self, other: &Mpq) -> Ordering {
+        unsafe { mpq_cmp(self.as_raw(), other.as_raw()) }.cmp(&0)
+    }
+}
+
+impl Hash for Mpq {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.numer().hash(state);
+        self.denom().hash(state);
+    }
+}
+
+impl PartialEq<Mpz> for Mpq {
+    fn eq(&self, other: &Mpz) -> bool {
+        self.is_integer() && self.numer() == other
+    }
+}
+
+impl PartialEq<Mpq> for Mpz {
+    fn eq(&self, other: &Mpq) -> bool {
+        other == self
+    }
+}
+
+impl PartialOrd<Mpz> for Mpq {
+    fn partial_cmp(&self, other: &Mpz) -> Option<Ordering> {
+        Some(cmp_mpz(self, other))
+    }
+}
+
+impl PartialOrd<Mpq> for Mpz {
+    fn partial_cmp(&self, other: &Mpq) -> Option<Ordering> {
+        Some(cmp_mpz(other, self).reverse())
+    }
+}
+
+macro_rules! cmp_prim {
+    ($($t:ty)*) => {$(
+        impl PartialEq<$t> for Mpq {
+            fn eq(&self, other: &$t) -> bool {
+                cmp_small(self, other.small()) == Ordering::Equal
+            }
+        }
+
+        impl PartialEq<Mpq> for $t {
+            fn eq(&self, other: &Mpq) -> bool {
+                other == self
+            }
+        }
+
+        impl PartialOrd<$t> for Mpq {
+            fn partial_cmp(&self, other: &$t) -> Option<Ordering> {
+                Some(cmp_small(self, other.small()))
+            }
+        }
+
+        impl PartialOrd<Mpq> for $t {
+            fn partial_cmp(&self, other: &Mpq) -> Option<Ordering> {
+                Some(cmp_small(other, self.small()).reverse())
+            }
+        }
+    )*};
+}
+
+cmp_prim! { u8 u16 u32 u64 u128 usize i8 i16 i32 i64 i128 isize }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::collections::hash_map::DefaultHasher;
+    use std::collections::BTreeSet;
+
+    fn q(num: i64, den: i64) -> Mpq {
+        Mpq::from_parts(Mpz::from(num), Mpz::from(den)).unwrap()
+    }
+
+    fn hash(x: &Mpq) -> u64 {
+        let mut h = DefaultHasher::new();
+        x.hash(&mut h);
+        h.finish()
+    }
+
+    #[test]
+    fn ord() {
+        assert_eq!(q(1, 2), q(2, 4));
+        assert_ne!(q(1, 2), q(-1, 2));
+        assert!(q(1, 3) < q(1, 2));
+        assert!(q(-1, 2) < q(-1, 3));
+        let set: BTreeSet<Mpq> = [q(1, 2), q(-3, 4), q(2, 4), q(0, 1)].into_iter().collect();
+        assert_eq!(
+            set.into_iter().collect::<Vec<_>>(),
+            [q(-3, 4), q(0, 1), q(1, 2)]
+        );
+        assert_eq!(hash(&q(3, 6)), hash(&q(1, 2)));
+        assert_ne!(hash(&q(1, 2)), hash(&q(-1, 2)));
+    }
+
+    #[test]
+    fn integers() {
+        let x = q(7, 2);
+        let (three, four) = (Mpz::from(3), Mpz::from(4));
+        assert!(x > three && x < four);
+        assert!(four > x);
+        assert_ne!(x, three);
+        assert_eq!(q(8, 2), Mpz::from(4));
+        assert_eq!(Mpz::from(4), q(8, 2));
+        assert!(q(-7, 2) < -three);
+        assert!(x > 3 && x < 4u8);
+        assert!(3i64 < x);
+        assert_eq!(q(-6, 2), -3);
+        assert_eq!(-3i32, q(-6, 2));
+        assert!(q(-1, 2) > i64::MIN);
+        assert!(q(-1, 2) > i128::MIN);
+        assert!(q(1, 2) < u128::MAX);
+        let big = Mpq::from_parts(Mpz::from(u128::MAX) * 2u32 + 1u32, Mpz::from(2)).unwrap();
+        assert!(big > u128::MAX);
+    }
+
+    #[test]
+    fn cmp_abs() {
+        assert_eq!(q(-3, 4).cmp_abs(&q(1, 2)), Ordering::Greater);
+        assert_eq!(q(-1, 2).cmp_abs(&q(2, 4)), Ordering::Equal);
+        assert_eq!(q(1, 3).cmp_abs(&q(-1, 2)), Ordering::Less);
+    }
+}
@@ -0,0 +1,113 @@
+//! Conversions between [`Mpq`] and integers and floats
+//!
+//! Every [`Mpz`] and primitive integer converts into an `Mpq` with `From`. Finite floats convert
+//! exactly with `TryFrom`, since every float is a rational with a power of two denominator.
+
+use std::error::Error;
+use std::fmt;
+
+use super::*;
+use crate::mpz::ops::{Primitive, Small};
+
+impl Mpq {
+    /// Convert to an `f64`, truncating if necessary (i.e. rounding towards zero).
+    ///
+    /// If the value is too large for an `f64`, the result is system dependent, usually infinity.
+    pub fn to_f64(&self) -> f64 {
+        unsafe { mpq_get_d(self.as_raw()) }
+    }
+}
+
+impl From<&Mpz> for Mpq {
+    fn from(z: &Mpz) -> Self {
+        unsafe { Mpq::from_init(|x| mpq_set_z(x, z.as_raw())) }
+    }
+}
+
+impl From<Mpz> for Mpq {
+    /// Take the integer as the numerator, reusing its allocation.
+    fn from(z: Mpz) -> Self {
+        Mpq::from_parts(z, Mpz::from(1)).unwrap()
+    }
+}
+
+macro_rules! from_primitive {
+    ($($t:ty)*) => {$(
+        impl From<$t> for Mpq {
+            fn from(v: $t) -> Self {
+                match v.small() {
+                    Small::Pos(u) => unsafe { Mpq::from_init(|x| mpq_set_ui(x, u, 1)) },
+                    v => Mpq::from(v.into_mpz()),
+                }
+            }
+        }
+    )*};
+}
+
+from_primitive! { u8 u16 u32 u64 u128 usize i8 i16 i32 i64 i128 isize }
+
+impl TryFrom<f64> for Mpq {
+    type Error = TryFromFloatError;
+
+    /// Convert a finite float to an `Mpq` exactly.
+    fn try_from(d: f64) -> Result<Self, Self::Error> {
+        if !d.is_finite() {
+            return Err(TryFromFloatError(()));
+        }
+        Ok(unsafe { Mpq::from_init(|x| mpq_set_d(x, d)) })
+    }
+}
+
+impl TryFrom<f32> for Mpq {
+    type Error = TryFromFloatError;
+
+    /// Convert a finite float to an `Mpq` exactly.
+    fn try_from(d: f32) -> Result<Self, Self::Error> {
+        Mpq::try_from(d as f64)
+    }
+}
+
+/// Error returned when converting a NaN or infinite float to an [`Mpq`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TryFromFloatError(());
+
+impl fmt::Display for TryFromFloatError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("cannot convert NaN or infinite float to a rational")
+    }
+}
+
+impl Error for TryFromFloatError {}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn q(num: i64, den: i64) -> Mpq {
+        Mpq::from_parts(Mpz::from(num), Mpz::from(den)).unwrap()
+    }
+
+    #[test]
+    fn integers() {
+        assert_eq!(Mpq::from(Mpz::from(-5)), q(-5, 1));
+        assert_eq!(Mpq::from(&Mpz::from(7)), q(7, 1));
+        assert_eq!(Mpq::from(3u8), q(3, 1));
+        assert_eq!(Mpq::from(-3i64), q(-3, 1));
+        assert_eq!(*Mpq::from(i128::MIN).numer(), i128::MIN);
+        assert_eq!(*Mpq::from(u128::MAX).numer(), u128::MAX);
+        assert_eq!(*Mpq::from(u64::MAX).denom(), 1);
+    }
+
+    #[test]
+    fn floats() {
+        assert_eq!(Mpq::try_from(0.75f64), Ok(q(3, 4)));
+        assert_eq!(Mpq::try_from(-2.5f32), Ok(q(-5, 2)));
+        assert_eq!(Mpq::try_from(0.0f64), Ok(Mpq::new()));
+        let tiny = Mpq::try_from(f64::MIN_POSITIVE).unwrap();
+        assert_eq!(*tiny.denom(), Mpz::from(1) << 1022u32);
+        assert!(Mpq::try_from(f64::NAN).is_err());
+        assert!(Mpq::try_from(f32::INFINITY).is_err());
+        assert_eq!(q(1, 3).to_f64(), 1.0 / 3.0);
+        assert_eq!(q(-7, 4).to_f64(), -1.75);
+    }
+}
@@ -0,0 +1,177 @@
+//! String conversion for [`Mpq`]
+//!
+//! Rationals are written as `num/den`, or just `num` when the denominator is 1, with the digits of
+//! each part as for [`Mpz`]. Parsing accepts the same forms, with an optional sign on the numerator
+//! only, and puts the result in lowest terms, so `"6/-4"` is rejected but `"-6/4"` is `-3/2`.
+
+use std::error::Error;
+use std::fmt;
+use std::str::FromStr;
+
+use super::*;
+use crate::mpz::ParseMpzErrorKind;
+
+impl Mpq {
+    /// Convert to a string of digits in `radix`, as `num/den` or `num` if the denominator is 1,
+    /// with a leading `-` if negative.
+    ///
+    /// The digits are as for [`Mpz::to_string_radix`], and it returns
+    /// [`MpirError::InvalidBase`] for the same radixes.
+    pub fn to_string_radix(&self, radix: i32) -> Result<String, MpirError> {
+        let num = self.numer().to_string_radix(radix)?;
+        Ok(match self.is_integer() {
+            true => num,
+            false => format!("{}/{}", num, self.denom().to_string_radix(radix)?),
+        })
+    }
+
+    /// Parse `num/den` or `num` in `radix`, with an optional leading `+` or `-`.
+    ///
+    /// The digits are as for [`Mpz::from_str_radix`]. The result is put in lowest terms. A `radix`
+    /// outside the range 2 to 62 gives [`ParseMpqErrorKind::InvalidBase`].
+    pub fn from_str_radix(s: &str, radix: i32) -> Result<Self, ParseMpqError> {
+        let error = |kind| Err(ParseMpqError { kind });
+        let (num, den) = match s.split_once('/') {
+            Some((num, den)) => (num, Some(den)),
+            None => (s, None),
+        };
+        let num = match Mpz::from_str_radix(num, radix) {
+            Ok(num) => num,
+            Err(e) if *e.kind() == ParseMpzErrorKind::InvalidBase(radix) => {
+                return error(ParseMpqErrorKind::InvalidBase(radix))
+            }
+            Err(e) if s.is_empty() && *e.kind() == ParseMpzErrorKind::Empty => {
+                return error(ParseMpqErrorKind::Empty)
+            }
+            Err(_) => return error(ParseMpqErrorKind::InvalidDigit),
+        };
+        let den = match den {
+            None => Mpz::from(1),
+            Some(den) if den.starts_with(['+', '-']) => {
+                return error(ParseMpqErrorKind::InvalidDigit)
+            }
+            Some(den) => match Mpz::from_str_radix(den, radix) {
+                Ok(den) => den,
+                Err(_) => return error(ParseMpqErrorKind::InvalidDigit),
+            },
+        };
+        Mpq::from_parts(num, den).or_else(|_| error(ParseMpqErrorKind::ZeroDenominator))
+    }
+}
+
+impl FromStr for Mpq {
+    type Err = ParseMpqError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Mpq::from_str_radix(s, 10)
+    }
+}
+
+impl fmt::Display for Mpq {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let s = self.to_string_radix(10).expect("10 is a valid radix");
+        match s.strip_prefix('-') {
+            Some(digits) => f.pad_integral(false, "", digits),
+            None => f.pad_integral(true, "", &s),
+        }
+    }
+}
+
+impl fmt::Debug for Mpq {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(self, f)
+    }
+}
+
+/// Reason a string could not be parsed as an [`Mpq`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ParseMpqErrorKind {
+    /// The string had no digits.
+    Empty,
+    /// The numerator or denominator contained a character that is not a digit in the given base,
+    /// or was missing.
+    InvalidDigit,
+    /// The denominator was zero.
+    ZeroDenominator,
+    /// The base was not in the range 2 to 62.
+    InvalidBase(i32),
+}
+
+/// Error returned when parsing an [`Mpq`] from a string fails.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ParseMpqError {
+    kind: ParseMpqErrorKind,
+}
+
+impl ParseMpqError {
+    /// The reason parsing failed.
+    pub fn kind(&self) -> &ParseMpqErrorKind {
+        &self.kind
+    }
+}
+
+impl fmt::Display for ParseMpqError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.kind {
+            ParseMpqErrorKind::Empty => f.write_str("cannot parse rational from empty string"),
+            ParseMpqErrorKind::InvalidDigit => f.write_str("invalid digit found in string"),
+            ParseMpqErrorKind::ZeroDenominator => f.write_str("denominator is zero"),
+            ParseMpqErrorKind::InvalidBase(radix) => MpirError::InvalidBase(radix).fmt(f),
+        }
+    }
+}
+
+impl Error for ParseMpqError {}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn q(num: i64, den: i64) -> Mpq {
+        Mpq::from_parts(Mpz::from(num), Mpz::from(den)).unwrap()
+    }
+
+    #[test]
+    fn display() {
+        assert_eq!(q(3, 4).to_string(), "3/4");
+        assert_eq!(q(-6, 4).to_string(), "-3/2");
+        assert_eq!(q(5, 1).to_string(), "5");
+        assert_eq!(Mpq::new().to_string(), "0");
+        assert_eq!(format!("{:+}", q(1, 2)), "+1/2");
+        assert_eq!(format!("{:>6}", q(-1, 2)), "  -1/2");
+        assert_eq!(format!("{:?}", q(1, 3)), "1/3");
+        assert_eq!(q(-255, 16).to_string_radix(16).unwrap(), "-ff/10");
+        assert_eq!(q(36, 61).to_string_radix(62).unwrap(), "a/z");
+        assert_eq!(q(1, 2).to_string_radix(0), Err(MpirError::InvalidBase(0)));
+    }
+
+    #[test]
+    fn from_str() {
+        assert_eq!("3/4".parse::<Mpq>(), Ok(q(3, 4)));
+        assert_eq!("-6/4".parse::<Mpq>(), Ok(q(-3, 2)));
+        assert_eq!("+7".parse::<Mpq>(), Ok(q(7, 1)));
+        assert_eq!("0/5".parse::<Mpq>(), Ok(Mpq::new()));
+        assert_eq!(Mpq::from_str_radix("-ff/10", 16), Ok(q(-255, 16)));
+        let big: Mpq = "1267650600228229401496703205376/3".parse().unwrap();
+        assert_eq!(*big.numer(), Mpz::from(1) << 100u32);
+        assert_eq!(big.to_string(), "1267650600228229401496703205376/3");
+    }
+
+    #[test]
+    fn parse_errors() {
+        let kind = |s: &str| *s.parse::<Mpq>().unwrap_err().kind();
+        assert_eq!(kind(""), ParseMpqErrorKind::Empty);
+        assert_eq!(kind("/"), ParseMpqErrorKind::InvalidDigit);
+        assert_eq!(kind("3/"), ParseMpqErrorKind::InvalidDigit);
+        assert_eq!(kind("/3"), ParseMpqErrorKind::InvalidDigit);
+        assert_eq!(kind("6/-4"), ParseMpqErrorKind::InvalidDigit);
+        assert_eq!(kind("1/2/3"), ParseMpqErrorKind::InvalidDigit);
+        assert_eq!(kind("1.5"), ParseMpqErrorKind::InvalidDigit);
+        assert_eq!(kind(" 1/2"), ParseMpqErrorKind::InvalidDigit);
+        assert_eq!(kind("1/0"), ParseMpqErrorKind::ZeroDenominator);
+        assert_eq!(
+            *Mpq::from_str_radix("1/2", 63).unwrap_err().kind(),
+            ParseMpqErrorKind::InvalidBase(63)
+        );
+    }
+}
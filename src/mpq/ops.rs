@@ -0,0 +1,243 @@
+//! Operator overloading for [`Mpq`]
+//!
+//! `+`, `-`, `*` and `/` are implemented for owned and borrowed rationals, and with an [`Mpz`] or a
+//! primitive integer on either side, which is converted to an `Mpq` first. Every combination
+//! computes its result straight away, reusing the allocation of an owned operand where there is
+//! one. Dividing by zero panics.
+
+use std::ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Sub, SubAssign};
+
+use super::*;
+use crate::Assign;
+
+unsafe fn div(rop: mpq_ptr, n: mpq_srcptr, d: mpq_srcptr) {
+    if mpq_sgn(d) == 0 {
+        panic!("attempt to divide by zero");
+    }
+    mpq_div(rop, n, d);
+}
+
+// -------------------------------------------------------------------------------------------------
+// Mpq op Mpq
+
+macro_rules! arith_mpq {
+    ($Op:ident $op:ident $OpAssign:ident $op_assign:ident $func:ident) => {
+        impl $Op<Mpq> for Mpq {
+            type Output = Mpq;
+            fn $op(mut self, rhs: Mpq) -> Mpq {
+                self.$op_assign(&rhs);
+                self
+            }
+        }
+
+        impl $Op<&Mpq> for Mpq {
+            type Output = Mpq;
+            fn $op(mut self, rhs: &Mpq) -> Mpq {
+                self.$op_assign(rhs);
+                self
+            }
+        }
+
+        impl $Op<Mpq> for &Mpq {
+            type Output = Mpq;
+            fn $op(self, mut rhs: Mpq) -> Mpq {
+                let p = rhs.as_raw_mut();
+                unsafe { $func(p, self.as_raw(), p) };
+                rhs
+            }
+        }
+
+        impl $Op<&Mpq> for &Mpq {
+            type Output = Mpq;
+            fn $op(self, rhs: &Mpq) -> Mpq {
+                let mut r = Mpq::new();
+                unsafe { $func(r.as_raw_mut(), self.as_raw(), rhs.as_raw()) };
+                r
+            }
+        }
+
+        impl $OpAssign<Mpq> for Mpq {
+            fn $op_assign(&mut self, rhs: Mpq) {
+                self.$op_assign(&rhs);
+            }
+        }
+
+        impl $OpAssign<&Mpq> for Mpq {
+            fn $op_assign(&mut self, rhs: &Mpq) {
+                let p = self.as_raw_mut();
+                unsafe { $func(p, p, rhs.as_raw()) };
+            }
+        }
+    };
+}
+
+arith_mpq! { Add add AddAssign add_assign mpq_add }
+arith_mpq! { Sub sub SubAssign sub_assign mpq_sub }
+arith_mpq! { Mul mul MulAssign mul_assign mpq_mul }
+arith_mpq! { Div div DivAssign div_assign div }
+
+// -------------------------------------------------------------------------------------------------
+// Mpq op integer, integer op Mpq
+
+macro_rules! arith_int {
+    ($Op:ident $op:ident $OpAssign:ident $op_assign:ident; $($t:ty)*) => {$(
+        impl $Op<$t> for Mpq {
+            type Output = Mpq;
+            fn $op(self, rhs: $t) -> Mpq {
+                self.$op(Mpq::from(rhs))
+            }
+        }
+
+        impl $Op<$t> for &Mpq {
+            type Output = Mpq;
+            fn $op(self, rhs: $t) -> Mpq {
+                self.$op(Mpq::from(rhs))
+            }
+        }
+
+        impl $Op<Mpq> for $t {
+            type Output = Mpq;
+            fn $op(self, rhs: Mpq) -> Mpq {
+                Mpq::from(self).$op(rhs)
+            }
+        }
+
+        impl $Op<&Mpq> for $t {
+            type Output = Mpq;
+            fn $op(self, rhs: &Mpq) -> Mpq {
+                Mpq::from(self).$op(rhs)
+            }
+        }
+
+        impl $OpAssign<$t> for Mpq {
+            fn $op_assign(&mut self, rhs: $t) {
+                self.$op_assign(Mpq::from(rhs));
+            }
+        }
+    )*};
+}
+
+macro_rules! arith_ints {
+    ($($Op:ident $op:ident $OpAssign:ident $op_assign:ident;)*) => {$(
+        arith_int! {
+            $Op $op $OpAssign $op_assign;
+            Mpz &Mpz u8 u16 u32 u64 u128 usize i8 i16 i32 i64 i128 isize
+        }
+    )*};
+}
+
+arith_ints! {
+    Add add AddAssign add_assign;
+    Sub sub SubAssign sub_assign;
+    Mul mul MulAssign mul_assign;
+    Div div DivAssign div_assign;
+}
+
+// -------------------------------------------------------------------------------------------------
+// Unary operators
+
+impl Neg for Mpq {
+    type Output = Mpq;
+    fn neg(mut self) -> Mpq {
+        let p = self.as_raw_mut();
+        unsafe { mpq_neg(p, p) };
+        self
+    }
+}
+
+impl Neg for &Mpq {
+    type Output = Mpq;
+    fn neg(self) -> Mpq {
+        let mut r = Mpq::new();
+        unsafe { mpq_neg(r.as_raw_mut(), self.as_raw()) };
+        r
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+// Assignment
+
+impl Assign<&Mpq> for Mpq {
+    fn assign(&mut self, src: &Mpq) {
+        unsafe { mpq_set(self.as_raw_mut(), src.as_raw()) }
+    }
+}
+
+impl Assign<Mpq> for Mpq {
+    fn assign(&mut self, mut src: Mpq) {
+        unsafe { mpq_swap(self.as_raw_mut(), src.as_raw_mut()) }
+    }
+}
+
+impl Assign<&Mpz> for Mpq {
+    fn assign(&mut self, src: &Mpz) {
+        unsafe { mpq_set_z(self.as_raw_mut(), src.as_raw()) }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn q(num: i64, den: i64) -> Mpq {
+        Mpq::from_parts(Mpz::from(num), Mpz::from(den)).unwrap()
+    }
+
+    #[test]
+    fn mpq_mpq() {
+        let (a, b) = (q(1, 2), q(-1, 3));
+        assert_eq!(&a + &b, q(1, 6));
+        assert_eq!(&a - &b, q(5, 6));
+        assert_eq!(&a * &b, q(-1, 6));
+        assert_eq!(&a / &b, q(-3, 2));
+        assert_eq!(a.clone() + b.clone(), q(1, 6));
+        assert_eq!(&a - b.clone(), q(5, 6));
+        assert_eq!(a.clone() * &b, q(-1, 6));
+        assert_eq!(-&a, q(-1, 2));
+        assert_eq!(-b, q(1, 3));
+    }
+
+    #[test]
+    fn integers() {
+        let a = q(3, 4);
+        assert_eq!(&a + 1u8, q(7, 4));
+        assert_eq!(&a - Mpz::from(1), q(-1, 4));
+        assert_eq!(a.clone() * &Mpz::from(-4), q(-3, 1));
+        assert_eq!(&a / -3i32, q(-1, 4));
+        assert_eq!(1u64 - &a, q(1, 4));
+        assert_eq!(Mpz::from(3) / a.clone(), q(4, 1));
+        assert_eq!(&Mpz::from(2) * &a, q(3, 2));
+        assert_eq!(
+            i128::MIN * &a,
+            Mpq::from(Mpz::from(i128::MIN) * 3u32 / 4u32)
+        );
+    }
+
+    #[test]
+    fn assign() {
+        let mut a = q(1, 2);
+        a += q(1, 3);
+        a -= &q(1, 6);
+        a *= 6u32;
+        a /= Mpz::from(8);
+        assert_eq!(a, q(1, 2));
+        a.assign(&q(5, 7));
+        assert_eq!(a, q(5, 7));
+        a.assign(q(-1, 9));
+        assert_eq!(a, q(-1, 9));
+        a.assign(&Mpz::from(4));
+        assert_eq!(a, q(4, 1));
+    }
+
+    #[test]
+    #[should_panic(expected = "divide by zero")]
+    fn div_zero() {
+        let _ = q(1, 2) / Mpq::new();
+    }
+
+    #[test]
+    #[should_panic(expected = "divide by zero")]
+    fn div_zero_integer() {
+        let _ = q(1, 2) / 0u8;
+    }
+}
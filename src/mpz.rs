@@ -2,29 +2,97 @@
 //!
 //! [MPIR 3.0.0 - C documentation](https://mpir.org/mpir-3.0.0.pdf)
 
-use std::mem::{size_of, uninitialized};
+use std::mem::MaybeUninit;
 
 use crate::ctype::{
-    c_char, c_double, c_int, c_long, c_ulong, c_void, mp_bitcnt_t, mpz_ptr, mpz_srcptr, mpz_struct,
-    size_t, CString, mp_limb_t
+    alloc_func, c_char, c_double, c_int, c_long, c_ulong, c_void, free_func, mp_bitcnt_t, mpz_ptr,
+    mpz_srcptr, mpz_struct, realloc_func, size_t, CString, mp_limb_t, gmp_randstate_ptr, mpq_srcptr,
+    mpf_srcptr
 };
 
 use crate::Sign;
 
-#[link(name = "mpir", kind = "static")]
+mod cmp;
+mod comb;
+mod convert;
+mod div;
+mod fib;
+mod fmt;
+mod incomplete;
+mod number;
+pub(crate) mod ops;
+mod pow;
+mod prime;
+mod random;
+#[cfg(feature = "zeroize")]
+mod secret;
+#[cfg(feature = "rand")]
+mod uniform;
+
+pub use convert::{TryFromFloatError, TryFromMpzError};
+pub use fmt::{ParseMpzError, ParseMpzErrorKind};
+pub use prime::PrimeIter;
+#[cfg(feature = "zeroize")]
+pub use secret::SecretMpz;
+#[cfg(feature = "rand")]
+pub use uniform::UniformMpz;
+
+pub use incomplete::{
+    AddIncomplete, AddMulIncomplete, MulIncomplete, SubIncomplete, SubMulIncomplete,
+};
+
+/* The headers of both MPIR and GMP define mpz_add etc. as macros for the exported __gmpz_add etc.,
+so every binding names its symbol with link_name. The symbols are the same for both backends, apart
+from the few functions that only MPIR provides.
+
+The bindings use the C ABI, so nothing can unwind through MPIR, whose functions are not
+exception-safe. A Rust callback that panics, such as a memory function, aborts the process. */
 extern "C" {
 
     // ---------------------------------------------------------------------------------------------
     // Constants
 
     /// The MPIR version number, as a null-terminated string, in the form “i.j.k”.
-    static mpir_version: *const c_char;
+    ///
+    /// MPIR only, not available with the `backend-gmp` feature.
+    #[cfg(not(feature = "backend-gmp"))]
+    #[link_name = "__mpir_version"]
+    pub static mpir_version: *const c_char;
 
     /// The GNU MP version number, as a null-terminated string, in the form “i.j.k”.
-    static gmp_version: *const c_char;
+    #[link_name = "__gmp_version"]
+    pub static gmp_version: *const c_char;
 
     /// The number of bits per limb.
-    static mp_bits_per_limb: c_int;
+    #[link_name = "__gmp_bits_per_limb"]
+    pub static mp_bits_per_limb: c_int;
+
+    // ---------------------------------------------------------------------------------------------
+    // Custom Allocation
+
+    /// Get the current allocation functions, storing function pointers to the locations given by
+    /// the arguments. If an argument is NULL, that function pointer is not stored.
+    ///
+    /// Blocks returned by MPIR, such as the string from mpz_get_str when str is NULL, must be freed
+    /// with the free function obtained here.
+    #[link_name = "__gmp_get_memory_functions"]
+    pub fn mp_get_memory_functions(
+        alloc: *mut Option<alloc_func>,
+        realloc: *mut Option<realloc_func>,
+        free: *mut Option<free_func>,
+    );
+
+    /// Replace the current allocation functions from the arguments. If an argument is NULL, the
+    /// corresponding default function is used.
+    ///
+    /// This should be called before any other MPIR function, as blocks allocated with one set of
+    /// functions are freed or reallocated with whatever is current at the time.
+    #[link_name = "__gmp_set_memory_functions"]
+    pub fn mp_set_memory_functions(
+        alloc: Option<alloc_func>,
+        realloc: Option<realloc_func>,
+        free: Option<free_func>,
+    );
 
     // ---------------------------------------------------------------------------------------------
     // Initialisation Functions
@@ -44,6 +112,7 @@ extern "C" {
     As you can see, you can store new values any number of times, once an object is initialized */
 
     /// Initialize integer, and set its value to 0
+    #[link_name = "__gmpz_init"]
     pub fn mpz_init(x: mpz_ptr);
 
     /// Initialize integer, with space for n bits, and set its value to 0.
@@ -51,10 +120,12 @@ extern "C" {
     /// n is only the initial space, integer will grow automatically in the normal way, if necessary,
     /// for subsequent values stored. mpz_init2 makes it possible to avoid such reallocations if a
     /// maximum size is known in advance
+    #[link_name = "__gmpz_init2"]
     pub fn mpz_init2(x: mpz_ptr, n: mp_bitcnt_t);
 
     /// Free the space occupied by integer. Call this function for all mpz_t variables when you are
     /// done with them.
+    #[link_name = "__gmpz_clear"]
     pub fn mpz_clear(x: mpz_ptr);
 
     /// Change the space allocated for integer to n bits. The value in integer is preserved if it fits,
@@ -62,6 +133,7 @@ extern "C" {
     ///
     /// This function can be used to increase the space for a variable in order to avoid repeated
     /// automatic reallocations, or to decrease it to give memory back to the heap
+    #[link_name = "__gmpz_realloc2"]
     pub fn mpz_realloc2(x: mpz_ptr, n: mp_bitcnt_t);
 
     // ---------------------------------------------------------------------------------------------
@@ -70,24 +142,28 @@ extern "C" {
     /* These functions assign new values to already initialized integers */
 
     /// Set the value of rop from another mpz.
-    pub fn mpz_set(rop: mpz_ptr, op: mpz_ptr);
+    #[link_name = "__gmpz_set"]
+    pub fn mpz_set(rop: mpz_ptr, op: mpz_srcptr);
 
     /// Set the value of rop from op.
+    #[link_name = "__gmpz_set_ui"]
     pub fn mpz_set_ui(rop: mpz_ptr, op: c_ulong);
 
     /// Set the value of rop from op.
-    pub fn mpz_set_si(rop: mpz_ptr, op: c_ulong);
+    #[link_name = "__gmpz_set_si"]
+    pub fn mpz_set_si(rop: mpz_ptr, op: c_long);
 
     /// Set the value of rop from a C double.
+    #[link_name = "__gmpz_set_d"]
     pub fn mpz_set_d(rop: mpz_ptr, op: c_double);
 
-    // TODO : when rational is added
-    // /// Set the value of rop from op.
-    // pub fn mpz_set_q (rop: mpz_ptr, op: );
+    /// Set the value of rop from op, truncating towards zero.
+    #[link_name = "__gmpz_set_q"]
+    pub fn mpz_set_q(rop: mpz_ptr, op: mpq_srcptr);
 
-    // TODO : when float is added
-    // /// Set the value of rop from op.
-    // pub fn mpz_set_f (rop: mpz_ptr, op: mpf_ptr);
+    /// Set the value of rop from op, truncating towards zero.
+    #[link_name = "__gmpz_set_f"]
+    pub fn mpz_set_f(rop: mpz_ptr, op: mpf_srcptr);
 
     /// Set the value of rop from str, a null-terminated C string in base base. White space is allowed
     /// in the string, and is simply ignored.
@@ -100,9 +176,11 @@ extern "C" {
     ///
     /// This function returns 0 if the entire string is a valid number in base base. Otherwise it returns
     /// −1
+    #[link_name = "__gmpz_set_str"]
     pub fn mpz_set_str(rop: mpz_ptr, s: *const c_char, base: c_int) -> c_int;
 
     /// Swap the values rop1 and rop2 efficiently
+    #[link_name = "__gmpz_swap"]
     pub fn mpz_swap(rop1: mpz_ptr, rop2: mpz_ptr);
 
     // ---------------------------------------------------------------------------------------------
@@ -127,21 +205,26 @@ extern "C" {
     and-set function on a variable already initialized! */
 
     /// Initialize rop with limb space and set the initial numeric value from another mpz.
-    pub fn mpz_init_set(rop: mpz_ptr, op: mpz_ptr);
+    #[link_name = "__gmpz_init_set"]
+    pub fn mpz_init_set(rop: mpz_ptr, op: mpz_srcptr);
 
     /// Set the value of rop from op.
+    #[link_name = "__gmpz_init_set_ui"]
     pub fn mpz_init_set_ui(rop: mpz_ptr, op: c_ulong);
 
     /// Set the value of rop from op.
-    pub fn mpz_init_set_si(rop: mpz_ptr, op: c_ulong);
+    #[link_name = "__gmpz_init_set_si"]
+    pub fn mpz_init_set_si(rop: mpz_ptr, op: c_long);
 
     /// Initialize rop with limb space and set the initial numeric value from a C double.
+    #[link_name = "__gmpz_init_set_d"]
     pub fn mpz_init_set_d(rop: mpz_ptr, op: c_double);
 
     /// Initialize rop and set its value like mpz_set_str (see its documentation above for details).
     ///
     /// If the string is a correct base base number, the function returns 0; if an error occurs it returns
     /// −1. rop is initialized even if an error occurs. (I.e., you have to call mpz_clear for it.)
+    #[link_name = "__gmpz_init_set_str"]
     pub fn mpz_init_set_str(rop: mpz_ptr, s: *const c_char, base: c_int) -> c_int;
 
     // Conversion Functions
@@ -152,6 +235,7 @@ extern "C" {
     ///
     /// If op is too big to fit an mpir_ui then just the least significant bits that do fit are returned.
     /// The sign of op is ignored, only the absolute value is used.
+    #[link_name = "__gmpz_get_ui"]
     pub fn mpz_get_ui(op: mpz_srcptr) -> c_ulong;
 
     /// If op fits into a mpir_si return the value of op. Otherwise return the least significant part
@@ -159,12 +243,14 @@ extern "C" {
     ///
     /// If op is too big to fit in a mpir_si, the returned result is probably not very useful. To find
     /// out if the value will fit, use the function mpz_fits_slong_p.
-    pub fn mpz_get_si(op: mpz_srcptr) -> c_ulong;
+    #[link_name = "__gmpz_get_si"]
+    pub fn mpz_get_si(op: mpz_srcptr) -> c_long;
 
     /// Convert op to a double, truncating if necessary (ie. rounding towards zero).
     ///
     /// If the exponent from the conversion is too big, the result is system dependent. An infinity is
     /// returned where available. A hardware overflow trap may or may not occur.
+    #[link_name = "__gmpz_get_d"]
     pub fn mpz_get_d(op: mpz_srcptr) -> c_double;
 
     /// Convert op to a double, truncating if necessary (ie. rounding towards zero), and returning
@@ -175,7 +261,8 @@ extern "C" {
     ///
     /// This is similar to the standard C frexp function (see Section “Normalization Functions” in
     /// The GNU C Library Reference Manual).
-    pub fn mpz_get_d_2exp(exp: mpz_ptr, op: mpz_srcptr);
+    #[link_name = "__gmpz_get_d_2exp"]
+    pub fn mpz_get_d_2exp(exp: *mut c_long, op: mpz_srcptr) -> c_double;
 
     /// Convert op to a string of digits in base base. The base may vary from 2 to 36 or from −2 to
     /// −36.
@@ -193,54 +280,70 @@ extern "C" {
     /// null-terminator.
     ///
     /// A pointer to the result string is returned, being either the allocated block, or the given str.
+    #[link_name = "__gmpz_get_str"]
     pub fn mpz_get_str(s: *mut c_char, base: c_int, op: mpz_srcptr) -> *mut c_char;
 
     // ---------------------------------------------------------------------------------------------
     // Arithmetic Functions
 
     /// Set rop to op1 + op2.
+    #[link_name = "__gmpz_add"]
     pub fn mpz_add(rop: mpz_ptr, op1: mpz_srcptr, op2: mpz_srcptr);
 
     /// Set rop to op1 + op2.
-    pub fn mpz_add_ui(rop: mpz_ptr, op1: mpz_srcptr, op2: c_ulong) -> c_int;
+    #[link_name = "__gmpz_add_ui"]
+    pub fn mpz_add_ui(rop: mpz_ptr, op1: mpz_srcptr, op2: c_ulong);
 
     /// Set rop to op1 − op2.
+    #[link_name = "__gmpz_sub"]
     pub fn mpz_sub(rop: mpz_ptr, op1: mpz_srcptr, op2: mpz_srcptr);
 
     /// Set rop to op1 − op2.
+    #[link_name = "__gmpz_sub_ui"]
     pub fn mpz_sub_ui(rop: mpz_ptr, op1: mpz_srcptr, op2: c_ulong);
 
     /// Set rop to op1 − op2.
+    #[link_name = "__gmpz_ui_sub"]
     pub fn mpz_ui_sub(rop: mpz_ptr, op1: c_ulong, op2: mpz_srcptr);
 
     /// Set rop to op1 × op2.
+    #[link_name = "__gmpz_mul"]
     pub fn mpz_mul(rop: mpz_ptr, op1: mpz_srcptr, op2: mpz_srcptr);
 
     /// Set rop to op1 × op2.
+    #[link_name = "__gmpz_mul_si"]
     pub fn mpz_mul_si(rop: mpz_ptr, op1: mpz_srcptr, op2: c_long);
 
     /// Set rop to op1 × op2.
+    #[link_name = "__gmpz_mul_ui"]
     pub fn mpz_mul_ui(rop: mpz_ptr, op1: mpz_srcptr, op2: c_ulong);
 
     /// Set rop to rop + op1 × op2.
+    #[link_name = "__gmpz_addmul"]
     pub fn mpz_addmul(rop: mpz_ptr, op1: mpz_srcptr, op2: mpz_srcptr);
 
     /// Set rop to rop + op1 × op2.
+    #[link_name = "__gmpz_addmul_ui"]
     pub fn mpz_addmul_ui(rop: mpz_ptr, op1: mpz_srcptr, op2: c_ulong);
 
     /// Set rop to rop − op1 × op2.
+    #[link_name = "__gmpz_submul"]
     pub fn mpz_submul(rop: mpz_ptr, op1: mpz_srcptr, op2: mpz_srcptr);
 
     /// Set rop to rop − op1 × op2.
+    #[link_name = "__gmpz_submul_ui"]
     pub fn mpz_submul_ui(rop: mpz_ptr, op1: mpz_srcptr, op2: c_ulong);
 
     /// Set rop to op1 × 2op2. This operation can also be defined as a left shift by op2 bits.
+    #[link_name = "__gmpz_mul_2exp"]
     pub fn mpz_mul_2exp(rop: mpz_ptr, op1: mpz_srcptr, op2: mp_bitcnt_t);
 
     /// Set rop to −op.
+    #[link_name = "__gmpz_neg"]
     pub fn mpz_neg(rop: mpz_ptr, op: mpz_srcptr);
 
     /// Set rop to the absolute value of op.
+    #[link_name = "__gmpz_abs"]
     pub fn mpz_abs(rop: mpz_ptr, op: mpz_srcptr);
 
     // ---------------------------------------------------------------------------------------------
@@ -282,77 +385,102 @@ extern "C" {
     complement the same as the bitwise logical functions do, whereas mpz_tdiv_q_2exp effec-
     tively treats n as sign and magnitude. */
 
-    pub fn mpz_cdiv_q(rop: mpz_ptr, n: mpz_srcptr, d: c_ulong);
+    #[link_name = "__gmpz_cdiv_q"]
+    pub fn mpz_cdiv_q(rop: mpz_ptr, n: mpz_srcptr, d: mpz_srcptr);
 
-    pub fn mpz_cdiv_r(rop: mpz_ptr, n: mpz_srcptr, d: c_ulong);
+    #[link_name = "__gmpz_cdiv_r"]
+    pub fn mpz_cdiv_r(rop: mpz_ptr, n: mpz_srcptr, d: mpz_srcptr);
 
-    pub fn mpz_cdiv_qr(rop: mpz_ptr, r: mpz_ptr, n: mpz_srcptr, d: c_ulong);
+    #[link_name = "__gmpz_cdiv_qr"]
+    pub fn mpz_cdiv_qr(rop: mpz_ptr, r: mpz_ptr, n: mpz_srcptr, d: mpz_srcptr);
 
+    #[link_name = "__gmpz_cdiv_q_ui"]
     pub fn mpz_cdiv_q_ui(rop: mpz_ptr, n: mpz_srcptr, d: c_ulong) -> c_ulong;
 
+    #[link_name = "__gmpz_cdiv_r_ui"]
     pub fn mpz_cdiv_r_ui(rop: mpz_ptr, n: mpz_srcptr, d: c_ulong) -> c_ulong;
 
+    #[link_name = "__gmpz_cdiv_qr_ui"]
     pub fn mpz_cdiv_qr_ui(rop: mpz_ptr, r: mpz_ptr, n: mpz_srcptr, d: c_ulong) -> c_ulong;
 
-    pub fn mpz_cdiv_ui(rop: mpz_ptr, d: c_ulong) -> c_ulong;
+    #[link_name = "__gmpz_cdiv_ui"]
+    pub fn mpz_cdiv_ui(n: mpz_srcptr, d: c_ulong) -> c_ulong;
 
+    #[link_name = "__gmpz_cdiv_q_2exp"]
     pub fn mpz_cdiv_q_2exp(rop: mpz_ptr, n: mpz_srcptr, b: mp_bitcnt_t);
 
+    #[link_name = "__gmpz_cdiv_r_2exp"]
     pub fn mpz_cdiv_r_2exp(rop: mpz_ptr, n: mpz_srcptr, b: mp_bitcnt_t);
 
+    #[link_name = "__gmpz_fdiv_q"]
     pub fn mpz_fdiv_q(rop: mpz_ptr, n: mpz_srcptr, d: mpz_srcptr);
 
+    #[link_name = "__gmpz_fdiv_r"]
     pub fn mpz_fdiv_r(rop: mpz_ptr, n: mpz_srcptr, d: mpz_srcptr);
 
+    #[link_name = "__gmpz_fdiv_qr"]
     pub fn mpz_fdiv_qr(rop: mpz_ptr, r: mpz_ptr, n: mpz_srcptr, d: mpz_srcptr);
 
+    #[link_name = "__gmpz_fdiv_q_ui"]
     pub fn mpz_fdiv_q_ui(rop: mpz_ptr, n: mpz_srcptr, d: c_ulong) -> c_ulong;
 
+    #[link_name = "__gmpz_fdiv_r_ui"]
     pub fn mpz_fdiv_r_ui(rop: mpz_ptr, n: mpz_srcptr, d: c_ulong) -> c_ulong;
 
-    pub fn mpz_fdiv_qr_ui(rop: mpz_ptr, r: mpz_srcptr, n: mpz_srcptr, d: c_ulong) -> c_ulong;
+    #[link_name = "__gmpz_fdiv_qr_ui"]
+    pub fn mpz_fdiv_qr_ui(rop: mpz_ptr, r: mpz_ptr, n: mpz_srcptr, d: c_ulong) -> c_ulong;
 
-    pub fn mpz_fdiv_ui(rop: mpz_ptr, d: c_ulong) -> c_ulong;
+    #[link_name = "__gmpz_fdiv_ui"]
+    pub fn mpz_fdiv_ui(n: mpz_srcptr, d: c_ulong) -> c_ulong;
 
+    #[link_name = "__gmpz_fdiv_q_2exp"]
     pub fn mpz_fdiv_q_2exp(rop: mpz_ptr, n: mpz_srcptr, b: mp_bitcnt_t);
 
+    #[link_name = "__gmpz_fdiv_r_2exp"]
     pub fn mpz_fdiv_r_2exp(rop: mpz_ptr, n: mpz_srcptr, b: mp_bitcnt_t);
 
+    #[link_name = "__gmpz_tdiv_q"]
     pub fn mpz_tdiv_q(rop: mpz_ptr, n: mpz_srcptr, d: mpz_srcptr);
 
+    #[link_name = "__gmpz_tdiv_r"]
     pub fn mpz_tdiv_r(rop: mpz_ptr, n: mpz_srcptr, d: mpz_srcptr);
 
-    pub fn mpz_tdiv_qr(rop: mpz_ptr, r: mpz_srcptr, n: mpz_srcptr, d: mpz_srcptr);
+    #[link_name = "__gmpz_tdiv_qr"]
+    pub fn mpz_tdiv_qr(rop: mpz_ptr, r: mpz_ptr, n: mpz_srcptr, d: mpz_srcptr);
 
+    #[link_name = "__gmpz_tdiv_q_ui"]
     pub fn mpz_tdiv_q_ui(rop: mpz_ptr, n: mpz_srcptr, d: c_ulong) -> c_ulong;
 
+    #[link_name = "__gmpz_tdiv_r_ui"]
     pub fn mpz_tdiv_r_ui(rop: mpz_ptr, n: mpz_srcptr, d: c_ulong) -> c_ulong;
 
-    pub fn mpz_tdiv_qr_ui(rop: mpz_ptr, r: mpz_srcptr, n: mpz_srcptr, d: c_ulong) -> c_ulong;
+    #[link_name = "__gmpz_tdiv_qr_ui"]
+    pub fn mpz_tdiv_qr_ui(rop: mpz_ptr, r: mpz_ptr, n: mpz_srcptr, d: c_ulong) -> c_ulong;
 
-    pub fn mpz_tdiv_ui(rop: mpz_ptr, d: c_ulong) -> c_ulong;
+    #[link_name = "__gmpz_tdiv_ui"]
+    pub fn mpz_tdiv_ui(n: mpz_srcptr, d: c_ulong) -> c_ulong;
 
+    #[link_name = "__gmpz_tdiv_q_2exp"]
     pub fn mpz_tdiv_q_2exp(q: mpz_ptr, n: mpz_srcptr, b: mp_bitcnt_t);
 
+    #[link_name = "__gmpz_tdiv_r_2exp"]
     pub fn mpz_tdiv_r_2exp(r: mpz_ptr, n: mpz_srcptr, b: mp_bitcnt_t);
 
     /// Set r to n mod d. The sign of the divisor is ignored; the result is always non-negative.
     ///
     /// mpz_mod_ui is identical to mpz_fdiv_r_ui above, returning the remainder as well as setting
     /// r. See mpz_fdiv_ui above if only the return value is wanted.
+    #[link_name = "__gmpz_mod"]
     pub fn mpz_mod(r: mpz_ptr, n: mpz_srcptr, d: mpz_srcptr);
 
-    /// Set r to n mod d. The sign of the divisor is ignored; the result is always non-negative.
-    ///
-    /// mpz_mod_ui is identical to mpz_fdiv_r_ui above, returning the remainder as well as setting
-    /// r. See mpz_fdiv_ui above if only the return value is wanted.
-    pub fn mpz_mod_ui(r: mpz_ptr, n: mpz_srcptr, d: mpz_srcptr) -> c_ulong;
+    // mpz_mod_ui is a macro, see below.
 
     /// Set q to n/d. These functions produce correct results only when it is known in advance that
     /// d divides n.
     ///
     /// These routines are much faster than the other division functions, and are the best choice
     /// when exact division is known to occur, for example reducing a rational to lowest terms.
+    #[link_name = "__gmpz_divexact"]
     pub fn mpz_divexact(q: mpz_ptr, n: mpz_srcptr, d: mpz_srcptr);
 
     /// Set q to n/d. These functions produce correct results only when it is known in advance that
@@ -360,6 +488,7 @@ extern "C" {
     ///
     /// These routines are much faster than the other division functions, and are the best choice
     /// when exact division is known to occur, for example reducing a rational to lowest terms.
+    #[link_name = "__gmpz_divexact_ui"]
     pub fn mpz_divexact_ui(q: mpz_ptr, n: mpz_srcptr, d: c_ulong);
 
     /// Return non-zero if n is exactly divisible by d, or in the case of mpz_divisible_2exp_p by 2b.
@@ -367,21 +496,24 @@ extern "C" {
     /// n is divisible by d if there exists an integer q satisfying n = qd. Unlike the other division
     /// functions, d = 0 is accepted and following the rule it can be seen that only 0 is considered
     /// divisible by 0.
-    pub fn mpz_divisible_p(n: mpz_ptr, d: mpz_srcptr) -> c_int;
+    #[link_name = "__gmpz_divisible_p"]
+    pub fn mpz_divisible_p(n: mpz_srcptr, d: mpz_srcptr) -> c_int;
 
     /// Return non-zero if n is exactly divisible by d, or in the case of mpz_divisible_2exp_p by 2b.
     ///
     /// n is divisible by d if there exists an integer q satisfying n = qd. Unlike the other division
     /// functions, d = 0 is accepted and following the rule it can be seen that only 0 is considered
     /// divisible by 0.
-    pub fn mpz_divisible_ui_p(n: mpz_ptr, d: c_ulong) -> c_int;
+    #[link_name = "__gmpz_divisible_ui_p"]
+    pub fn mpz_divisible_ui_p(n: mpz_srcptr, d: c_ulong) -> c_int;
 
     /// Return non-zero if n is exactly divisible by d, or in the case of mpz_divisible_2exp_p by 2b.
     ///
     /// n is divisible by d if there exists an integer q satisfying n = qd. Unlike the other division
     /// functions, d = 0 is accepted and following the rule it can be seen that only 0 is considered
     /// divisible by 0.
-    pub fn mpz_divisible_2exp_p(n: mpz_ptr, b: mp_bitcnt_t) -> c_int;
+    #[link_name = "__gmpz_divisible_2exp_p"]
+    pub fn mpz_divisible_2exp_p(n: mpz_srcptr, b: mp_bitcnt_t) -> c_int;
 
     /// Return non-zero if n is congruent to c modulo d, or in the case of mpz_congruent_2exp_p
     /// modulo 2b.
@@ -389,7 +521,8 @@ extern "C" {
     /// n is congruent to c mod d if there exists an integer q satisfying n = c + qd. Unlike the other
     /// division functions, d = 0 is accepted and following the rule it can be seen that n and c are
     /// considered congruent mod 0 only when exactly equal.
-    pub fn mpz_congruent_p(n: mpz_ptr, c: mpz_srcptr, d: mpz_srcptr) -> c_int;
+    #[link_name = "__gmpz_congruent_p"]
+    pub fn mpz_congruent_p(n: mpz_srcptr, c: mpz_srcptr, d: mpz_srcptr) -> c_int;
 
     /// Return non-zero if n is congruent to c modulo d, or in the case of mpz_congruent_2exp_p
     /// modulo 2b.
@@ -397,7 +530,8 @@ extern "C" {
     /// n is congruent to c mod d if there exists an integer q satisfying n = c + qd. Unlike the other
     /// division functions, d = 0 is accepted and following the rule it can be seen that n and c are
     /// considered congruent mod 0 only when exactly equal.
-    pub fn mpz_congruent_ui_p(n: mpz_ptr, c: c_ulong, d: c_ulong) -> c_int;
+    #[link_name = "__gmpz_congruent_ui_p"]
+    pub fn mpz_congruent_ui_p(n: mpz_srcptr, c: c_ulong, d: c_ulong) -> c_int;
 
     /// Return non-zero if n is congruent to c modulo d, or in the case of mpz_congruent_2exp_p
     /// modulo 2b.
@@ -405,7 +539,8 @@ extern "C" {
     /// n is congruent to c mod d if there exists an integer q satisfying n = c + qd. Unlike the other
     /// division functions, d = 0 is accepted and following the rule it can be seen that n and c are
     /// considered congruent mod 0 only when exactly equal.
-    pub fn mpz_congruent_2exp_p(n: mpz_ptr, c: mpz_srcptr, b: mp_bitcnt_t) -> c_int;
+    #[link_name = "__gmpz_congruent_2exp_p"]
+    pub fn mpz_congruent_2exp_p(n: mpz_srcptr, c: mpz_srcptr, b: mp_bitcnt_t) -> c_int;
 
     // ---------------------------------------------------------------------------------------------
     // Exponentiation Functions
@@ -415,6 +550,7 @@ extern "C" {
     /// A negative exp is supported in mpz_powm if an inverse base−1 mod mod exists (see mpz_
     /// invert in Section 5.9 [Number Theoretic Functions], page 36). If an inverse doesn’t exist
     /// then a divide by zero is raised.
+    #[link_name = "__gmpz_powm"]
     pub fn mpz_powm(rop: mpz_ptr, base: mpz_srcptr, exp: mpz_srcptr, m: mpz_srcptr);
 
     /// Set rop to base^exp mod mod.
@@ -422,12 +558,15 @@ extern "C" {
     /// A negative exp is supported in mpz_powm if an inverse base−1 mod mod exists (see mpz_
     /// invert in Section 5.9 [Number Theoretic Functions], page 36). If an inverse doesn’t exist
     /// then a divide by zero is raised.
+    #[link_name = "__gmpz_powm_ui"]
     pub fn mpz_powm_ui(rop: mpz_ptr, base: mpz_srcptr, exp: c_ulong, m: mpz_srcptr);
 
     /// Set rop to base^exp. The case 00 yields 1.
+    #[link_name = "__gmpz_pow_ui"]
     pub fn mpz_pow_ui(rop: mpz_ptr, base: mpz_srcptr, exp: c_ulong);
 
     /// Set rop to base^exp. The case 00 yields 1.
+    #[link_name = "__gmpz_ui_pow_ui"]
     pub fn mpz_ui_pow_ui(rop: mpz_ptr, base: c_ulong, exp: c_ulong);
 
     // ---------------------------------------------------------------------------------------------
@@ -435,28 +574,36 @@ extern "C" {
 
     /// Set rop to b n
     ///
-    /// √opc, the truncated integer part of the nth root of op. Return non-zero if the
-    /// computation was exact, i.e., if op is rop to the nth power.
+    /// √opc, the truncated integer part of the nth root of op.
+    ///
+    /// MPIR only, not available with the `backend-gmp` feature; use mpz_root instead.
+    #[cfg(not(feature = "backend-gmp"))]
+    #[link_name = "__gmpz_nthroot"]
     pub fn mpz_nthroot(rop: mpz_ptr, op: mpz_srcptr, n: c_ulong);
 
     /// Set rop to b n
     ///
-    /// √opc, the truncated integer part of the nth root of op.
+    /// √opc, the truncated integer part of the nth root of op. Return non-zero if the
+    /// computation was exact, i.e., if op is rop to the nth power.
+    #[link_name = "__gmpz_root"]
     pub fn mpz_root(rop: mpz_ptr, op: mpz_srcptr, n: c_ulong) -> c_int;
 
     /// Set root to b n
     ///
     /// √uc, the truncated integer part of the nth root of u. Set rem to the remainder,
     /// (u − rootn).
-    pub fn mpz_rootrem(rop: mpz_ptr, rem: mpz_srcptr, u: mpz_srcptr, n: c_ulong);
+    #[link_name = "__gmpz_rootrem"]
+    pub fn mpz_rootrem(rop: mpz_ptr, rem: mpz_ptr, u: mpz_srcptr, n: c_ulong);
 
     /// Set rop to b√opc, the truncated integer part of the square root of op.
+    #[link_name = "__gmpz_sqrt"]
     pub fn mpz_sqrt(rop: mpz_ptr, op: mpz_srcptr);
 
     /// Set rop1 to b√opc, like mpz_sqrt. Set rop2 to the remainder (op − rop12), which will be
     /// zero if op is a perfect square.
     ///
     /// If rop1 and rop2 are the same variable, the results are undefined.
+    #[link_name = "__gmpz_sqrtrem"]
     pub fn mpz_sqrtrem(rop1: mpz_ptr, rop2: mpz_ptr, op: mpz_srcptr);
 
     /// Return non-zero if op is a perfect power, i.e., if there exist integers a and b, with b > 1, such
@@ -464,230 +611,256 @@ extern "C" {
     ///
     /// Under this definition both 0 and 1 are considered to be perfect powers. Negative values of
     /// op are accepted, but of course can only be odd perfect powers.
+    #[link_name = "__gmpz_perfect_power_p"]
     pub fn mpz_perfect_power_p(op: mpz_srcptr) -> c_int;
 
     /// Return non-zero if op is a perfect square, i.e., if the square root of op is an integer. Under
     /// this definition both 0 and 1 are considered to be perfect squares.
+    #[link_name = "__gmpz_perfect_square_p"]
     pub fn mpz_perfect_square_p(op: mpz_srcptr) -> c_int;
 
     // ---------------------------------------------------------------------------------------------
     // Number Theoretic Functions
 
-    // TODO mpz_probable_prime_p
-    // /// Determine whether n is a probable prime with the chance of error being at most 1 in 2^prob.
-    // /// return value is 1 if n is probably prime, or 0 if n is definitely composite.
-    // ///
-    // /// This function does some trial divisions to speed up the average case, then some probabilistic
-    // /// primality tests to achieve the desired level of error.
-    // /// div can be used to inform the function that trial division up to div has already been performed
-    // /// on n and so n has NO divisors <= div.Use 0 to inform the function that no trial division has
-    // /// been done.
-    // /// This function interface is preliminary and may change in the future.
-    // pub fn mpz_probable_prime_p (mpz t n, gmp randstate t state, int prob, mpir ui div) -> c_int;
-
-    // TODO mpz_likely_prime_p
-    // /// Determine whether n is likely a prime, i.e. you can consider it a prime for practical purposes.
-    // /// return value is 1 if n can be considered prime, or 0 if n is definitely composite.
-    // ///
-    // /// This function does some trial divisions to speed up the average case, then some probabilistic
-    // /// primality tests. The term “likely” refers to the fact that the number will not have small
-    // /// factors.
-    // /// div can be used to inform the function that trial division up to div has already been performed
-    // /// on n and so n has NO divisors <= div
-    // /// This function interface is preliminary and may change in the future.
-    // pub fn mpz_likely_prime_p(mpz t n, gmp randstate t state, mpir ui div) -> c_int;
-
-    // TODO mpz_next_prime_candidate
-    // /// Set rop to the next candidate prime greater than op. Note that this function will occasionally
-    // /// return composites. It is designed to give a quick method for generating numbers which do
-    // /// not have small prime factors (less than 1000) and which pass a small number of rounds of
-    // /// Miller-Rabin (just two rounds).The test is designed for speed, assuming that a high quality
-    // /// followup test can then be run to ensure primality.
-    // ///
-    // /// The variable state must be initialized by calling one of the gmp_randinit functions
-    // /// (Section 9.1 [Random State Initialization], page 67) before invoking this function.
-    // pub fn mpz_next_prime_candidate(mpz t rop, mpz t op, gmp randstate t state);
-
-    // TODO mpz_gcd
-    // /// Set rop to the greatest common divisor of op1 and op2. The result is always positive even if
-    // /// one or both input operands are negative.
-    // pub fn mpz_gcd(mpz t rop, mpz t op1, mpz t op2);
-
-    // TODO mpz_gcd_ui
-    // /// Compute the greatest common divisor of op1 and op2. If rop is not NULL, store the result
-    // /// there.
-    // /// If the result is small enough to fit in an mpir_ui, it is returned. If the result does not fit, 0
-    // /// is returned, and the result is equal to the argument op1. Note that the result will always fit
-    // /// if op2 is non-zero.
-    // pub fn mpz_gcd_ui (mpz t rop, mpz t op1, mpir ui op2) -> c_ulong;
-
-    // TODO mpz_gcdext
-    // /// Set g to the greatest common divisor of a and b, and in addition set s and t to coefficients
-    // /// satisfying as + bt = g. The value in g is always positive, even if one or both of a and b
-    // /// are negative (or zero if both inputs are zero). The values in s and t are chosen such that
-    // /// normally, |s| < |b|/(2g) and |t| < |a|/(2g), and these relations define s and t uniquely. There
-    // /// are a few exceptional cases:
-    // /// If |a| = |b|, then s = 0, t = sgn(b).
-    // /// Otherwise, s = sgn(a) if b = 0 or |b| = 2g, and t = sgn(b) if a = 0 or |a| = 2g.
-    // /// In all cases, s = 0 if and only if g = |b|, i.e., if b divides a or a = b = 0.
-    // /// If t is NULL then that value is not computed.
-    // pub fn mpz_gcdext(mpz t g, mpz t s, mpz t t, const mpz t a, const mpz t b);
-
-    // TODO mpz_lcm
-    // /// Set rop to the least common multiple of op1 and op2. rop is always positive, irrespective of
-    // /// the signs of op1 and op2. rop will be zero if either op1 or op2 is zero.
-    // pub fn mpz_lcm(mpz t rop, mpz t op1, mpz t op2);
-
-    // TODO mpz_lcm_ui
-    // /// Set rop to the least common multiple of op1 and op2. rop is always positive, irrespective of
-    // /// the signs of op1 and op2. rop will be zero if either op1 or op2 is zero.
-    // pub fn mpz_lcm_ui (mpz t rop, mpz t op1, mpir ui op2);
-
-    // TODO mpz_invert
-    // /// Compute the inverse of op1 modulo op2 and put the result in rop. If the inverse exists, the
-    // /// return value is non-zero and rop will satisfy 0 ≤ rop < op2. If an inverse doesn’t exist the
-    // /// return value is zero and rop is undefined.
-    // pub fn mpz_invert (mpz t rop, mpz t op1, mpz t op2) -> c_int;
-
-    // TODO mpz_jacobi
-    // /// Calculate the Jacobi symbol ( a b ).
-    // /// This is defined only for b odd.
-    // pub fn mpz_jacobi (mpz t a, mpz t b) -> c_int;
-
-    // TODO mpz_legendre
-    // /// Calculate the Legendre symbol ( a p ).
-    // /// This is defined only for p an odd positive prime, and
-    // /// for such p it’s identical to the Jacobi symbol.
-    // pub fn mpz_legendre (mpz t a, mpz t p) -> c_int;
-
-    // TODO mpz_kronecker
-    // /// Calculate the Jacobi symbol ( a b ) with the Kronecker extension ( a 2 ) = ( 2 a ) when a odd, or( a 2 ) = 0 when a even.
-    // ///
-    // /// When b is odd the Jacobi symbol and Kronecker symbol are identical, so mpz_kronecker_ui
-    // /// etc can be used for mixed precision Jacobi symbols too.
-    // ///
-    // /// For more information see Henri Cohen section 1.4.2 (see Appendix B [References], page 145),
-    // /// or any number theory textbook. See also the example program demos/qcn.c which uses
-    // /// mpz_kronecker_ui on the MPIR website.
-    // pub fn mpz_kronecker (mpz t a, mpz t b) -> c_int;
+    // mpz_probable_prime_p and mpz_likely_prime_p are not bound. They are MPIR only and
+    // preliminary, and Mpz::is_probably_prime covers them on both backends.
 
-    // TODO mpz_kronecker_si
-    // /// Calculate the Jacobi symbol ( a b ) with the Kronecker extension ( a 2 ) = ( 2 a ) when a odd, or( a 2 ) = 0 when a even.
-    // ///
-    // /// When b is odd the Jacobi symbol and Kronecker symbol are identical, so mpz_kronecker_ui
-    // /// etc can be used for mixed precision Jacobi symbols too.
-    // ///
-    // /// For more information see Henri Cohen section 1.4.2 (see Appendix B [References], page 145),
-    // /// or any number theory textbook. See also the example program demos/qcn.c which uses
-    // /// mpz_kronecker_ui on the MPIR website.
-    // pub fn mpz_kronecker_si (mpz t a, mpir si b) -> c_int;
+    /// Set rop to the next candidate prime greater than op. Note that this function will occasionally
+    /// return composites. It is designed to give a quick method for generating numbers which do
+    /// not have small prime factors (less than 1000) and which pass a small number of rounds of
+    /// Miller-Rabin (just two rounds).The test is designed for speed, assuming that a high quality
+    /// followup test can then be run to ensure primality.
+    ///
+    /// The variable state must be initialized by calling one of the gmp_randinit functions
+    /// (Section 9.1 [Random State Initialization], page 67) before invoking this function.
+    ///
+    /// MPIR only, not available with the `backend-gmp` feature; use mpz_nextprime instead.
+    #[cfg(not(feature = "backend-gmp"))]
+    #[link_name = "__gmpz_next_prime_candidate"]
+    pub fn mpz_next_prime_candidate(rop: mpz_ptr, op: mpz_srcptr, state: gmp_randstate_ptr);
 
-    // TODO mpz_kronecker_ui
-    // /// Calculate the Jacobi symbol ( a b ) with the Kronecker extension ( a 2 ) = ( 2 a ) when a odd, or( a 2 ) = 0 when a even.
-    // ///
-    // /// When b is odd the Jacobi symbol and Kronecker symbol are identical, so mpz_kronecker_ui
-    // /// etc can be used for mixed precision Jacobi symbols too.
-    // ///
-    // /// For more information see Henri Cohen section 1.4.2 (see Appendix B [References], page 145),
-    // /// or any number theory textbook. See also the example program demos/qcn.c which uses
-    // /// mpz_kronecker_ui on the MPIR website.
-    // pub fn mpz_kronecker_ui (mpz t a, mpir ui b) -> c_int;
+    /// Determine whether n is prime. Return 2 if n is definitely prime, return 1 if n is probably
+    /// prime (without being certain), or return 0 if n is definitely composite.
+    ///
+    /// This function does some trial divisions, then some Miller-Rabin probabilistic primality
+    /// tests. reps controls how many such tests are done, 5 to 10 is a reasonable number, more will
+    /// reduce the chances of a composite being returned as “probably prime”.
+    #[link_name = "__gmpz_probab_prime_p"]
+    pub fn mpz_probab_prime_p(n: mpz_srcptr, reps: c_int) -> c_int;
 
-    // TODO mpz_si_kronecker
-    // /// Calculate the Jacobi symbol ( a b ) with the Kronecker extension ( a 2 ) = ( 2 a ) when a odd, or( a 2 ) = 0 when a even.
-    // ///
-    // /// When b is odd the Jacobi symbol and Kronecker symbol are identical, so mpz_kronecker_ui
-    // /// etc can be used for mixed precision Jacobi symbols too.
-    // ///
-    // /// For more information see Henri Cohen section 1.4.2 (see Appendix B [References], page 145),
-    // /// or any number theory textbook. See also the example program demos/qcn.c which uses
-    // /// mpz_kronecker_ui on the MPIR website.
-    // pub fn mpz_si_kronecker (mpir si a, mpz t b) -> c_int;
+    /// Set rop to the next prime greater than op.
+    ///
+    /// This function uses a probabilistic algorithm to identify primes. For practical purposes it’s
+    /// adequate, the chance of a composite passing will be extremely small.
+    ///
+    /// Obsolete in MPIR, where mpz_next_prime_candidate is preferred.
+    #[link_name = "__gmpz_nextprime"]
+    pub fn mpz_nextprime(rop: mpz_ptr, op: mpz_srcptr);
 
-    // TODO mpz_ui_kronecker
-    // /// Calculate the Jacobi symbol ( a b ) with the Kronecker extension ( a 2 ) = ( 2 a ) when a odd, or( a 2 ) = 0 when a even.
-    // ///
-    // /// When b is odd the Jacobi symbol and Kronecker symbol are identical, so mpz_kronecker_ui
-    // /// etc can be used for mixed precision Jacobi symbols too.
-    // ///
-    // /// For more information see Henri Cohen section 1.4.2 (see Appendix B [References], page 145),
-    // /// or any number theory textbook. See also the example program demos/qcn.c which uses
-    // /// mpz_kronecker_ui on the MPIR website.
-    // pub fn mpz_ui_kronecker (mpir ui a, mpz t b) -> c_int;
-
-    // TODO mpz_remove
-    // /// Remove all occurrences of the factor f from op and store the result in rop. The return value
-    // /// is how many such occurrences were removed.
-    // pub fn mpz_remove (mpz t rop, mpz t op, mpz t f) -> mp_bitcnt_t;
-
-    // TODO mpz_fac_ui
-    // /// Set rop to the factorial of n: mpz_fac_ui computes the plain factorial n!, mpz_2fac_ui
-    // /// computes the double-factorial n!!, and mpz_mfac_uiui the m-multi-factorial n!(m).
-    // pub fn mpz_fac_ui (mpz t rop, unsigned long int n);
-
-    // TODO mpz_2fac_ui
-    // /// Set rop to the factorial of n: mpz_fac_ui computes the plain factorial n!, mpz_2fac_ui
-    // /// computes the double-factorial n!!, and mpz_mfac_uiui the m-multi-factorial n!(m).
-    // pub fn mpz_2fac_ui (mpz t rop, unsigned long int n);
-
-    // TODO mpz_mfac_uiui
-    // /// Set rop to the factorial of n: mpz_fac_ui computes the plain factorial n!, mpz_2fac_ui
-    // /// computes the double-factorial n!!, and mpz_mfac_uiui the m-multi-factorial n!(m).
-    // pub fn mpz_mfac_uiui (mpz t rop, unsigned long int n, unsigned long int m);
-
-    // TODO mpz_primorial_ui
-    // /// Set rop to the primorial of n, i.e. the product of all positive prime numbers ≤ n.
-    // pub fn mpz_primorial_ui (mpz t rop, unsigned long int n);
-
-    // TODO mpz_bin_ui
-    // /// Compute the binomial coefficient ( n k ) and store the result in rop.
-    // /// Negative values of n are supported by mpz_bin_ui, using the identity ( −n k ) = (−1)k ( n+k−1 k )
-    // pub fn mpz_bin_ui (mpz t rop, mpz t n, mpir ui k);
-
-    // TODO mpz_bin_uiui
-    // /// Compute the binomial coefficient ( n k ) and store the result in rop.
-    // /// Negative values of n are supported by mpz_bin_ui, using the identity ( −n k ) = (−1)k ( n+k−1 k )
-    // pub fn mpz_bin_uiui (mpz t rop, mpir ui n, mpir ui k);
-
-    // TODO mpz_fib_ui
-    // /// mpz_fib_ui sets fn to to Fn, the n’th Fibonacci number. mpz_fib2_ui sets fn to Fn, and fnsub1 to Fn−1.
-    // ///
-    // /// These functions are designed for calculating isolated Fibonacci numbers. When a sequence of
-    // /// values is wanted it’s best to start with mpz_fib2_ui and iterate the defining Fn+1 = Fn +Fn−1
-    // /// or similar.
-    // fn mpz_fib_ui (mpz t fn, mpir ui n);
+    /// Set rop to the greatest common divisor of op1 and op2. The result is always positive even if
+    /// one or both input operands are negative.
+    #[link_name = "__gmpz_gcd"]
+    pub fn mpz_gcd(rop: mpz_ptr, op1: mpz_srcptr, op2: mpz_srcptr);
 
-    // TODO mpz_fib2_ui
-    // /// mpz_fib_ui sets pub fn to to Fn, the n’th Fibonacci number. mpz_fib2_ui sets fn to Fn, and fnsub1 to Fn−1.
-    // ///
-    // /// These functions are designed for calculating isolated Fibonacci numbers. When a sequence of
-    // /// values is wanted it’s best to start with mpz_fib2_ui and iterate the defining Fn+1 = Fn +Fn−1
-    // /// or similar.
-    // pub fn mpz_fib2_ui (mpz t fn, mpz t fnsub1, mpir ui n);
-
-    // TODO mpz_lucnum_ui
-    // /// mpz_lucnum_ui sets ln to to Ln, the n’th Lucas number. mpz_lucnum2_ui sets ln to Ln, and
-    // /// lnsub1 to Ln−1.
-    // ///
-    // /// These functions are designed for calculating isolated Lucas numbers. When a sequence of
-    // /// values is wanted it’s best to start with mpz_lucnum2_ui and iterate the defining Ln+1 =
-    // /// Ln + Ln−1 or similar.
-    // ///
-    // /// The Fibonacci numbers and Lucas numbers are related sequences, so it’s never necessary
-    // /// to call both mpz_fib2_ui and mpz_lucnum2_ui. The formulas for going from Fibonacci to
-    // pub fn mpz_lucnum_ui (mpz t ln, mpir ui n);
+    /// Compute the greatest common divisor of op1 and op2. If rop is not NULL, store the result
+    /// there.
+    ///
+    /// If the result is small enough to fit in an mpir_ui, it is returned. If the result does not fit, 0
+    /// is returned, and the result is equal to the argument op1. Note that the result will always fit
+    /// if op2 is non-zero.
+    #[link_name = "__gmpz_gcd_ui"]
+    pub fn mpz_gcd_ui(rop: mpz_ptr, op1: mpz_srcptr, op2: c_ulong) -> c_ulong;
+
+    /// Set g to the greatest common divisor of a and b, and in addition set s and t to coefficients
+    /// satisfying as + bt = g. The value in g is always positive, even if one or both of a and b
+    /// are negative (or zero if both inputs are zero). The values in s and t are chosen such that
+    /// normally, |s| < |b|/(2g) and |t| < |a|/(2g), and these relations define s and t uniquely. There
+    /// are a few exceptional cases:
+    ///
+    /// If |a| = |b|, then s = 0, t = sgn(b).
+    ///
+    /// Otherwise, s = sgn(a) if b = 0 or |b| = 2g, and t = sgn(b) if a = 0 or |a| = 2g.
+    ///
+    /// In all cases, s = 0 if and only if g = |b|, i.e., if b divides a or a = b = 0.
+    ///
+    /// If t is NULL then that value is not computed.
+    #[link_name = "__gmpz_gcdext"]
+    pub fn mpz_gcdext(g: mpz_ptr, s: mpz_ptr, t: mpz_ptr, a: mpz_srcptr, b: mpz_srcptr);
+
+    /// Set rop to the least common multiple of op1 and op2. rop is always positive, irrespective of
+    /// the signs of op1 and op2. rop will be zero if either op1 or op2 is zero.
+    #[link_name = "__gmpz_lcm"]
+    pub fn mpz_lcm(rop: mpz_ptr, op1: mpz_srcptr, op2: mpz_srcptr);
+
+    /// Set rop to the least common multiple of op1 and op2. rop is always positive, irrespective of
+    /// the signs of op1 and op2. rop will be zero if either op1 or op2 is zero.
+    #[link_name = "__gmpz_lcm_ui"]
+    pub fn mpz_lcm_ui(rop: mpz_ptr, op1: mpz_srcptr, op2: c_ulong);
+
+    /// Compute the inverse of op1 modulo op2 and put the result in rop. If the inverse exists, the
+    /// return value is non-zero and rop will satisfy 0 ≤ rop < op2. If an inverse doesn’t exist the
+    /// return value is zero and rop is undefined.
+    #[link_name = "__gmpz_invert"]
+    pub fn mpz_invert(rop: mpz_ptr, op1: mpz_srcptr, op2: mpz_srcptr) -> c_int;
+
+    /// Calculate the Jacobi symbol (a/b).
+    ///
+    /// This is defined only for b odd.
+    #[link_name = "__gmpz_jacobi"]
+    pub fn mpz_jacobi(a: mpz_srcptr, b: mpz_srcptr) -> c_int;
 
-    // TODO mpz_lucnum2_ui
-    // /// mpz_lucnum_ui sets ln to to Ln, the n’th Lucas number. mpz_lucnum2_ui sets ln to Ln, and
-    // /// lnsub1 to Ln−1.
-    // ///
-    // /// These functions are designed for calculating isolated Lucas numbers. When a sequence of
-    // /// values is wanted it’s best to start with mpz_lucnum2_ui and iterate the defining Ln+1 =
-    // /// Ln + Ln−1 or similar.
-    // ///
-    // /// The Fibonacci numbers and Lucas numbers are related sequences, so it’s never necessary
-    // /// to call both mpz_fib2_ui and mpz_lucnum2_ui. The formulas for going from Fibonacci to
-    // pub fn mpz_lucnum2_ui (mpz t ln, mpz t lnsub1, mpir ui n);
+    /// Calculate the Legendre symbol (a/p).
+    ///
+    /// This is defined only for p an odd positive prime, and for such p it’s identical to the
+    /// Jacobi symbol.
+    #[link_name = "__gmpz_legendre"]
+    pub fn mpz_legendre(a: mpz_srcptr, p: mpz_srcptr) -> c_int;
+
+    /// Calculate the Jacobi symbol (a/b) with the Kronecker extension (a/2) = (2/a) when a odd, or
+    /// (a/2) = 0 when a even.
+    ///
+    /// When b is odd the Jacobi symbol and Kronecker symbol are identical, so mpz_kronecker_ui
+    /// etc can be used for mixed precision Jacobi symbols too.
+    ///
+    /// For more information see Henri Cohen section 1.4.2 (see Appendix B [References], page 145),
+    /// or any number theory textbook. See also the example program demos/qcn.c which uses
+    /// mpz_kronecker_ui on the MPIR website.
+    ///
+    /// The headers define mpz_kronecker as mpz_jacobi, which handles both.
+    #[link_name = "__gmpz_jacobi"]
+    pub fn mpz_kronecker(a: mpz_srcptr, b: mpz_srcptr) -> c_int;
+
+    /// Calculate the Jacobi symbol (a/b) with the Kronecker extension (a/2) = (2/a) when a odd, or
+    /// (a/2) = 0 when a even.
+    ///
+    /// When b is odd the Jacobi symbol and Kronecker symbol are identical, so mpz_kronecker_ui
+    /// etc can be used for mixed precision Jacobi symbols too.
+    ///
+    /// For more information see Henri Cohen section 1.4.2 (see Appendix B [References], page 145),
+    /// or any number theory textbook. See also the example program demos/qcn.c which uses
+    /// mpz_kronecker_ui on the MPIR website.
+    #[link_name = "__gmpz_kronecker_si"]
+    pub fn mpz_kronecker_si(a: mpz_srcptr, b: c_long) -> c_int;
+
+    /// Calculate the Jacobi symbol (a/b) with the Kronecker extension (a/2) = (2/a) when a odd, or
+    /// (a/2) = 0 when a even.
+    ///
+    /// When b is odd the Jacobi symbol and Kronecker symbol are identical, so mpz_kronecker_ui
+    /// etc can be used for mixed precision Jacobi symbols too.
+    ///
+    /// For more information see Henri Cohen section 1.4.2 (see Appendix B [References], page 145),
+    /// or any number theory textbook. See also the example program demos/qcn.c which uses
+    /// mpz_kronecker_ui on the MPIR website.
+    #[link_name = "__gmpz_kronecker_ui"]
+    pub fn mpz_kronecker_ui(a: mpz_srcptr, b: c_ulong) -> c_int;
+
+    /// Calculate the Jacobi symbol (a/b) with the Kronecker extension (a/2) = (2/a) when a odd, or
+    /// (a/2) = 0 when a even.
+    ///
+    /// When b is odd the Jacobi symbol and Kronecker symbol are identical, so mpz_kronecker_ui
+    /// etc can be used for mixed precision Jacobi symbols too.
+    ///
+    /// For more information see Henri Cohen section 1.4.2 (see Appendix B [References], page 145),
+    /// or any number theory textbook. See also the example program demos/qcn.c which uses
+    /// mpz_kronecker_ui on the MPIR website.
+    #[link_name = "__gmpz_si_kronecker"]
+    pub fn mpz_si_kronecker(a: c_long, b: mpz_srcptr) -> c_int;
+
+    /// Calculate the Jacobi symbol (a/b) with the Kronecker extension (a/2) = (2/a) when a odd, or
+    /// (a/2) = 0 when a even.
+    ///
+    /// When b is odd the Jacobi symbol and Kronecker symbol are identical, so mpz_kronecker_ui
+    /// etc can be used for mixed precision Jacobi symbols too.
+    ///
+    /// For more information see Henri Cohen section 1.4.2 (see Appendix B [References], page 145),
+    /// or any number theory textbook. See also the example program demos/qcn.c which uses
+    /// mpz_kronecker_ui on the MPIR website.
+    #[link_name = "__gmpz_ui_kronecker"]
+    pub fn mpz_ui_kronecker(a: c_ulong, b: mpz_srcptr) -> c_int;
+
+    /// Remove all occurrences of the factor f from op and store the result in rop. The return value
+    /// is how many such occurrences were removed.
+    #[link_name = "__gmpz_remove"]
+    pub fn mpz_remove(rop: mpz_ptr, op: mpz_srcptr, f: mpz_srcptr) -> mp_bitcnt_t;
+
+    /// Set rop to the factorial of n: mpz_fac_ui computes the plain factorial n!, mpz_2fac_ui
+    /// computes the double-factorial n!!, and mpz_mfac_uiui the m-multi-factorial n!(m).
+    #[link_name = "__gmpz_fac_ui"]
+    pub fn mpz_fac_ui(rop: mpz_ptr, n: c_ulong);
+
+    /// Set rop to the factorial of n: mpz_fac_ui computes the plain factorial n!, mpz_2fac_ui
+    /// computes the double-factorial n!!, and mpz_mfac_uiui the m-multi-factorial n!(m).
+    #[link_name = "__gmpz_2fac_ui"]
+    pub fn mpz_2fac_ui(rop: mpz_ptr, n: c_ulong);
+
+    /// Set rop to the factorial of n: mpz_fac_ui computes the plain factorial n!, mpz_2fac_ui
+    /// computes the double-factorial n!!, and mpz_mfac_uiui the m-multi-factorial n!(m).
+    #[link_name = "__gmpz_mfac_uiui"]
+    pub fn mpz_mfac_uiui(rop: mpz_ptr, n: c_ulong, m: c_ulong);
+
+    /// Set rop to the primorial of n, i.e. the product of all positive prime numbers ≤ n.
+    #[link_name = "__gmpz_primorial_ui"]
+    pub fn mpz_primorial_ui(rop: mpz_ptr, n: c_ulong);
+
+    /// Compute the binomial coefficient (n k) and store the result in rop.
+    ///
+    /// Negative values of n are supported by mpz_bin_ui, using the identity
+    /// (−n k) = (−1)^k (n+k−1 k).
+    #[link_name = "__gmpz_bin_ui"]
+    pub fn mpz_bin_ui(rop: mpz_ptr, n: mpz_srcptr, k: c_ulong);
+
+    /// Compute the binomial coefficient (n k) and store the result in rop.
+    ///
+    /// Negative values of n are supported by mpz_bin_ui, using the identity
+    /// (−n k) = (−1)^k (n+k−1 k).
+    #[link_name = "__gmpz_bin_uiui"]
+    pub fn mpz_bin_uiui(rop: mpz_ptr, n: c_ulong, k: c_ulong);
+
+    /// mpz_fib_ui sets fn to to Fn, the n’th Fibonacci number. mpz_fib2_ui sets fn to Fn, and fnsub1
+    /// to Fn−1.
+    ///
+    /// These functions are designed for calculating isolated Fibonacci numbers. When a sequence of
+    /// values is wanted it’s best to start with mpz_fib2_ui and iterate the defining Fn+1 = Fn +Fn−1
+    /// or similar.
+    #[link_name = "__gmpz_fib_ui"]
+    pub fn mpz_fib_ui(fn_: mpz_ptr, n: c_ulong);
+
+    /// mpz_fib_ui sets fn to to Fn, the n’th Fibonacci number. mpz_fib2_ui sets fn to Fn, and fnsub1
+    /// to Fn−1.
+    ///
+    /// These functions are designed for calculating isolated Fibonacci numbers. When a sequence of
+    /// values is wanted it’s best to start with mpz_fib2_ui and iterate the defining Fn+1 = Fn +Fn−1
+    /// or similar.
+    #[link_name = "__gmpz_fib2_ui"]
+    pub fn mpz_fib2_ui(fn_: mpz_ptr, fnsub1: mpz_ptr, n: c_ulong);
+
+    /// mpz_lucnum_ui sets ln to to Ln, the n’th Lucas number. mpz_lucnum2_ui sets ln to Ln, and
+    /// lnsub1 to Ln−1.
+    ///
+    /// These functions are designed for calculating isolated Lucas numbers. When a sequence of
+    /// values is wanted it’s best to start with mpz_lucnum2_ui and iterate the defining Ln+1 =
+    /// Ln + Ln−1 or similar.
+    ///
+    /// The Fibonacci numbers and Lucas numbers are related sequences, so it’s never necessary
+    /// to call both mpz_fib2_ui and mpz_lucnum2_ui. The formulas for going from Fibonacci to
+    /// Lucas numbers are in the Lucas Numbers Algorithm section, and the reverse is straightforward
+    /// too.
+    #[link_name = "__gmpz_lucnum_ui"]
+    pub fn mpz_lucnum_ui(ln: mpz_ptr, n: c_ulong);
+
+    /// mpz_lucnum_ui sets ln to to Ln, the n’th Lucas number. mpz_lucnum2_ui sets ln to Ln, and
+    /// lnsub1 to Ln−1.
+    ///
+    /// These functions are designed for calculating isolated Lucas numbers. When a sequence of
+    /// values is wanted it’s best to start with mpz_lucnum2_ui and iterate the defining Ln+1 =
+    /// Ln + Ln−1 or similar.
+    ///
+    /// The Fibonacci numbers and Lucas numbers are related sequences, so it’s never necessary
+    /// to call both mpz_fib2_ui and mpz_lucnum2_ui. The formulas for going from Fibonacci to
+    /// Lucas numbers are in the Lucas Numbers Algorithm section, and the reverse is straightforward
+    /// too.
+    #[link_name = "__gmpz_lucnum2_ui"]
+    pub fn mpz_lucnum2_ui(ln: mpz_ptr, lnsub1: mpz_ptr, n: c_ulong);
 
     // ---------------------------------------------------------------------------------------------
     // Comparison Functions
@@ -697,6 +870,7 @@ extern "C" {
     ///
     /// mpz_cmp_ui and mpz_cmp_si are macros and will evaluate their arguments more than once.
     /// mpz_cmp_d can be called with an infinity, but results are undefined for a NaN.
+    #[link_name = "__gmpz_cmp"]
     pub fn mpz_cmp (op1: mpz_srcptr, op2: mpz_srcptr) -> c_int;
 
     /// Compare op1 and op2. Return a positive value if op1 > op2, zero if op1 = op2, or a negative
@@ -704,12 +878,14 @@ extern "C" {
     ///
     /// mpz_cmp_ui and mpz_cmp_si are macros and will evaluate their arguments more than once.
     /// mpz_cmp_d can be called with an infinity, but results are undefined for a NaN.
+    #[link_name = "__gmpz_cmp_d"]
     pub fn mpz_cmp_d (op1: mpz_srcptr, op2: c_double) -> c_int;
 
     /// Compare op1 and op2. Return a positive value if op1 > op2, zero if op1 = op2, or a negative
     /// value if op1 < op2.
     ///cros and will evaluate their arguments more than once.
     /// mpz_cmp_d can be called with an infinity, but results are undefined for a NaN.
+    #[link_name = "__gmpz_cmp_si"]
     pub fn mpz_cmp_si (op1: mpz_srcptr, op2: c_long) -> c_int;
 
     /// Compare op1 and op2. Return a positive value if op1 > op2, zero if op1 = op2, or a negative
@@ -717,29 +893,31 @@ extern "C" {
     ///
     /// mpz_cmp_ui and mpz_cmp_si are macros and will evaluate their arguments more than once.
     /// mpz_cmp_d can be called with an infinity, but results are undefined for a NaN.
+    #[link_name = "__gmpz_cmp_ui"]
     pub fn mpz_cmp_ui (op1: mpz_srcptr, op2: c_ulong) -> c_int;
 
     /// Compare the absolute values of op1 and op2. Return a positive value if |op1| > |op2|, zero
     /// if |op1| = |op2|, or a negative value if |op1| < |op2|.
     ///
     /// mpz_cmpabs_d can be called with an infinity, but results are undefined for a NaN.
+    #[link_name = "__gmpz_cmpabs"]
     pub fn mpz_cmpabs (op1: mpz_srcptr, op2: mpz_srcptr) -> c_int;
 
     /// Compare the absolute values of op1 and op2. Return a positive value if |op1| > |op2|, zero
     /// if |op1| = |op2|, or a negative value if |op1| < |op2|.
     ///
     /// mpz_cmpabs_d can be called with an infinity, but results are undefined for a NaN.
+    #[link_name = "__gmpz_cmpabs_d"]
     pub fn mpz_cmpabs_d (op1: mpz_srcptr, op2: c_double) -> c_int;
 
     /// Compare the absolute values of op1 and op2. Return a positive value if |op1| > |op2|, zero
     /// if |op1| = |op2|, or a negative value if |op1| < |op2|.
     ///
     /// mpz_cmpabs_d can be called with an infinity, but results are undefined for a NaN.
+    #[link_name = "__gmpz_cmpabs_ui"]
     pub fn mpz_cmpabs_ui (op1: mpz_srcptr, op2: c_ulong) -> c_int;
 
-    /// Return +1 if op > 0, 0 if op = 0, and −1 if op < 0.
-    /// This function is actually implemented as a macro. It evaluates its argument multiple times.
-    fn mpz_sgn (op1: mpz_srcptr) -> c_int;
+    // mpz_sgn is a macro, see below.
 
     // ---------------------------------------------------------------------------------------------
     // Logical and Bit Manipulation Functions
@@ -748,27 +926,33 @@ extern "C" {
     is the actual implementation). The least significant bit is number 0. */
 
     /// Set rop to op1 bitwise-and op2.
+    #[link_name = "__gmpz_and"]
     pub fn mpz_and (rop: mpz_ptr, op1: mpz_srcptr, op2: mpz_srcptr);
 
     /// Set rop to op1 bitwise inclusive-or op2.
+    #[link_name = "__gmpz_ior"]
     pub fn mpz_ior (rop: mpz_ptr, op1: mpz_srcptr, op2: mpz_srcptr);
 
     /// Set rop to op1 bitwise exclusive-or op2.
+    #[link_name = "__gmpz_xor"]
     pub fn mpz_xor (rop: mpz_ptr, op1: mpz_srcptr, op2: mpz_srcptr);
 
     /// Set rop to the one’s complement of op.
+    #[link_name = "__gmpz_com"]
     pub fn mpz_com (rop: mpz_ptr, op: mpz_srcptr);
 
     /// If op ≥ 0, return the population count of op, which is the number of 1 bits in the binary
     /// representation. If op < 0, the number of 1s is infinite, and the return value is ULONG MAX,
     /// the largest possible mp_bitcnt_t.
-    pub fn mpz_popcount (rop: mpz_ptr) -> mp_bitcnt_t;
+    #[link_name = "__gmpz_popcount"]
+    pub fn mpz_popcount (op: mpz_srcptr) -> mp_bitcnt_t;
 
     /// If op1 and op2 are both ≥ 0 or both < 0, return the hamming distance between the two
     /// operands, which is the number of bit positions where op1 and op2 have different bit values.
     ///
     /// If one operand is ≥ 0 and the other < 0 then the number of bits different is infinite, and the
     /// return value is the largest possible imp_bitcnt_t.
+    #[link_name = "__gmpz_hamdist"]
     pub fn mpz_hamdist (op1: mpz_srcptr, op2: mpz_srcptr) -> mp_bitcnt_t;
 
     /// Scan op, starting from bit starting bit, towards more significant bits, until the first 0 or 1 bit
@@ -778,6 +962,7 @@ extern "C" {
     /// If there’s no bit found, then the largest possible mp_bitcnt_t is returned. This will happen
     /// in mpz_scan0 past the end of a positive number, or mpz_scan1 past the end of a nonnegative
     /// number.
+    #[link_name = "__gmpz_scan0"]
     pub fn mpz_scan0 (op: mpz_srcptr, starting_bit: mp_bitcnt_t) -> mp_bitcnt_t;
 
     /// Scan op, starting from bit starting bit, towards more significant bits, until the first 0 or 1 bit
@@ -787,18 +972,23 @@ extern "C" {
     /// If there’s no bit found, then the largest possible mp_bitcnt_t is returned. This will happen
     /// in mpz_scan0 past the end of a positive number, or mpz_scan1 past the end of a nonnegative
     /// number.
+    #[link_name = "__gmpz_scan1"]
     pub fn mpz_scan1 (op: mpz_srcptr, starting_bit: mp_bitcnt_t) -> mp_bitcnt_t;
 
     /// Set bit bit index in rop.
+    #[link_name = "__gmpz_setbit"]
     pub fn mpz_setbit (rop: mpz_ptr, bit_index: mp_bitcnt_t);
 
     /// Clear bit bit index in rop.
+    #[link_name = "__gmpz_clrbit"]
     pub fn mpz_clrbit (rop: mpz_ptr, bit_index: mp_bitcnt_t);
 
     /// Complement bit bit index in rop.
+    #[link_name = "__gmpz_combit"]
     pub fn mpz_combit (rop: mpz_ptr, bit_index: mp_bitcnt_t);
 
     /// Test bit bit index in op and return 0 or 1 accordingly.
+    #[link_name = "__gmpz_tstbit"]
     pub fn mpz_tstbit (op: mpz_srcptr, starting_bit: mp_bitcnt_t) -> c_int;
 
     // ---------------------------------------------------------------------------------------------
@@ -811,64 +1001,65 @@ extern "C" {
     not to use random number functions.
     */
 
-    // TODO mpz_urandomb
-    // /// Generate a uniformly distributed random integer in the range 0 to 2n − 1, inclusive.
-    // ///
-    // /// The variable state must be initialized by calling one of the gmp_randinit functions
-    // /// (Section 9.1 [Random State Initialization], page 67) before invoking this function.
-    // pub fn mpz_urandomb (mpz t rop, gmp randstate t state, mp bitcnt t n);
+    /// Generate a uniformly distributed random integer in the range 0 to 2^n − 1, inclusive.
+    ///
+    /// The variable state must be initialized by calling one of the gmp_randinit functions
+    /// (Section 9.1 [Random State Initialization], page 67) before invoking this function.
+    #[link_name = "__gmpz_urandomb"]
+    pub fn mpz_urandomb(rop: mpz_ptr, state: gmp_randstate_ptr, n: mp_bitcnt_t);
 
-    // TODO mpz_urandomm
-    // /// Generate a uniform random integer in the range 0 to n − 1, inclusive.
-    // ///
-    // /// The variable state must be initialized by calling one of the gmp_randinit functions
-    // /// (Section 9.1 [Random State Initialization], page 67) before invoking this function.
-    // pub fn mpz_urandomm (mpz t rop, gmp randstate t state, mpz t n);
+    /// Generate a uniform random integer in the range 0 to n − 1, inclusive.
+    ///
+    /// The variable state must be initialized by calling one of the gmp_randinit functions
+    /// (Section 9.1 [Random State Initialization], page 67) before invoking this function.
+    #[link_name = "__gmpz_urandomm"]
+    pub fn mpz_urandomm(rop: mpz_ptr, state: gmp_randstate_ptr, n: mpz_srcptr);
 
-    // TODO mpz_rrandomb
-    // /// Generate a random integer with long strings of zeros and ones in the binary representation.
-    // ///
-    // /// Useful for testing functions and algorithms, since this kind of random numbers have proven
-    // /// to be more likely to trigger corner-case bugs. The random number will be in the range 0 to
-    // /// 2n − 1, inclusive.
-    // /// The variable state must be initialized by calling one of the gmp_randinit functions
-    // /// (Section 9.1 [Random State Initialization], page 67) before invoking this function.
-    // pub fn mpz_rrandomb (mpz t rop, gmp randstate t state, mp bitcnt t n);
+    /// Generate a random integer with long strings of zeros and ones in the binary representation.
+    ///
+    /// Useful for testing functions and algorithms, since this kind of random numbers have proven
+    /// to be more likely to trigger corner-case bugs. The random number will be in the range 0 to
+    /// 2^n − 1, inclusive.
+    ///
+    /// The variable state must be initialized by calling one of the gmp_randinit functions
+    /// (Section 9.1 [Random State Initialization], page 67) before invoking this function.
+    #[link_name = "__gmpz_rrandomb"]
+    pub fn mpz_rrandomb(rop: mpz_ptr, state: gmp_randstate_ptr, n: mp_bitcnt_t);
 
     // ---------------------------------------------------------------------------------------------
     // Miscellaneous Functions
 
     /// Return non-zero iff the value of op fits in an unsigned long, long, unsigned int, signed
     /// int, unsigned short int, or signed short int, respectively. Otherwise, return zero.
+    #[link_name = "__gmpz_fits_ulong_p"]
     pub fn mpz_fits_ulong_p (op: mpz_srcptr) -> c_int;
 
     /// Return non-zero iff the value of op fits in an unsigned long, long, unsigned int, signed
     /// int, unsigned short int, or signed short int, respectively. Otherwise, return zero.
+    #[link_name = "__gmpz_fits_slong_p"]
     pub fn mpz_fits_slong_p (op: mpz_srcptr) -> c_int;
 
     /// Return non-zero iff the value of op fits in an unsigned long, long, unsigned int, signed
     /// int, unsigned short int, or signed short int, respectively. Otherwise, return zero.
+    #[link_name = "__gmpz_fits_uint_p"]
     pub fn mpz_fits_uint_p (op: mpz_srcptr) -> c_int;
 
     /// Return non-zero iff the value of op fits in an unsigned long, long, unsigned int, signed
     /// int, unsigned short int, or signed short int, respectively. Otherwise, return zero.
+    #[link_name = "__gmpz_fits_sint_p"]
     pub fn mpz_fits_sint_p (op: mpz_srcptr) -> c_int;
 
     /// Return non-zero iff the value of op fits in an unsigned long, long, unsigned int, signed
     /// int, unsigned short int, or signed short int, respectively. Otherwise, return zero.
+    #[link_name = "__gmpz_fits_ushort_p"]
     pub fn mpz_fits_ushort_p (op: mpz_srcptr) -> c_int;
 
     /// Return non-zero iff the value of op fits in an unsigned long, long, unsigned int, signed
     /// int, unsigned short int, or signed short int, respectively. Otherwise, return zero.
+    #[link_name = "__gmpz_fits_sshort_p"]
     pub fn mpz_fits_sshort_p (op: mpz_srcptr) -> c_int;
 
-    /// Determine whether op is odd or even, respectively. Return non-zero if yes, zero if no. These
-    /// macros evaluate their argument more than once.
-    pub fn mpz_odd_p (op: mpz_srcptr) -> c_int;
-
-    /// Determine whether op is odd or even, respectively. Return non-zero if yes, zero if no. These
-    /// macros evaluate their argument more than once.
-    pub fn mpz_even_p (op: mpz_srcptr) -> c_int;
+    // mpz_odd_p and mpz_even_p are macros, see below.
 
     /// Return the size of op measured in number of digits in the given base. base can vary from 2
     /// to 36.
@@ -884,6 +1075,7 @@ extern "C" {
     /// It will be noted that mpz_sizeinbase(op,2) can be used to locate the most significant 1 bit
     /// in op, counting from 1. (Unlike the bitwise functions which start from 0, See Section 5.11
     /// [Logical and Bit Manipulation Functions], page 39.)
+    #[link_name = "__gmpz_sizeinbase"]
     pub fn mpz_sizeinbase (op: mpz_srcptr, base: c_int) -> size_t;
 
     // ---------------------------------------------------------------------------------------------
@@ -936,9 +1128,7 @@ extern "C" {
     // /// is outside the range 0 to mpz_size(op)-1.
     // mp_limb_t mpz_getlimbn (mpz t op, mp size t n)
 
-    /// Return the size of op measured in number of limbs. If op is zero, the returned value will be
-    /// zero.
-    fn mpz_size (op: mpz_srcptr) -> size_t;
+    // mpz_size is implemented in Rust, see below.
 
     // TODO mpz_limbs_read
     // /// Return a pointer to the limb array representing the absolute value of x.
@@ -1007,14 +1197,631 @@ extern "C" {
     // ---------------------------------------------------------------------------------------------
 }
 
+// -------------------------------------------------------------------------------------------------
+// Macros
+
+/* These are macros in mpir.h rather than exported functions, so there is no symbol to link
+against. They are implemented here by reading the mpz_struct directly, as the macros do. */
+
+/// Set r to n mod d. The sign of the divisor is ignored; the result is always non-negative.
+///
+/// mpz_mod_ui is identical to mpz_fdiv_r_ui above, returning the remainder as well as setting
+/// r. See mpz_fdiv_ui above if only the return value is wanted.
+///
+/// # Safety
+///
+/// `r` and `n` must point to initialised `mpz_struct`s.
+pub unsafe fn mpz_mod_ui(r: mpz_ptr, n: mpz_srcptr, d: c_ulong) -> c_ulong {
+    mpz_fdiv_r_ui(r, n, d)
+}
+
+/// Return +1 if op > 0, 0 if op = 0, and −1 if op < 0.
+///
+/// # Safety
+///
+/// `op` must point to an initialised `mpz_struct`.
+pub unsafe fn mpz_sgn(op: mpz_srcptr) -> c_int {
+    (*op)._mp_size.signum()
+}
+
+/// Return the size of op measured in number of limbs. If op is zero, the returned value will be
+/// zero.
+///
+/// This is an exported function in MPIR, but it only reads `_mp_size`, so it is implemented here
+/// the same way to avoid the call.
+///
+/// # Safety
+///
+/// `op` must point to an initialised `mpz_struct`.
+pub unsafe fn mpz_size(op: mpz_srcptr) -> size_t {
+    (*op)._mp_size.unsigned_abs() as size_t
+}
+
+/// Determine whether op is odd. Return non-zero if yes, zero if no.
+///
+/// # Safety
+///
+/// `op` must point to an initialised `mpz_struct`.
+pub unsafe fn mpz_odd_p(op: mpz_srcptr) -> c_int {
+    ((*op)._mp_size != 0 && *((*op)._mp_d as *const mp_limb_t) & 1 == 1) as c_int
+}
+
+/// Determine whether op is even. Return non-zero if yes, zero if no.
+///
+/// # Safety
+///
+/// `op` must point to an initialised `mpz_struct`.
+pub unsafe fn mpz_even_p(op: mpz_srcptr) -> c_int {
+    (mpz_odd_p(op) == 0) as c_int
+}
+
+/// Arbitrary precision integer.
+///
+/// Owns an initialised `mpz_struct`, which is cleared when the value is dropped. The layout is the
+/// same, so the numerator and denominator of an `mpq_struct` can be borrowed as `Mpz`.
+///
+/// Any operation that allocates aborts the process if the allocation fails, as the standard
+/// collections do. MPIR cannot recover from it; see [`crate::alloc`].
+#[repr(transparent)]
 pub struct Mpz(mpz_struct);
 
-impl Mpz {}
+// The limb storage is uniquely owned by the value, and MPIR never mutates through a `mpz_srcptr`.
+unsafe impl Send for Mpz {}
+unsafe impl Sync for Mpz {}
+
+impl Mpz {
+    /// Create a new integer with the value 0.
+    pub fn new() -> Self {
+        unsafe { Self::from_init(|x| mpz_init(x)) }
+    }
+
+    /// Create a new integer with the value 0, and space for `n` bits.
+    ///
+    /// This is only the initial space, the integer will still grow if a larger value is stored.
+    pub fn with_capacity_bits(n: mp_bitcnt_t) -> Self {
+        unsafe { Self::from_init(|x| mpz_init2(x, n)) }
+    }
+
+    /// Pointer to the underlying `mpz_struct`, for passing as a source operand to MPIR.
+    pub fn as_raw(&self) -> mpz_srcptr {
+        &self.0
+    }
+
+    /// Mutable pointer to the underlying `mpz_struct`, for passing as an output operand to MPIR.
+    pub fn as_raw_mut(&mut self) -> mpz_ptr {
+        &mut self.0
+    }
+
+    /// Return the sign of the integer.
+    pub fn sign(&self) -> Sign {
+        match unsafe { mpz_sgn(self.as_raw()) } {
+            0 => Sign::Zero,
+            s if s < 0 => Sign::Negative,
+            _ => Sign::Positive,
+        }
+    }
+
+    /// Return `true` if the integer is zero.
+    pub fn is_zero(&self) -> bool {
+        self.sign() == Sign::Zero
+    }
+
+    /// Return `true` if the integer is greater than zero.
+    pub fn is_positive(&self) -> bool {
+        self.sign() == Sign::Positive
+    }
+
+    /// Return `true` if the integer is less than zero.
+    pub fn is_negative(&self) -> bool {
+        self.sign() == Sign::Negative
+    }
+
+    /// Return -1, 0 or 1 depending on the sign of the integer.
+    pub fn signum(&self) -> Mpz {
+        Mpz::from(unsafe { mpz_sgn(self.as_raw()) })
+    }
+
+    /// The limbs in use, least significant first. The sign is not included, and zero has none.
+    pub(crate) fn limbs(&self) -> &[mp_limb_t] {
+        match self.0._mp_size.unsigned_abs() as usize {
+            0 => &[],
+            n => unsafe { std::slice::from_raw_parts(self.0._mp_d as *const mp_limb_t, n) },
+        }
+    }
+
+    /// Run one of the `mpz_init*` functions on fresh storage and take ownership of the result.
+    ///
+    /// # Safety
+    ///
+    /// `init` must fully initialise the `mpz_struct` it is given.
+    pub(crate) unsafe fn from_init<F: FnOnce(mpz_ptr)>(init: F) -> Self {
+        crate::alloc::init();
+        let mut x = MaybeUninit::<mpz_struct>::uninit();
+        init(x.as_mut_ptr());
+        Mpz(x.assume_init())
+    }
+}
+
+impl Default for Mpz {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Clone for Mpz {
+    fn clone(&self) -> Self {
+        unsafe { Self::from_init(|x| mpz_init_set(x, self.as_raw())) }
+    }
+
+    fn clone_from(&mut self, source: &Self) {
+        unsafe { mpz_set(self.as_raw_mut(), source.as_raw()) }
+    }
+}
+
+impl Drop for Mpz {
+    fn drop(&mut self) {
+        unsafe { mpz_clear(self.as_raw_mut()) }
+    }
+}
 
 #[cfg(test)]
 mod test {
     use super::*;
+    use crate::rand::RandState;
+    use std::ptr::null_mut;
+
+    #[test]
+    fn new() {
+        let x = Mpz::new();
+        assert_eq!(x.0._mp_size, 0);
+        assert_eq!(unsafe { mpz_cmp_ui(x.as_raw(), 0) }, 0);
+    }
+
+    #[test]
+    fn default() {
+        let x = Mpz::default();
+        assert_eq!(unsafe { mpz_cmp_ui(x.as_raw(), 0) }, 0);
+    }
+
+    #[test]
+    fn with_capacity_bits() {
+        let x = Mpz::with_capacity_bits(1024);
+        assert_eq!(unsafe { mpz_cmp_ui(x.as_raw(), 0) }, 0);
+        assert!(x.0._mp_alloc as u32 * mp_limb_t::BITS >= 1024);
+    }
+
+    #[test]
+    fn clone() {
+        let mut x = Mpz::new();
+        unsafe { mpz_set_ui(x.as_raw_mut(), 123456789) };
+        let y = x.clone();
+        assert_eq!(unsafe { mpz_cmp(x.as_raw(), y.as_raw()) }, 0);
+        assert_ne!(x.0._mp_d, y.0._mp_d);
+    }
+
+    #[test]
+    fn clone_from() {
+        let mut x = Mpz::new();
+        unsafe { mpz_set_ui(x.as_raw_mut(), 987654321) };
+        let mut y = Mpz::with_capacity_bits(256);
+        let limbs = y.0._mp_d;
+        y.clone_from(&x);
+        assert_eq!(unsafe { mpz_cmp(x.as_raw(), y.as_raw()) }, 0);
+        assert_eq!(y.0._mp_d, limbs);
+    }
+
+    #[test]
+    fn sign() {
+        let values = [(-5i64, Sign::Negative), (0, Sign::Zero), (7, Sign::Positive)];
+        for (v, sign) in values {
+            let x = Mpz::from(v);
+            assert_eq!(x.sign(), sign);
+            assert_eq!(x.is_zero(), v == 0);
+            assert_eq!(x.is_positive(), v > 0);
+            assert_eq!(x.is_negative(), v < 0);
+            assert_eq!(i64::try_from(x.signum()), Ok(v.signum()));
+        }
+        let big = Mpz::from(-1) << 200u32;
+        assert_eq!(big.sign(), Sign::Negative);
+        assert_eq!(i64::try_from(big.signum()), Ok(-1));
+    }
+
+    // ---------------------------------------------------------------------------------------------
+    // Bindings
+    //
+    // Each binding is called against a known result, so that a signature that disagrees with the
+    // MPIR headers shows up as a wrong value rather than silent corruption.
+
+    fn int(v: i64) -> Mpz {
+        Mpz::from(v)
+    }
+
+    fn get(x: &Mpz) -> i64 {
+        i64::try_from(x).unwrap()
+    }
+
+    fn raw<F: FnOnce(mpz_ptr)>(f: F) -> i64 {
+        let mut r = Mpz::new();
+        f(r.as_raw_mut());
+        get(&r)
+    }
+
+    #[test]
+    fn ffi_constants() {
+        unsafe {
+            assert_eq!(mp_bits_per_limb as u32, mp_limb_t::BITS);
+            let version = std::ffi::CStr::from_ptr(gmp_version).to_str().unwrap();
+            assert!(version.split('.').all(|part| part.parse::<u32>().is_ok()));
+            #[cfg(not(feature = "backend-gmp"))]
+            {
+                let version = std::ffi::CStr::from_ptr(mpir_version).to_str().unwrap();
+                assert!(version.split('.').all(|part| part.parse::<u32>().is_ok()));
+            }
+        }
+    }
+
+    #[test]
+    fn ffi_memory_functions() {
+        let mut alloc = None;
+        let mut realloc = None;
+        let mut free = None;
+        unsafe { mp_get_memory_functions(&mut alloc, &mut realloc, &mut free) };
+        assert!(alloc.is_some() && realloc.is_some() && free.is_some());
+    }
+
+    #[test]
+    fn ffi_initialisation() {
+        unsafe {
+            let mut x = Mpz::from(12345);
+            mpz_realloc2(x.as_raw_mut(), 256);
+            assert_eq!(get(&x), 12345);
+            // Shrinking below the size of the value sets it to zero.
+            let mut y = Mpz::from(u128::MAX);
+            mpz_realloc2(y.as_raw_mut(), 64);
+            assert_eq!(get(&y), 0);
+        }
+    }
+
+    #[test]
+    fn ffi_assignment() {
+        unsafe {
+            let a = int(-77);
+            assert_eq!(raw(|r| mpz_set(r, a.as_raw())), -77);
+            assert_eq!(raw(|r| mpz_set_ui(r, c_ulong::MAX >> 1)), (c_ulong::MAX >> 1) as i64);
+            assert_eq!(raw(|r| mpz_set_si(r, -5)), -5);
+            assert_eq!(raw(|r| mpz_set_d(r, -2.75)), -2);
+            let s = CString::new("-ff").unwrap();
+            assert_eq!(raw(|r| assert_eq!(mpz_set_str(r, s.as_ptr(), 16), 0)), -255);
+            let bad = CString::new("12z").unwrap();
+            assert_eq!(raw(|r| assert_eq!(mpz_set_str(r, bad.as_ptr(), 10), -1)), 0);
+            let mut x = int(1);
+            let mut y = int(2);
+            mpz_swap(x.as_raw_mut(), y.as_raw_mut());
+            assert_eq!((get(&x), get(&y)), (2, 1));
+        }
+    }
+
+    #[test]
+    fn ffi_init_assignment() {
+        unsafe {
+            let a = int(-77);
+            assert_eq!(get(&Mpz::from_init(|x| mpz_init_set(x, a.as_raw()))), -77);
+            assert_eq!(get(&Mpz::from_init(|x| mpz_init_set_ui(x, 9))), 9);
+            assert_eq!(get(&Mpz::from_init(|x| mpz_init_set_si(x, -9))), -9);
+            assert_eq!(get(&Mpz::from_init(|x| mpz_init_set_d(x, 1e3))), 1000);
+            let s = CString::new("-1010").unwrap();
+            let mut ok = -1;
+            let x = Mpz::from_init(|x| ok = mpz_init_set_str(x, s.as_ptr(), 2));
+            assert_eq!((ok, get(&x)), (0, -10));
+        }
+    }
+
+    #[test]
+    fn ffi_conversion() {
+        unsafe {
+            let a = int(-300);
+            assert_eq!(mpz_get_ui(a.as_raw()), 300);
+            assert_eq!(mpz_get_si(a.as_raw()), -300);
+            assert_eq!(mpz_get_d(a.as_raw()), -300.0);
+            let mut exp = 0;
+            assert_eq!(mpz_get_d_2exp(&mut exp, a.as_raw()), -0.5859375);
+            assert_eq!(exp, 9);
+            let mut buf = [0 as c_char; 8];
+            mpz_get_str(buf.as_mut_ptr(), -16, a.as_raw());
+            let s = std::ffi::CStr::from_ptr(buf.as_ptr());
+            assert_eq!(s.to_str().unwrap(), "-12C");
+        }
+    }
+
+    #[test]
+    fn ffi_arithmetic() {
+        unsafe {
+            let a = int(-12);
+            let b = int(5);
+            assert_eq!(raw(|r| mpz_add(r, a.as_raw(), b.as_raw())), -7);
+            assert_eq!(raw(|r| mpz_add_ui(r, a.as_raw(), 20)), 8);
+            assert_eq!(raw(|r| mpz_sub(r, a.as_raw(), b.as_raw())), -17);
+            assert_eq!(raw(|r| mpz_sub_ui(r, a.as_raw(), 3)), -15);
+            assert_eq!(raw(|r| mpz_ui_sub(r, 3, a.as_raw())), 15);
+            assert_eq!(raw(|r| mpz_mul(r, a.as_raw(), b.as_raw())), -60);
+            assert_eq!(raw(|r| mpz_mul_si(r, a.as_raw(), -3)), 36);
+            assert_eq!(raw(|r| mpz_mul_ui(r, a.as_raw(), 3)), -36);
+            let mut x = int(100);
+            mpz_addmul(x.as_raw_mut(), a.as_raw(), b.as_raw());
+            assert_eq!(get(&x), 40);
+            mpz_addmul_ui(x.as_raw_mut(), a.as_raw(), 2);
+            assert_eq!(get(&x), 16);
+            mpz_submul(x.as_raw_mut(), a.as_raw(), b.as_raw());
+            assert_eq!(get(&x), 76);
+            mpz_submul_ui(x.as_raw_mut(), a.as_raw(), 2);
+            assert_eq!(get(&x), 100);
+            assert_eq!(raw(|r| mpz_mul_2exp(r, a.as_raw(), 3)), -96);
+            assert_eq!(raw(|r| mpz_neg(r, a.as_raw())), 12);
+            assert_eq!(raw(|r| mpz_abs(r, a.as_raw())), 12);
+        }
+    }
+
+    #[test]
+    fn ffi_division() {
+        unsafe {
+            let n = int(-17);
+            let d = int(5);
+            let (n, d) = (n.as_raw(), d.as_raw());
+            type Qr = unsafe extern "C" fn(mpz_ptr, mpz_ptr, mpz_srcptr, mpz_srcptr);
+            type QrUi = unsafe extern "C" fn(mpz_ptr, mpz_ptr, mpz_srcptr, c_ulong) -> c_ulong;
+            let qr = |f: Qr| {
+                let mut r = Mpz::new();
+                let q = raw(|q| f(q, r.as_raw_mut(), n, d));
+                (q, get(&r))
+            };
+            let qr_ui = |f: QrUi| {
+                let mut r = Mpz::new();
+                let mut ret = 0;
+                let q = raw(|q| ret = f(q, r.as_raw_mut(), n, 5));
+                (q, get(&r), ret)
+            };
+
+            assert_eq!(raw(|r| mpz_cdiv_q(r, n, d)), -3);
+            assert_eq!(raw(|r| mpz_cdiv_r(r, n, d)), -2);
+            assert_eq!(qr(mpz_cdiv_qr), (-3, -2));
+            assert_eq!(raw(|r| assert_eq!(mpz_cdiv_q_ui(r, n, 5), 2)), -3);
+            assert_eq!(raw(|r| assert_eq!(mpz_cdiv_r_ui(r, n, 5), 2)), -2);
+            assert_eq!(qr_ui(mpz_cdiv_qr_ui), (-3, -2, 2));
+            assert_eq!(mpz_cdiv_ui(n, 5), 2);
+            assert_eq!(raw(|r| mpz_cdiv_q_2exp(r, n, 2)), -4);
+            assert_eq!(raw(|r| mpz_cdiv_r_2exp(r, n, 2)), -1);
+
+            assert_eq!(raw(|r| mpz_fdiv_q(r, n, d)), -4);
+            assert_eq!(raw(|r| mpz_fdiv_r(r, n, d)), 3);
+            assert_eq!(qr(mpz_fdiv_qr), (-4, 3));
+            assert_eq!(raw(|r| assert_eq!(mpz_fdiv_q_ui(r, n, 5), 3)), -4);
+            assert_eq!(raw(|r| assert_eq!(mpz_fdiv_r_ui(r, n, 5), 3)), 3);
+            assert_eq!(qr_ui(mpz_fdiv_qr_ui), (-4, 3, 3));
+            assert_eq!(mpz_fdiv_ui(n, 5), 3);
+            assert_eq!(raw(|r| mpz_fdiv_q_2exp(r, n, 2)), -5);
+            assert_eq!(raw(|r| mpz_fdiv_r_2exp(r, n, 2)), 3);
+
+            assert_eq!(raw(|r| mpz_tdiv_q(r, n, d)), -3);
+            assert_eq!(raw(|r| mpz_tdiv_r(r, n, d)), -2);
+            assert_eq!(qr(mpz_tdiv_qr), (-3, -2));
+            assert_eq!(raw(|r| assert_eq!(mpz_tdiv_q_ui(r, n, 5), 2)), -3);
+            assert_eq!(raw(|r| assert_eq!(mpz_tdiv_r_ui(r, n, 5), 2)), -2);
+            assert_eq!(qr_ui(mpz_tdiv_qr_ui), (-3, -2, 2));
+            assert_eq!(mpz_tdiv_ui(n, 5), 2);
+            assert_eq!(raw(|r| mpz_tdiv_q_2exp(r, n, 2)), -4);
+            assert_eq!(raw(|r| mpz_tdiv_r_2exp(r, n, 2)), -1);
+
+            assert_eq!(raw(|r| mpz_mod(r, n, d)), 3);
+            assert_eq!(raw(|r| assert_eq!(mpz_mod_ui(r, n, 5), 3)), 3);
+        }
+    }
 
     #[test]
-    fn new() {}
+    fn ffi_divisibility() {
+        unsafe {
+            let n = int(105);
+            let d = int(7);
+            assert_eq!(raw(|r| mpz_divexact(r, n.as_raw(), d.as_raw())), 15);
+            assert_eq!(raw(|r| mpz_divexact_ui(r, n.as_raw(), 5)), 21);
+            assert_ne!(mpz_divisible_p(n.as_raw(), d.as_raw()), 0);
+            assert_eq!(mpz_divisible_p(n.as_raw(), int(4).as_raw()), 0);
+            assert_ne!(mpz_divisible_ui_p(n.as_raw(), 3), 0);
+            assert_eq!(mpz_divisible_ui_p(n.as_raw(), 2), 0);
+            assert_ne!(mpz_divisible_2exp_p(int(96).as_raw(), 5), 0);
+            assert_eq!(mpz_divisible_2exp_p(int(96).as_raw(), 6), 0);
+            let c = int(5);
+            assert_ne!(mpz_congruent_p(n.as_raw(), c.as_raw(), int(10).as_raw()), 0);
+            assert_eq!(mpz_congruent_p(n.as_raw(), c.as_raw(), int(3).as_raw()), 0);
+            assert_ne!(mpz_congruent_ui_p(n.as_raw(), 1, 8), 0);
+            assert_eq!(mpz_congruent_ui_p(n.as_raw(), 2, 8), 0);
+            assert_ne!(mpz_congruent_2exp_p(n.as_raw(), int(9).as_raw(), 5), 0);
+            assert_eq!(mpz_congruent_2exp_p(n.as_raw(), int(9).as_raw(), 7), 0);
+        }
+    }
+
+    #[test]
+    fn ffi_exponentiation() {
+        unsafe {
+            let base = int(3);
+            let exp = int(4);
+            let m = int(5);
+            assert_eq!(raw(|r| mpz_powm(r, base.as_raw(), exp.as_raw(), m.as_raw())), 1);
+            assert_eq!(raw(|r| mpz_powm_ui(r, base.as_raw(), 3, m.as_raw())), 2);
+            assert_eq!(raw(|r| mpz_pow_ui(r, int(-2).as_raw(), 5)), -32);
+            assert_eq!(raw(|r| mpz_ui_pow_ui(r, 3, 4)), 81);
+        }
+    }
+
+    #[test]
+    fn ffi_roots() {
+        unsafe {
+            #[cfg(not(feature = "backend-gmp"))]
+            assert_eq!(raw(|r| mpz_nthroot(r, int(100).as_raw(), 3)), 4);
+            assert_eq!(raw(|r| assert_ne!(mpz_root(r, int(27).as_raw(), 3), 0)), 3);
+            assert_eq!(raw(|r| assert_eq!(mpz_root(r, int(28).as_raw(), 3), 0)), 3);
+            let mut rem = Mpz::new();
+            assert_eq!(raw(|r| mpz_rootrem(r, rem.as_raw_mut(), int(30).as_raw(), 3)), 3);
+            assert_eq!(get(&rem), 3);
+            assert_eq!(raw(|r| mpz_sqrt(r, int(17).as_raw())), 4);
+            assert_eq!(raw(|r| mpz_sqrtrem(r, rem.as_raw_mut(), int(20).as_raw())), 4);
+            assert_eq!(get(&rem), 4);
+            assert_ne!(mpz_perfect_power_p(int(-27).as_raw()), 0);
+            assert_eq!(mpz_perfect_power_p(int(28).as_raw()), 0);
+            assert_ne!(mpz_perfect_square_p(int(16).as_raw()), 0);
+            assert_eq!(mpz_perfect_square_p(int(15).as_raw()), 0);
+        }
+    }
+
+    #[test]
+    fn ffi_number_theory() {
+        unsafe {
+            let m = int(13);
+            assert_eq!(raw(|r| assert_ne!(mpz_invert(r, int(3).as_raw(), m.as_raw()), 0)), 9);
+            let mut r = Mpz::new();
+            assert_eq!(mpz_invert(r.as_raw_mut(), int(4).as_raw(), int(8).as_raw()), 0);
+
+            let (a, b) = (int(-84), int(36));
+            assert_eq!(raw(|r| mpz_gcd(r, a.as_raw(), b.as_raw())), 12);
+            assert_eq!(mpz_gcd_ui(null_mut(), a.as_raw(), 18), 6);
+            assert_eq!(mpz_gcd_ui(r.as_raw_mut(), a.as_raw(), 0), 84);
+            assert_eq!(get(&r), 84);
+            let (mut g, mut s, mut t) = (Mpz::new(), Mpz::new(), Mpz::new());
+            mpz_gcdext(g.as_raw_mut(), s.as_raw_mut(), t.as_raw_mut(), a.as_raw(), b.as_raw());
+            assert_eq!(get(&g), 12);
+            assert_eq!(-84 * get(&s) + 36 * get(&t), 12);
+            mpz_gcdext(g.as_raw_mut(), s.as_raw_mut(), null_mut(), a.as_raw(), b.as_raw());
+            assert_eq!(get(&g), 12);
+            assert_eq!(raw(|r| mpz_lcm(r, a.as_raw(), b.as_raw())), 252);
+            assert_eq!(raw(|r| mpz_lcm_ui(r, a.as_raw(), 0)), 0);
+
+            let (a, b) = (int(2), int(15));
+            assert_eq!(mpz_jacobi(a.as_raw(), b.as_raw()), 1);
+            assert_eq!(mpz_legendre(a.as_raw(), m.as_raw()), -1);
+            assert_eq!(mpz_kronecker(b.as_raw(), int(-4).as_raw()), 1);
+            assert_eq!(mpz_kronecker_si(int(-7).as_raw(), -2), -1);
+            assert_eq!(mpz_kronecker_ui(a.as_raw(), 6), 0);
+            assert_eq!(mpz_si_kronecker(-1, m.as_raw()), 1);
+            assert_eq!(mpz_ui_kronecker(3, m.as_raw()), 1);
+
+            let mut r = Mpz::new();
+            assert_eq!(mpz_remove(r.as_raw_mut(), int(-720).as_raw(), int(2).as_raw()), 4);
+            assert_eq!(get(&r), -45);
+
+            assert_eq!(mpz_probab_prime_p(m.as_raw(), 10), 2);
+            assert_eq!(mpz_probab_prime_p(int(91).as_raw(), 10), 0);
+            assert_eq!(raw(|r| mpz_nextprime(r, m.as_raw())), 17);
+
+            assert_eq!(raw(|r| mpz_fac_ui(r, 10)), 3628800);
+            assert_eq!(raw(|r| mpz_2fac_ui(r, 9)), 945);
+            assert_eq!(raw(|r| mpz_mfac_uiui(r, 10, 3)), 280);
+            assert_eq!(raw(|r| mpz_primorial_ui(r, 13)), 30030);
+            assert_eq!(raw(|r| mpz_bin_ui(r, int(-5).as_raw(), 3)), -35);
+            assert_eq!(raw(|r| mpz_bin_uiui(r, 52, 5)), 2598960);
+
+            assert_eq!(raw(|r| mpz_fib_ui(r, 90)), 2880067194370816120);
+            assert_eq!(raw(|r| mpz_lucnum_ui(r, 10)), 123);
+            let mut prev = Mpz::new();
+            assert_eq!(raw(|r| mpz_fib2_ui(r, prev.as_raw_mut(), 10)), 55);
+            assert_eq!(get(&prev), 34);
+            assert_eq!(raw(|r| mpz_lucnum2_ui(r, prev.as_raw_mut(), 10)), 123);
+            assert_eq!(get(&prev), 76);
+        }
+    }
+
+    #[test]
+    #[cfg(not(feature = "backend-gmp"))]
+    fn ffi_prime_candidates() {
+        unsafe {
+            let mut state = RandState::new();
+            let c = raw(|r| mpz_next_prime_candidate(r, int(1_000_000).as_raw(), state.as_raw_mut()));
+            assert!(c > 1_000_000 && c <= 1_000_003);
+        }
+    }
+
+    #[test]
+    fn ffi_comparison() {
+        unsafe {
+            let a = int(-10);
+            let b = int(3);
+            assert!(mpz_cmp(a.as_raw(), b.as_raw()) < 0);
+            assert!(mpz_cmp_d(a.as_raw(), -10.5) > 0);
+            assert!(mpz_cmp_si(a.as_raw(), -10) == 0);
+            assert!(mpz_cmp_ui(a.as_raw(), 0) < 0);
+            assert!(mpz_cmpabs(a.as_raw(), b.as_raw()) > 0);
+            assert!(mpz_cmpabs_d(a.as_raw(), -10.5) < 0);
+            assert!(mpz_cmpabs_ui(a.as_raw(), 10) == 0);
+        }
+    }
+
+    #[test]
+    fn ffi_logic() {
+        unsafe {
+            let a = int(0b1100);
+            let b = int(-0b1010);
+            assert_eq!(raw(|r| mpz_and(r, a.as_raw(), b.as_raw())), 0b0100);
+            assert_eq!(raw(|r| mpz_ior(r, a.as_raw(), b.as_raw())), -0b0010);
+            assert_eq!(raw(|r| mpz_xor(r, a.as_raw(), b.as_raw())), -0b0110);
+            assert_eq!(raw(|r| mpz_com(r, a.as_raw())), -0b1101);
+            assert_eq!(mpz_popcount(a.as_raw()), 2);
+            assert_eq!(mpz_popcount(b.as_raw()), mp_bitcnt_t::MAX);
+            assert_eq!(mpz_hamdist(a.as_raw(), int(0b0101).as_raw()), 2);
+            assert_eq!(mpz_scan0(a.as_raw(), 2), 4);
+            assert_eq!(mpz_scan1(a.as_raw(), 0), 2);
+            let mut x = int(0);
+            mpz_setbit(x.as_raw_mut(), 5);
+            assert_eq!(get(&x), 32);
+            mpz_combit(x.as_raw_mut(), 0);
+            assert_eq!(get(&x), 33);
+            mpz_clrbit(x.as_raw_mut(), 5);
+            assert_eq!(get(&x), 1);
+            assert_eq!(mpz_tstbit(a.as_raw(), 3), 1);
+            assert_eq!(mpz_tstbit(a.as_raw(), 0), 0);
+        }
+    }
+
+    #[test]
+    fn ffi_random() {
+        unsafe {
+            let mut state = RandState::new();
+            for _ in 0..100 {
+                let x = raw(|r| mpz_urandomb(r, state.as_raw_mut(), 10));
+                assert!((0..1024).contains(&x));
+                let x = raw(|r| mpz_urandomm(r, state.as_raw_mut(), int(7).as_raw()));
+                assert!((0..7).contains(&x));
+                let x = raw(|r| mpz_rrandomb(r, state.as_raw_mut(), 10));
+                assert!((0..1024).contains(&x));
+            }
+        }
+    }
+
+    #[test]
+    fn ffi_miscellaneous() {
+        unsafe {
+            let big = Mpz::from(u64::MAX);
+            let neg = int(-1);
+            assert_ne!(mpz_fits_ulong_p(big.as_raw()), 0);
+            assert_eq!(mpz_fits_ulong_p(neg.as_raw()), 0);
+            assert_ne!(mpz_fits_slong_p(neg.as_raw()), 0);
+            assert_eq!(mpz_fits_slong_p(big.as_raw()), 0);
+            assert_ne!(mpz_fits_uint_p(int(u32::MAX as i64).as_raw()), 0);
+            assert_eq!(mpz_fits_uint_p(int(u32::MAX as i64 + 1).as_raw()), 0);
+            assert_ne!(mpz_fits_sint_p(int(i32::MIN as i64).as_raw()), 0);
+            assert_eq!(mpz_fits_sint_p(int(i32::MIN as i64 - 1).as_raw()), 0);
+            assert_ne!(mpz_fits_ushort_p(int(u16::MAX as i64).as_raw()), 0);
+            assert_eq!(mpz_fits_ushort_p(neg.as_raw()), 0);
+            assert_ne!(mpz_fits_sshort_p(int(i16::MIN as i64).as_raw()), 0);
+            assert_eq!(mpz_fits_sshort_p(int(i16::MAX as i64 + 1).as_raw()), 0);
+            assert_ne!(mpz_odd_p(int(-3).as_raw()), 0);
+            assert_eq!(mpz_odd_p(int(0).as_raw()), 0);
+            assert_ne!(mpz_even_p(int(0).as_raw()), 0);
+            assert_eq!(mpz_even_p(int(7).as_raw()), 0);
+            assert_eq!(mpz_sizeinbase(int(-1000).as_raw(), 10), 4);
+            assert_eq!(mpz_sizeinbase(int(255).as_raw(), 2), 8);
+            assert_eq!(mpz_sgn(int(-9).as_raw()), -1);
+            assert_eq!(mpz_sgn(int(0).as_raw()), 0);
+            assert_eq!(mpz_sgn(int(9).as_raw()), 1);
+            assert_eq!(mpz_size(int(0).as_raw()), 0);
+            assert_eq!(mpz_size(Mpz::from(-1i128 << 100).as_raw()), 128 / mp_limb_t::BITS as usize);
+        }
+    }
 }
@@ -0,0 +1,250 @@
+//! Comparison and hashing for [`Mpz`]
+//!
+//! Integers are totally ordered by `mpz_cmp`, and compare with the primitive integer types through
+//! `mpz_cmp_ui` / `mpz_cmp_si` where the primitive fits, on either side of the operator. Floats
+//! compare with `mpz_cmp_d`: infinities compare as expected and NaN is unordered.
+//!
+//! Equal integers hash the same however much space they have allocated, as only the sign and the
+//! limbs in use are hashed.
+
+use std::cmp::Ordering;
+use std::hash::{Hash, Hasher};
+
+use super::ops::{Primitive, Small};
+use super::*;
+
+/// Compare `z` with a primitive operand.
+fn cmp_small(z: &Mpz, v: Small) -> Ordering {
+    let c = match v {
+        Small::Pos(u) => unsafe { mpz_cmp_ui(z.as_raw(), u) },
+        Small::Neg(u) if u <= c_long::MIN.unsigned_abs() => unsafe {
+            mpz_cmp_si(z.as_raw(), (u as c_long).wrapping_neg())
+        },
+        v => v.with_raw(|p| unsafe { mpz_cmp(z.as_raw(), p) }),
+    };
+    c.cmp(&0)
+}
+
+/// Compare `z` with a float, or `None` if it is NaN.
+fn cmp_f64(z: &Mpz, d: f64) -> Option<Ordering> {
+    match d {
+        _ if d.is_nan() => None,
+        f64::INFINITY => Some(Ordering::Less),
+        f64::NEG_INFINITY => Some(Ordering::Greater),
+        _ => Some(unsafe { mpz_cmp_d(z.as_raw(), d) }.cmp(&0)),
+    }
+}
+
+impl Mpz {
+    /// Compare the absolute values of `self` and `other`.
+    pub fn cmp_abs(&self, other: &Mpz) -> Ordering {
+        unsafe { mpz_cmpabs(self.as_raw(), other.as_raw()) }.cmp(&0)
+    }
+
+    /// Compare the absolute value of `self` with `other`.
+    #[allow(clippy::unnecessary_fallible_conversions)]
+    pub fn cmp_abs_u64(&self, other: u64) -> Ordering {
+        match c_ulong::try_from(other) {
+            Ok(u) => unsafe { mpz_cmpabs_ui(self.as_raw(), u) }.cmp(&0),
+            Err(_) => self.cmp_abs(&Mpz::from(other)),
+        }
+    }
+
+    /// Compare the absolute values of `self` and `other`, or `None` if `other` is NaN.
+    pub fn cmp_abs_f64(&self, other: f64) -> Option<Ordering> {
+        match other {
+            _ if other.is_nan() => None,
+            _ if other.is_infinite() => Some(Ordering::Less),
+            _ => Some(unsafe { mpz_cmpabs_d(self.as_raw(), other) }.cmp(&0)),
+        }
+    }
+}
+
+impl PartialEq for Mpz {
+    fn eq(&self, other: &Mpz) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Mpz {}
+
+impl PartialOrd for Mpz {
+    fn partial_cmp(&self, other: &Mpz) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Mpz {
+    fn cmp(&self, other: &Mpz) -> Ordering {
+        unsafe { mpz_cmp(self.as_raw(), other.as_raw()) }.cmp(&0)
+    }
+}
+
+impl Hash for Mpz {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.sign().hash(state);
+        self.limbs().hash(state);
+    }
+}
+
+macro_rules! cmp_prim {
+    ($($t:ty)*) => {$(
+        impl PartialEq<$t> for Mpz {
+            fn eq(&self, other: &$t) -> bool {
+                cmp_small(self, other.small()) == Ordering::Equal
+            }
+        }
+
+        impl PartialEq<Mpz> for $t {
+            fn eq(&self, other: &Mpz) -> bool {
+                other == self
+            }
+        }
+
+        impl PartialOrd<$t> for Mpz {
+            fn partial_cmp(&self, other: &$t) -> Option<Ordering> {
+                Some(cmp_small(self, other.small()))
+            }
+        }
+
+        impl PartialOrd<Mpz> for $t {
+            fn partial_cmp(&self, other: &Mpz) -> Option<Ordering> {
+                Some(cmp_small(other, self.small()).reverse())
+            }
+        }
+    )*};
+}
+
+cmp_prim! { u8 u16 u32 u64 u128 usize i8 i16 i32 i64 i128 isize }
+
+macro_rules! cmp_float {
+    ($($t:ty)*) => {$(
+        impl PartialEq<$t> for Mpz {
+            fn eq(&self, other: &$t) -> bool {
+                cmp_f64(self, *other as f64) == Some(Ordering::Equal)
+            }
+        }
+
+        impl PartialEq<Mpz> for $t {
+            fn eq(&self, other: &Mpz) -> bool {
+                other == self
+            }
+        }
+
+        impl PartialOrd<$t> for Mpz {
+            fn partial_cmp(&self, other: &$t) -> Option<Ordering> {
+                cmp_f64(self, *other as f64)
+            }
+        }
+
+        impl PartialOrd<Mpz> for $t {
+            fn partial_cmp(&self, other: &Mpz) -> Option<Ordering> {
+                cmp_f64(other, *self as f64).map(Ordering::reverse)
+            }
+        }
+    )*};
+}
+
+cmp_float! { f32 f64 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::collections::hash_map::DefaultHasher;
+    use std::collections::{BTreeMap, HashMap};
+
+    fn hash(z: &Mpz) -> u64 {
+        let mut h = DefaultHasher::new();
+        z.hash(&mut h);
+        h.finish()
+    }
+
+    #[test]
+    fn ord() {
+        let a = Mpz::from(-3);
+        let b = Mpz::from(5);
+        let big = Mpz::from(1) << 100u32;
+        assert_eq!(a, Mpz::from(-3));
+        assert_ne!(a, b);
+        assert!(a < b && b < big && -big.clone() < a);
+        assert_eq!(big.cmp(&big.clone()), Ordering::Equal);
+        let mut sorted = vec![big.clone(), a.clone(), b.clone()];
+        sorted.sort();
+        assert_eq!(sorted, [a, b, big]);
+    }
+
+    #[test]
+    fn primitives() {
+        let x = Mpz::from(-7);
+        assert_eq!(x, -7);
+        assert_eq!(-7i8, x);
+        assert_ne!(x, 7u8);
+        assert_eq!(x.partial_cmp(&0u64), Some(Ordering::Less));
+        assert_eq!(0u64.partial_cmp(&x), Some(Ordering::Greater));
+        assert!(x > i64::MIN);
+        assert!(i128::MIN < x);
+        assert!(x < u128::MAX);
+        let big = Mpz::from(u128::MAX);
+        assert_eq!(big, u128::MAX);
+        assert!(big > u64::MAX);
+        assert!(big > i128::MAX);
+        let min = Mpz::from(i64::MIN);
+        assert_eq!(min, i64::MIN);
+        assert!(min < i64::MIN + 1);
+        assert_eq!(Mpz::from(i128::MIN), i128::MIN);
+    }
+
+    #[test]
+    fn floats() {
+        let x = Mpz::from(3);
+        assert_eq!(x, 3.0);
+        assert_eq!(3.0f32, x);
+        assert!(x < 3.5);
+        assert!(x > 2.5f32);
+        assert!(x < f64::INFINITY);
+        assert!(x > f64::NEG_INFINITY);
+        assert_eq!(f64::INFINITY.partial_cmp(&x), Some(Ordering::Greater));
+        assert_eq!(x.partial_cmp(&f64::NAN), None);
+        assert_eq!(f32::NAN.partial_cmp(&x), None);
+        assert!(!x.eq(&f64::NAN));
+    }
+
+    #[test]
+    fn cmp_abs() {
+        let a = Mpz::from(-9);
+        assert_eq!(a.cmp_abs(&Mpz::from(4)), Ordering::Greater);
+        assert_eq!(a.cmp_abs(&Mpz::from(9)), Ordering::Equal);
+        assert_eq!(a.cmp_abs_u64(10), Ordering::Less);
+        assert_eq!(a.cmp_abs_u64(9), Ordering::Equal);
+        assert_eq!(a.cmp_abs_f64(-8.5), Some(Ordering::Greater));
+        assert_eq!(a.cmp_abs_f64(f64::NEG_INFINITY), Some(Ordering::Less));
+        assert_eq!(a.cmp_abs_f64(f64::NAN), None);
+    }
+
+    #[test]
+    fn hash_ignores_allocation() {
+        let mut x = Mpz::with_capacity_bits(4096);
+        x += 12345;
+        assert_eq!(hash(&x), hash(&Mpz::from(12345)));
+        assert_ne!(hash(&x), hash(&Mpz::from(-12345)));
+
+        // Shrinking the value leaves the high limbs allocated but unused.
+        let mut y = Mpz::from(1) << 300u32;
+        y -= Mpz::from(1) << 300u32;
+        assert_eq!(hash(&y), hash(&Mpz::new()));
+    }
+
+    #[test]
+    fn collections() {
+        let mut map = HashMap::new();
+        map.insert(Mpz::from(1) << 70u32, "big");
+        map.insert(Mpz::from(-1), "minus one");
+        assert_eq!(map[&(Mpz::from(2) << 69u32)], "big");
+        assert_eq!(map[&Mpz::from(-1)], "minus one");
+
+        let tree: BTreeMap<_, _> = [(Mpz::from(3), 'c'), (Mpz::from(-1), 'a'), (Mpz::new(), 'b')]
+            .into_iter()
+            .collect();
+        assert_eq!(tree.values().collect::<String>(), "abc");
+    }
+}
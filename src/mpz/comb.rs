@@ -0,0 +1,117 @@
+//! Combinatorial functions for [`Mpz`]
+//!
+//! The arguments are `u32`, as with [`Mpz::pow`], since any larger argument would give a result too
+//! large to hold in memory. The exception is the top of a binomial coefficient, which may be any
+//! integer.
+
+use super::*;
+
+impl Mpz {
+    /// The factorial `n!`.
+    pub fn factorial(n: u32) -> Mpz {
+        let mut r = Mpz::new();
+        unsafe { mpz_fac_ui(r.as_raw_mut(), n as c_ulong) };
+        r
+    }
+
+    /// The double factorial `n!!`, the product of the positive integers up to `n` with the same
+    /// parity as `n`.
+    pub fn double_factorial(n: u32) -> Mpz {
+        let mut r = Mpz::new();
+        unsafe { mpz_2fac_ui(r.as_raw_mut(), n as c_ulong) };
+        r
+    }
+
+    /// The multifactorial `n(!^m)`, the product of `n`, `n - m`, `n - 2m`, ... down to 1.
+    ///
+    /// # Panics
+    ///
+    /// If `m` is zero.
+    pub fn multifactorial(n: u32, m: u32) -> Mpz {
+        assert!(m > 0, "multifactorial step must be positive");
+        let mut r = Mpz::new();
+        unsafe { mpz_mfac_uiui(r.as_raw_mut(), n as c_ulong, m as c_ulong) };
+        r
+    }
+
+    /// The primorial `n#`, the product of the primes less than or equal to `n`.
+    pub fn primorial(n: u32) -> Mpz {
+        let mut r = Mpz::new();
+        unsafe { mpz_primorial_ui(r.as_raw_mut(), n as c_ulong) };
+        r
+    }
+
+    /// The binomial coefficient `n` choose `k`.
+    ///
+    /// Negative `n` is allowed, with `(-n choose k) = (-1)^k (n + k - 1 choose k)`.
+    pub fn binomial(n: &Mpz, k: u32) -> Mpz {
+        let mut r = Mpz::new();
+        unsafe { mpz_bin_ui(r.as_raw_mut(), n.as_raw(), k as c_ulong) };
+        r
+    }
+
+    /// The binomial coefficient `n` choose `k`, which is 0 if `k > n`.
+    #[allow(clippy::unnecessary_fallible_conversions)]
+    pub fn binomial_u(n: u64, k: u32) -> Mpz {
+        match c_ulong::try_from(n) {
+            Ok(n) => {
+                let mut r = Mpz::new();
+                unsafe { mpz_bin_uiui(r.as_raw_mut(), n, k as c_ulong) };
+                r
+            }
+            Err(_) => Mpz::binomial(&Mpz::from(n), k),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn factorials() {
+        assert_eq!(Mpz::factorial(0), 1);
+        assert_eq!(Mpz::factorial(20), 2432902008176640000u64);
+        assert_eq!(
+            Mpz::factorial(30).to_string(),
+            "265252859812191058636308480000000"
+        );
+        assert_eq!(Mpz::double_factorial(0), 1);
+        assert_eq!(Mpz::double_factorial(10), 3840);
+        assert_eq!(Mpz::double_factorial(11), 10395);
+        assert_eq!(Mpz::multifactorial(10, 3), 280);
+        assert_eq!(Mpz::multifactorial(10, 1), Mpz::factorial(10));
+        assert_eq!(Mpz::multifactorial(10, 2), Mpz::double_factorial(10));
+        assert_eq!(Mpz::multifactorial(2, 5), 2);
+    }
+
+    #[test]
+    #[should_panic(expected = "multifactorial step must be positive")]
+    fn multifactorial_zero() {
+        Mpz::multifactorial(5, 0);
+    }
+
+    #[test]
+    fn primorial() {
+        assert_eq!(Mpz::primorial(0), 1);
+        assert_eq!(Mpz::primorial(1), 1);
+        assert_eq!(Mpz::primorial(2), 2);
+        assert_eq!(Mpz::primorial(30), 6469693230u64);
+    }
+
+    #[test]
+    fn binomial() {
+        assert_eq!(Mpz::binomial(&Mpz::from(52), 5), 2598960);
+        assert_eq!(Mpz::binomial(&Mpz::from(5), 7), 0);
+        assert_eq!(Mpz::binomial(&Mpz::from(5), 0), 1);
+        assert_eq!(Mpz::binomial(&Mpz::from(-5), 3), -35);
+        assert_eq!(Mpz::binomial(&Mpz::from(-5), 4), 70);
+        assert_eq!(Mpz::binomial(&Mpz::from(-1), 9), -1);
+        let n = Mpz::from(1) << 100u32;
+        assert_eq!(Mpz::binomial(&n, 2), (&n * (n.clone() - 1u32)) >> 1u32);
+        assert_eq!(Mpz::binomial_u(52, 5), 2598960);
+        assert_eq!(Mpz::binomial_u(3, 4), 0);
+        assert_eq!(Mpz::binomial_u(u64::MAX, 1), u64::MAX);
+        assert_eq!(Mpz::binomial_u(100, 50), Mpz::binomial(&Mpz::from(100), 50));
+    }
+}
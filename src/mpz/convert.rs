@@ -0,0 +1,257 @@
+//! Conversions between [`Mpz`] and the primitive number types
+//!
+//! Every primitive integer converts into an `Mpz` with `From`, and back with `TryFrom<&Mpz>`, which
+//! fails if the value is out of range. Values that fit an `mpir_ui` / `mpir_si` go through
+//! `mpz_set_ui` / `mpz_set_si` and `mpz_get_ui` / `mpz_get_si`; wider values are handled limb by
+//! limb.
+//!
+//! Floats convert with truncation towards zero. NaN and infinities cannot be converted to an `Mpz`.
+
+use std::error::Error;
+use std::fmt;
+
+use super::ops::Primitive;
+use super::*;
+
+impl Mpz {
+    /// Convert to an `f64`, truncating if necessary (i.e. rounding towards zero).
+    ///
+    /// If the value is too large for an `f64`, the result is system dependent, usually infinity.
+    pub fn to_f64(&self) -> f64 {
+        unsafe { mpz_get_d(self.as_raw()) }
+    }
+
+    /// Convert to an `f64` mantissa and exponent, truncating if necessary.
+    ///
+    /// Returns `(d, exp)` where `0.5 <= |d| < 1` and `d * 2^exp` is the truncated value. For zero
+    /// the result is `(0.0, 0)`. Unlike [`Mpz::to_f64`] this cannot overflow.
+    pub fn to_f64_exp(&self) -> (f64, i64) {
+        let mut exp: c_long = 0;
+        let d = unsafe { mpz_get_d_2exp(&mut exp, self.as_raw()) };
+        (d, exp as i64)
+    }
+
+    /// Sign and magnitude, if the magnitude fits in a `u128`.
+    fn to_u128_parts(&self) -> Option<(bool, u128)> {
+        let limbs = self.limbs();
+        if limbs.len() * mp_limb_t::BITS as usize > u128::BITS as usize {
+            return None;
+        }
+        let magnitude = limbs.iter().enumerate().fold(0u128, |acc, (i, &limb)| {
+            acc | (limb as u128) << (i * mp_limb_t::BITS as usize)
+        });
+        Some((self.0._mp_size < 0, magnitude))
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+// Primitive integers
+
+macro_rules! from_unsigned {
+    ($($t:ty)*) => {$(
+        impl From<$t> for Mpz {
+            #[allow(clippy::unnecessary_fallible_conversions)]
+            fn from(v: $t) -> Self {
+                match c_ulong::try_from(v) {
+                    Ok(u) => unsafe { Mpz::from_init(|x| mpz_init_set_ui(x, u)) },
+                    Err(_) => v.small().into_mpz(),
+                }
+            }
+        }
+
+        impl TryFrom<&Mpz> for $t {
+            type Error = TryFromMpzError;
+
+            #[allow(clippy::unnecessary_fallible_conversions)]
+            fn try_from(z: &Mpz) -> Result<Self, Self::Error> {
+                if <$t>::BITS <= c_ulong::BITS {
+                    if unsafe { mpz_fits_ulong_p(z.as_raw()) } == 0 {
+                        return Err(TryFromMpzError(()));
+                    }
+                    let u = unsafe { mpz_get_ui(z.as_raw()) };
+                    return <$t>::try_from(u).map_err(|_| TryFromMpzError(()));
+                }
+                match z.to_u128_parts() {
+                    Some((false, m)) | Some((true, m @ 0)) => {
+                        <$t>::try_from(m).map_err(|_| TryFromMpzError(()))
+                    }
+                    _ => Err(TryFromMpzError(())),
+                }
+            }
+        }
+    )*};
+}
+
+macro_rules! from_signed {
+    ($($t:ty, $u:ty;)*) => {$(
+        impl From<$t> for Mpz {
+            #[allow(clippy::unnecessary_fallible_conversions)]
+            fn from(v: $t) -> Self {
+                match c_long::try_from(v) {
+                    Ok(si) => unsafe { Mpz::from_init(|x| mpz_init_set_si(x, si)) },
+                    Err(_) => v.small().into_mpz(),
+                }
+            }
+        }
+
+        impl TryFrom<&Mpz> for $t {
+            type Error = TryFromMpzError;
+
+            #[allow(clippy::unnecessary_fallible_conversions)]
+            fn try_from(z: &Mpz) -> Result<Self, Self::Error> {
+                if <$t>::BITS <= c_long::BITS {
+                    if unsafe { mpz_fits_slong_p(z.as_raw()) } == 0 {
+                        return Err(TryFromMpzError(()));
+                    }
+                    let si = unsafe { mpz_get_si(z.as_raw()) };
+                    return <$t>::try_from(si).map_err(|_| TryFromMpzError(()));
+                }
+                match z.to_u128_parts() {
+                    Some((false, m)) if m <= <$t>::MAX as u128 => Ok(m as $t),
+                    Some((true, m)) if m <= <$t>::MIN.unsigned_abs() as u128 => {
+                        Ok((m as $u).wrapping_neg() as $t)
+                    }
+                    _ => Err(TryFromMpzError(())),
+                }
+            }
+        }
+    )*};
+}
+
+from_unsigned! { u8 u16 u32 u64 u128 usize }
+from_signed! {
+    i8, u8;
+    i16, u16;
+    i32, u32;
+    i64, u64;
+    i128, u128;
+    isize, usize;
+}
+
+macro_rules! try_from_owned {
+    ($($t:ty)*) => {$(
+        impl TryFrom<Mpz> for $t {
+            type Error = TryFromMpzError;
+
+            fn try_from(z: Mpz) -> Result<Self, Self::Error> {
+                <$t>::try_from(&z)
+            }
+        }
+    )*};
+}
+
+try_from_owned! { u8 u16 u32 u64 u128 usize i8 i16 i32 i64 i128 isize }
+
+// -------------------------------------------------------------------------------------------------
+// Floats
+
+impl TryFrom<f64> for Mpz {
+    type Error = TryFromFloatError;
+
+    /// Convert a finite float to an `Mpz`, truncating any fractional part.
+    fn try_from(d: f64) -> Result<Self, Self::Error> {
+        if !d.is_finite() {
+            return Err(TryFromFloatError(()));
+        }
+        Ok(unsafe { Mpz::from_init(|x| mpz_init_set_d(x, d)) })
+    }
+}
+
+impl TryFrom<f32> for Mpz {
+    type Error = TryFromFloatError;
+
+    /// Convert a finite float to an `Mpz`, truncating any fractional part.
+    fn try_from(d: f32) -> Result<Self, Self::Error> {
+        Mpz::try_from(d as f64)
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+// Errors
+
+/// Error returned when an [`Mpz`] is out of range of the primitive type it is converted to.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TryFromMpzError(());
+
+impl fmt::Display for TryFromMpzError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("out of range integral type conversion attempted")
+    }
+}
+
+impl Error for TryFromMpzError {}
+
+/// Error returned when converting a NaN or infinite float to an [`Mpz`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TryFromFloatError(());
+
+impl fmt::Display for TryFromFloatError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("cannot convert NaN or infinite float to an integer")
+    }
+}
+
+impl Error for TryFromFloatError {}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    macro_rules! round_trip {
+        ($($t:ty)*) => {$(
+            for v in [<$t>::MIN, <$t>::MIN / 2, 0, 1, <$t>::MAX / 3, <$t>::MAX] {
+                let z = Mpz::from(v);
+                assert_eq!(z.to_string(), v.to_string());
+                assert_eq!(<$t>::try_from(&z), Ok(v));
+            }
+        )*};
+    }
+
+    #[test]
+    fn primitive_round_trip() {
+        round_trip! { u8 u16 u32 u64 u128 usize i8 i16 i32 i64 i128 isize }
+    }
+
+    #[test]
+    fn out_of_range() {
+        let err = TryFromMpzError(());
+        assert_eq!(u8::try_from(&Mpz::from(256)), Err(err));
+        assert_eq!(u8::try_from(&Mpz::from(-1)), Err(err));
+        assert_eq!(i8::try_from(&Mpz::from(-129)), Err(err));
+        assert_eq!(u64::try_from(&Mpz::from(-1)), Err(err));
+        assert_eq!(i64::try_from(&Mpz::from(u64::MAX)), Err(err));
+        assert_eq!(u128::try_from(&Mpz::from(-1)), Err(err));
+        assert_eq!(u128::try_from(Mpz::from(u128::MAX) + 1u8), Err(err));
+        assert_eq!(i128::try_from(Mpz::from(i128::MAX) + 1u8), Err(err));
+        assert_eq!(i128::try_from(Mpz::from(i128::MIN) - 1u8), Err(err));
+        assert_eq!(
+            i128::try_from(Mpz::from(i128::MAX) + 1u8 - 1u8),
+            Ok(i128::MAX)
+        );
+    }
+
+    #[test]
+    fn to_f64() {
+        assert_eq!(Mpz::from(-12345).to_f64(), -12345.0);
+        let big = Mpz::from(u64::MAX);
+        assert_eq!(big.to_f64(), 18446744073709549568.0);
+        assert_eq!(Mpz::new().to_f64_exp(), (0.0, 0));
+        assert_eq!(Mpz::from(-12).to_f64_exp(), (-0.75, 4));
+        let huge = Mpz::from(3) << 2000u32;
+        assert_eq!(huge.to_f64(), f64::INFINITY);
+        assert_eq!(huge.to_f64_exp(), (0.75, 2002));
+    }
+
+    #[test]
+    fn from_float() {
+        assert_eq!(Mpz::try_from(-2.9f64).unwrap().to_string(), "-2");
+        assert_eq!(
+            Mpz::try_from(1e20f64).unwrap().to_string(),
+            "100000000000000000000"
+        );
+        assert_eq!(Mpz::try_from(0.5f32).unwrap().to_string(), "0");
+        assert!(Mpz::try_from(f64::NAN).is_err());
+        assert!(Mpz::try_from(f64::INFINITY).is_err());
+        assert!(Mpz::try_from(f32::NEG_INFINITY).is_err());
+    }
+}
@@ -0,0 +1,338 @@
+//! Division with explicit rounding for [`Mpz`]
+//!
+//! The `/` and `%` operators truncate, like the primitive integer types. The methods here choose the
+//! rounding with [`Round`], using MPIR's `cdiv`, `fdiv` and `tdiv` families. [`Round::Euclid`] is
+//! `fdiv` for a positive divisor and `cdiv` for a negative one, so the remainder is never negative.
+//!
+//! [`Mpz::div_rem_round`] and the `_u64` variants return [`MpirError::DivisionByZero`] for a zero
+//! divisor. The other methods panic, as the operators do.
+
+use super::ops::check_divisor;
+use super::*;
+use crate::{MpirError, Round};
+
+/// Resolve `Round::Euclid` to ceil or floor, depending on the sign of the divisor.
+fn resolve(round: Round, d_negative: bool) -> Round {
+    match round {
+        Round::Euclid if d_negative => Round::Ceil,
+        Round::Euclid => Round::Floor,
+        round => round,
+    }
+}
+
+unsafe fn div_rem_raw(q: mpz_ptr, r: mpz_ptr, n: mpz_srcptr, d: mpz_srcptr, round: Round) {
+    match resolve(round, (*d)._mp_size < 0) {
+        Round::Ceil => mpz_cdiv_qr(q, r, n, d),
+        Round::Floor => mpz_fdiv_qr(q, r, n, d),
+        _ => mpz_tdiv_qr(q, r, n, d),
+    }
+}
+
+unsafe fn div_raw(q: mpz_ptr, n: mpz_srcptr, d: mpz_srcptr, round: Round) {
+    check_divisor(d);
+    match resolve(round, (*d)._mp_size < 0) {
+        Round::Ceil => mpz_cdiv_q(q, n, d),
+        Round::Floor => mpz_fdiv_q(q, n, d),
+        _ => mpz_tdiv_q(q, n, d),
+    }
+}
+
+unsafe fn rem_raw(r: mpz_ptr, n: mpz_srcptr, d: mpz_srcptr, round: Round) {
+    check_divisor(d);
+    match resolve(round, (*d)._mp_size < 0) {
+        Round::Ceil => mpz_cdiv_r(r, n, d),
+        Round::Floor => mpz_fdiv_r(r, n, d),
+        _ => mpz_tdiv_r(r, n, d),
+    }
+}
+
+impl Mpz {
+    /// Divide by `d`, rounding the quotient as `round` says, and return the quotient and remainder.
+    ///
+    /// The results satisfy `self == q * d + r`, with `|r| < |d|`.
+    pub fn div_rem_round(&self, d: &Mpz, round: Round) -> Result<(Mpz, Mpz), MpirError> {
+        if d.is_zero() {
+            return Err(MpirError::DivisionByZero);
+        }
+        let mut q = Mpz::new();
+        let mut r = Mpz::new();
+        unsafe {
+            div_rem_raw(
+                q.as_raw_mut(),
+                r.as_raw_mut(),
+                self.as_raw(),
+                d.as_raw(),
+                round,
+            )
+        };
+        Ok((q, r))
+    }
+
+    /// Divide by `d`, rounding the quotient as `round` says, and return the quotient and the
+    /// absolute value of the remainder.
+    ///
+    /// The sign of the remainder follows from `round`: it is never positive for [`Round::Ceil`],
+    /// never negative for [`Round::Floor`] and [`Round::Euclid`], and has the sign of `self` for
+    /// [`Round::Trunc`].
+    #[allow(clippy::unnecessary_fallible_conversions)]
+    pub fn div_rem_round_u64(&self, d: u64, round: Round) -> Result<(Mpz, u64), MpirError> {
+        if d == 0 {
+            return Err(MpirError::DivisionByZero);
+        }
+        let u = match c_ulong::try_from(d) {
+            Ok(u) => u,
+            Err(_) => {
+                let (q, r) = self.div_rem_round(&Mpz::from(d), round)?;
+                let r = if r.is_negative() { -r } else { r };
+                return Ok((
+                    q,
+                    u64::try_from(r).expect("remainder is less than the divisor"),
+                ));
+            }
+        };
+        let mut q = Mpz::new();
+        let (p, n) = (q.as_raw_mut(), self.as_raw());
+        let r = unsafe {
+            match round {
+                Round::Ceil => mpz_cdiv_q_ui(p, n, u),
+                Round::Floor | Round::Euclid => mpz_fdiv_q_ui(p, n, u),
+                Round::Trunc => mpz_tdiv_q_ui(p, n, u),
+            }
+        };
+        Ok((q, r as u64))
+    }
+
+    /// Return the absolute value of the remainder of dividing by `d`, rounding the quotient as
+    /// `round` says. The sign of the remainder is as for [`Mpz::div_rem_round_u64`].
+    ///
+    /// This does not compute the quotient, so it does not allocate.
+    #[allow(clippy::unnecessary_fallible_conversions)]
+    pub fn rem_round_u64(&self, d: u64, round: Round) -> Result<u64, MpirError> {
+        if d == 0 {
+            return Err(MpirError::DivisionByZero);
+        }
+        let u = match c_ulong::try_from(d) {
+            Ok(u) => u,
+            Err(_) => return self.div_rem_round_u64(d, round).map(|(_, r)| r),
+        };
+        let n = self.as_raw();
+        let r = unsafe {
+            match round {
+                Round::Ceil => mpz_cdiv_ui(n, u),
+                Round::Floor | Round::Euclid => mpz_fdiv_ui(n, u),
+                Round::Trunc => mpz_tdiv_ui(n, u),
+            }
+        };
+        Ok(r as u64)
+    }
+
+    /// Divide by `2^bits`, rounding the quotient as `round` says, and return the quotient and
+    /// remainder. This is a shift and a mask, so it is much faster than dividing by `2^bits` as an
+    /// `Mpz`.
+    pub fn div_rem_round_2exp(&self, bits: mp_bitcnt_t, round: Round) -> (Mpz, Mpz) {
+        let mut q = Mpz::new();
+        let mut r = Mpz::new();
+        let (pq, pr, n) = (q.as_raw_mut(), r.as_raw_mut(), self.as_raw());
+        unsafe {
+            match round {
+                Round::Ceil => {
+                    mpz_cdiv_q_2exp(pq, n, bits);
+                    mpz_cdiv_r_2exp(pr, n, bits);
+                }
+                Round::Floor | Round::Euclid => {
+                    mpz_fdiv_q_2exp(pq, n, bits);
+                    mpz_fdiv_r_2exp(pr, n, bits);
+                }
+                Round::Trunc => {
+                    mpz_tdiv_q_2exp(pq, n, bits);
+                    mpz_tdiv_r_2exp(pr, n, bits);
+                }
+            }
+        }
+        (q, r)
+    }
+
+    /// Divide by `d`, rounding the quotient towards positive infinity.
+    ///
+    /// # Panics
+    ///
+    /// If `d` is zero.
+    pub fn div_ceil(&self, d: &Mpz) -> Mpz {
+        self.div_round(d, Round::Ceil)
+    }
+
+    /// Divide by `d`, rounding the quotient towards negative infinity.
+    ///
+    /// # Panics
+    ///
+    /// If `d` is zero.
+    pub fn div_floor(&self, d: &Mpz) -> Mpz {
+        self.div_round(d, Round::Floor)
+    }
+
+    /// Divide by `d`, rounding the quotient towards zero. This is the same as `/`.
+    ///
+    /// # Panics
+    ///
+    /// If `d` is zero.
+    pub fn div_trunc(&self, d: &Mpz) -> Mpz {
+        self.div_round(d, Round::Trunc)
+    }
+
+    /// Divide by `d`, rounding the quotient so that the remainder is never negative.
+    ///
+    /// # Panics
+    ///
+    /// If `d` is zero.
+    pub fn div_euclid(&self, d: &Mpz) -> Mpz {
+        self.div_round(d, Round::Euclid)
+    }
+
+    /// Return the remainder of [`Mpz::div_euclid`], which is in the range `0..|d|`.
+    ///
+    /// # Panics
+    ///
+    /// If `d` is zero.
+    pub fn rem_euclid(&self, d: &Mpz) -> Mpz {
+        let mut r = Mpz::new();
+        unsafe { rem_raw(r.as_raw_mut(), self.as_raw(), d.as_raw(), Round::Euclid) };
+        r
+    }
+
+    /// Divide by `d`, which must divide `self` exactly.
+    ///
+    /// This is much faster than the other divisions, but the result is meaningless if `d` does not
+    /// divide `self`.
+    ///
+    /// # Panics
+    ///
+    /// If `d` is zero.
+    pub fn div_exact(&self, d: &Mpz) -> Mpz {
+        check_divisor(d.as_raw());
+        let mut q = Mpz::new();
+        unsafe { mpz_divexact(q.as_raw_mut(), self.as_raw(), d.as_raw()) };
+        q
+    }
+
+    /// Divide by `d`, which must divide `self` exactly, as [`Mpz::div_exact`].
+    ///
+    /// # Panics
+    ///
+    /// If `d` is zero.
+    #[allow(clippy::unnecessary_fallible_conversions)]
+    pub fn div_exact_u64(&self, d: u64) -> Mpz {
+        match c_ulong::try_from(d) {
+            Ok(0) => panic!("attempt to divide by zero"),
+            Ok(u) => {
+                let mut q = Mpz::new();
+                unsafe { mpz_divexact_ui(q.as_raw_mut(), self.as_raw(), u) };
+                q
+            }
+            Err(_) => self.div_exact(&Mpz::from(d)),
+        }
+    }
+
+    fn div_round(&self, d: &Mpz, round: Round) -> Mpz {
+        let mut q = Mpz::new();
+        unsafe { div_raw(q.as_raw_mut(), self.as_raw(), d.as_raw(), round) };
+        q
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    /// `(n, d, round, q, r)` for every combination of signs.
+    const CASES: [(i64, i64, Round, i64, i64); 16] = [
+        (17, 5, Round::Ceil, 4, -3),
+        (-17, 5, Round::Ceil, -3, -2),
+        (17, -5, Round::Ceil, -3, 2),
+        (-17, -5, Round::Ceil, 4, 3),
+        (17, 5, Round::Floor, 3, 2),
+        (-17, 5, Round::Floor, -4, 3),
+        (17, -5, Round::Floor, -4, -3),
+        (-17, -5, Round::Floor, 3, -2),
+        (17, 5, Round::Trunc, 3, 2),
+        (-17, 5, Round::Trunc, -3, -2),
+        (17, -5, Round::Trunc, -3, 2),
+        (-17, -5, Round::Trunc, 3, -2),
+        (17, 5, Round::Euclid, 3, 2),
+        (-17, 5, Round::Euclid, -4, 3),
+        (17, -5, Round::Euclid, -3, 2),
+        (-17, -5, Round::Euclid, 4, 3),
+    ];
+
+    #[test]
+    fn div_rem_round() {
+        for (n, d, round, q, r) in CASES {
+            let (n, d) = (Mpz::from(n), Mpz::from(d));
+            assert_eq!(n.div_rem_round(&d, round), Ok((Mpz::from(q), Mpz::from(r))));
+            assert_eq!(n.div_round(&d, round), q);
+        }
+    }
+
+    #[test]
+    fn named() {
+        let (n, d) = (Mpz::from(-17), Mpz::from(5));
+        assert_eq!(n.div_ceil(&d), -3);
+        assert_eq!(n.div_floor(&d), -4);
+        assert_eq!(n.div_trunc(&d), -3);
+        assert_eq!(n.div_euclid(&d), -4);
+        assert_eq!(n.rem_euclid(&d), 3);
+        assert_eq!(n.rem_euclid(&Mpz::from(-5)), 3);
+        assert_eq!(Mpz::from(-35).div_exact(&Mpz::from(7)), -5);
+        assert_eq!(Mpz::from(u64::MAX).div_exact_u64(5), u64::MAX / 5);
+    }
+
+    #[test]
+    fn u64_divisor() {
+        for (n, d, round, q, r) in CASES.into_iter().filter(|c| c.1 > 0) {
+            let n = Mpz::from(n);
+            let d = d as u64;
+            assert_eq!(
+                n.div_rem_round_u64(d, round),
+                Ok((Mpz::from(q), r.unsigned_abs()))
+            );
+            assert_eq!(n.rem_round_u64(d, round), Ok(r.unsigned_abs()));
+        }
+        let big = Mpz::from(u128::MAX);
+        let (q, r) = big.div_rem_round_u64(u64::MAX, Round::Floor).unwrap();
+        assert_eq!((q, r), (Mpz::from(u64::MAX) + 2u8, 0));
+    }
+
+    #[test]
+    fn power_of_two() {
+        let n = Mpz::from(-17);
+        let cases = [
+            (Round::Ceil, -4, -1),
+            (Round::Floor, -5, 3),
+            (Round::Trunc, -4, -1),
+            (Round::Euclid, -5, 3),
+        ];
+        for (round, q, r) in cases {
+            assert_eq!(n.div_rem_round_2exp(2, round), (Mpz::from(q), Mpz::from(r)));
+        }
+    }
+
+    #[test]
+    fn division_by_zero() {
+        let (n, zero) = (Mpz::from(3), Mpz::new());
+        let err = MpirError::DivisionByZero;
+        assert_eq!(n.div_rem_round(&zero, Round::Euclid), Err(err));
+        assert_eq!(n.div_rem_round_u64(0, Round::Floor), Err(err));
+        assert_eq!(n.rem_round_u64(0, Round::Ceil), Err(err));
+    }
+
+    #[test]
+    #[should_panic(expected = "attempt to divide by zero")]
+    fn div_floor_zero() {
+        Mpz::from(3).div_floor(&Mpz::new());
+    }
+
+    #[test]
+    #[should_panic(expected = "attempt to divide by zero")]
+    fn div_exact_zero() {
+        Mpz::from(3).div_exact_u64(0);
+    }
+}
@@ -0,0 +1,198 @@
+//! Fibonacci and Lucas numbers for [`Mpz`]
+//!
+//! Both are Lucas sequences, `U_n(1, -1)` and `V_n(1, -1)`. Other Lucas sequences, as used by the
+//! Lucas probable prime tests, are computed modulo some `m` by [`Mpz::lucas_sequence_mod`].
+
+use super::*;
+use crate::MpirError;
+
+impl Mpz {
+    /// The Fibonacci number `F_n`.
+    pub fn fibonacci(n: u32) -> Mpz {
+        let mut f = Mpz::new();
+        unsafe { mpz_fib_ui(f.as_raw_mut(), n as c_ulong) };
+        f
+    }
+
+    /// The Fibonacci numbers `(F_n, F_(n-1))`, from which the rest of the sequence can be iterated.
+    ///
+    /// `F_(-1)` is 1.
+    pub fn fibonacci2(n: u32) -> (Mpz, Mpz) {
+        let (mut f, mut prev) = (Mpz::new(), Mpz::new());
+        unsafe { mpz_fib2_ui(f.as_raw_mut(), prev.as_raw_mut(), n as c_ulong) };
+        (f, prev)
+    }
+
+    /// The Lucas number `L_n`.
+    pub fn lucas(n: u32) -> Mpz {
+        let mut l = Mpz::new();
+        unsafe { mpz_lucnum_ui(l.as_raw_mut(), n as c_ulong) };
+        l
+    }
+
+    /// The Lucas numbers `(L_n, L_(n-1))`, from which the rest of the sequence can be iterated.
+    ///
+    /// `L_(-1)` is -1.
+    pub fn lucas2(n: u32) -> (Mpz, Mpz) {
+        let (mut l, mut prev) = (Mpz::new(), Mpz::new());
+        unsafe { mpz_lucnum2_ui(l.as_raw_mut(), prev.as_raw_mut(), n as c_ulong) };
+        (l, prev)
+    }
+
+    /// The Lucas sequences `U_n(P, Q)` and `V_n(P, Q)` modulo `m`, as `(U_n, V_n)` in the range
+    /// `0..|m|`.
+    ///
+    /// The sequences start `U_0 = 0, U_1 = 1` and `V_0 = 2, V_1 = P`, and continue with
+    /// `X_(n+1) = P X_n - Q X_(n-1)`. They are computed in `O(log n)` multiplications, so `n` can be
+    /// as large as the numbers being tested for primality.
+    ///
+    /// Returns [`MpirError::DivisionByZero`] if `m` is zero.
+    ///
+    /// # Panics
+    ///
+    /// If `n` is negative.
+    pub fn lucas_sequence_mod(p: &Mpz, q: &Mpz, n: &Mpz, m: &Mpz) -> Result<(Mpz, Mpz), MpirError> {
+        assert!(
+            !n.is_negative(),
+            "Lucas sequence index must not be negative"
+        );
+        if m.is_zero() {
+            return Err(MpirError::DivisionByZero);
+        }
+        let (u, v, _) = lucas_mod(p, q, n, m);
+        Ok((u, v))
+    }
+}
+
+/// The Lucas sequences `U_k(P, Q)` and `V_k(P, Q)`, and `Q^k`, modulo `m`, for `k >= 0` and
+/// `m != 0`.
+///
+/// Each step doubles `k`, and adds one for a set bit, keeping `U_k` and `U_(k+1)` with
+///
+/// ```text
+/// U_2k     = U_k (2 U_(k+1) - P U_k)
+/// U_(2k+1) = U_(k+1)^2 - Q U_k^2
+/// U_(2k+2) = P U_(2k+1) - Q U_2k
+/// ```
+///
+/// which, unlike the usual formulas in `U` and `V`, need no division by 2 or by the discriminant, so
+/// work for any modulus. At the end `V_k = 2 U_(k+1) - P U_k`.
+pub(super) fn lucas_mod(p: &Mpz, q: &Mpz, k: &Mpz, m: &Mpz) -> (Mpz, Mpz, Mpz) {
+    let reduce = |x: Mpz| x.rem_euclid(m);
+    let p = p.rem_euclid(m);
+    let q = q.rem_euclid(m);
+    let mut u0 = Mpz::new();
+    let mut u1 = reduce(Mpz::from(1));
+    let mut qk = u1.clone();
+    for i in (0..k.size_in_base(2) as mp_bitcnt_t).rev() {
+        let mut u2k = Mpz::from(&u1 * 2u32);
+        u2k -= &p * &u0;
+        let u2k = reduce(u2k * &u0);
+        let mut u2k1 = Mpz::from(&u1 * &u1);
+        u2k1 -= Mpz::from(&u0 * &u0) * &q;
+        let u2k1 = reduce(u2k1);
+        qk = reduce(Mpz::from(&qk * &qk));
+        if unsafe { mpz_tstbit(k.as_raw(), i) } != 0 {
+            let mut u2k2 = Mpz::from(&p * &u2k1);
+            u2k2 -= &q * &u2k;
+            u1 = reduce(u2k2);
+            u0 = u2k1;
+            qk = reduce(qk * &q);
+        } else {
+            u0 = u2k;
+            u1 = u2k1;
+        }
+    }
+    let mut v = Mpz::from(&u1 * 2u32);
+    v -= &p * &u0;
+    let v = reduce(v);
+    (u0, v, qk)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn fibonacci() {
+        let fib: Vec<Mpz> = (0..10).map(Mpz::fibonacci).collect();
+        assert_eq!(fib, [0, 1, 1, 2, 3, 5, 8, 13, 21, 34].map(Mpz::from));
+        assert_eq!(Mpz::fibonacci(90), 2880067194370816120u64);
+        assert_eq!(
+            Mpz::fibonacci(200).to_string(),
+            "280571172992510140037611932413038677189525"
+        );
+        assert_eq!(Mpz::fibonacci2(0), (Mpz::new(), Mpz::from(1)));
+        let (f, prev) = Mpz::fibonacci2(100);
+        assert_eq!(f, Mpz::fibonacci(100));
+        assert_eq!(prev, Mpz::fibonacci(99));
+    }
+
+    #[test]
+    fn lucas() {
+        let lucas: Vec<Mpz> = (0..10).map(Mpz::lucas).collect();
+        assert_eq!(lucas, [2, 1, 3, 4, 7, 11, 18, 29, 47, 76].map(Mpz::from));
+        assert_eq!(Mpz::lucas(90), 6440026026380244498u64);
+        assert_eq!(Mpz::lucas2(0), (Mpz::from(2), Mpz::from(-1)));
+        let (l, prev) = Mpz::lucas2(100);
+        assert_eq!(l, Mpz::lucas(100));
+        assert_eq!(prev, Mpz::lucas(99));
+        // L_n = F_(n-1) + F_(n+1)
+        assert_eq!(l, Mpz::fibonacci(99) + Mpz::fibonacci(101));
+    }
+
+    #[test]
+    fn lucas_sequence_mod() {
+        let (one, minus_one) = (Mpz::from(1), Mpz::from(-1));
+        let m = Mpz::from(1) << 200u32;
+        for n in [0u32, 1, 2, 3, 10, 90, 150] {
+            let (u, v) = Mpz::lucas_sequence_mod(&one, &minus_one, &Mpz::from(n), &m).unwrap();
+            assert_eq!(u, Mpz::fibonacci(n), "U_{}", n);
+            assert_eq!(v, Mpz::lucas(n), "V_{}", n);
+        }
+        let (u, v) =
+            Mpz::lucas_sequence_mod(&one, &minus_one, &Mpz::from(5), &Mpz::from(-4)).unwrap();
+        assert_eq!((u, v), (Mpz::from(1), Mpz::from(3)));
+
+        // P = 3, Q = 2 gives U_n = 2^n - 1 and V_n = 2^n + 1.
+        let (p, q) = (Mpz::from(3), Mpz::from(2));
+        let n = Mpz::from(1000);
+        let m = Mpz::from(1_000_000_007);
+        let (u, v) = Mpz::lucas_sequence_mod(&p, &q, &n, &m).unwrap();
+        let two_n = Mpz::from(2).pow_mod(&n, &m).unwrap();
+        assert_eq!(u, (two_n.clone() - 1u32).rem_euclid(&m));
+        assert_eq!(v, (two_n + 1u32).rem_euclid(&m));
+
+        assert_eq!(
+            Mpz::lucas_sequence_mod(&p, &q, &n, &Mpz::new()),
+            Err(MpirError::DivisionByZero)
+        );
+    }
+
+    #[test]
+    fn lucas_mod_qk() {
+        // Fibonacci and Lucas numbers are U and V for P = 1, Q = -1.
+        let big = Mpz::from(1) << 100u32;
+        let (one, minus_one) = (Mpz::from(1), Mpz::from(-1));
+        let (u, v, qk) = lucas_mod(&one, &minus_one, &Mpz::from(90), &big);
+        assert_eq!(u, 2880067194370816120u64);
+        assert_eq!(v, 6440026026380244498u64);
+        assert_eq!(qk, 1);
+        let (u, v, qk) = lucas_mod(&one, &minus_one, &Mpz::from(5), &Mpz::from(4));
+        assert_eq!((u, v, qk), (Mpz::from(1), Mpz::from(3), Mpz::from(3)));
+        let (u, v, qk) = lucas_mod(&one, &minus_one, &Mpz::new(), &Mpz::from(7));
+        assert_eq!((u, v, qk), (Mpz::new(), Mpz::from(2), Mpz::from(1)));
+
+        // Q^k for Q = 2.
+        let m = Mpz::from(1_000_000_007);
+        let (_, _, qk) = lucas_mod(&Mpz::from(3), &Mpz::from(2), &Mpz::from(1000), &m);
+        assert_eq!(qk, Mpz::from(2).pow_mod_u64(1000, &m).unwrap());
+    }
+
+    #[test]
+    #[should_panic(expected = "Lucas sequence index must not be negative")]
+    fn lucas_sequence_negative() {
+        let _ =
+            Mpz::lucas_sequence_mod(&Mpz::from(1), &Mpz::from(-1), &Mpz::from(-1), &Mpz::from(7));
+    }
+}
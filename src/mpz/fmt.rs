@@ -0,0 +1,272 @@
+//! String conversion for [`Mpz`]
+//!
+//! Formatting goes through `mpz_get_str` and parsing through `mpz_init_set_str`. Bases 2 to 62 are
+//! supported: for bases up to 36 the digits are `0-9` then `a-z` (case-insensitive when parsing),
+//! and for bases 37 to 62 they are `0-9`, `A-Z` then `a-z`.
+
+use std::error::Error;
+use std::ffi::CStr;
+use std::fmt;
+use std::ptr::null_mut;
+use std::str::FromStr;
+
+use super::*;
+use crate::MpirError;
+
+/// Convert `op` to a string in `base` (negative for upper-case digits), as MPIR formats it.
+fn get_str(op: &Mpz, base: c_int) -> String {
+    unsafe {
+        let ptr = mpz_get_str(null_mut(), base, op.as_raw());
+        let s = CStr::from_ptr(ptr);
+        let len = s.to_bytes_with_nul().len();
+        let out = String::from(s.to_str().expect("MPIR produced a non-ASCII digit"));
+        crate::alloc::free(ptr as *mut c_void, len);
+        out
+    }
+}
+
+fn check_radix(radix: i32) -> Result<(), MpirError> {
+    match (2..=62).contains(&radix) {
+        true => Ok(()),
+        false => Err(MpirError::InvalidBase(radix)),
+    }
+}
+
+/// Value of a digit character in `radix`, following `mpz_set_str`.
+fn digit_value(c: u8, radix: i32) -> Option<i32> {
+    let v = match c {
+        b'0'..=b'9' => c - b'0',
+        b'A'..=b'Z' => c - b'A' + 10,
+        b'a'..=b'z' if radix <= 36 => c - b'a' + 10,
+        b'a'..=b'z' => c - b'a' + 36,
+        _ => return None,
+    } as i32;
+    (v < radix).then_some(v)
+}
+
+impl Mpz {
+    /// Return the number of digits of the absolute value in `base`, which can vary from 2 to 62.
+    ///
+    /// The result is either exact or one too big. It is exact if `base` is a power of 2. For zero
+    /// the result is always 1.
+    ///
+    /// # Panics
+    ///
+    /// If `base` is not in the range 2 to 62.
+    pub fn size_in_base(&self, base: i32) -> usize {
+        if let Err(e) = check_radix(base) {
+            panic!("{}", e);
+        }
+        unsafe { mpz_sizeinbase(self.as_raw(), base) as usize }
+    }
+
+    /// Convert to a string of digits in `radix`, with a leading `-` if negative.
+    ///
+    /// Bases above 10 use lower-case letters for digits 10 to 35, and bases above 36 use upper-case
+    /// letters for 10 to 35 and lower-case letters for 36 to 61.
+    ///
+    /// Returns [`MpirError::InvalidBase`] if `radix` is not in the range 2 to 62.
+    pub fn to_string_radix(&self, radix: i32) -> Result<String, MpirError> {
+        check_radix(radix)?;
+        Ok(get_str(self, radix))
+    }
+
+    /// Parse a string of digits in `radix`, with an optional leading `+` or `-`.
+    ///
+    /// Like the primitive integer types, no whitespace or base prefix is accepted. A `radix` outside
+    /// the range 2 to 62 gives [`ParseMpzErrorKind::InvalidBase`].
+    pub fn from_str_radix(s: &str, radix: i32) -> Result<Self, ParseMpzError> {
+        if check_radix(radix).is_err() {
+            return Err(ParseMpzError {
+                kind: ParseMpzErrorKind::InvalidBase(radix),
+            });
+        }
+        if s.is_empty() {
+            return Err(ParseMpzError {
+                kind: ParseMpzErrorKind::Empty,
+            });
+        }
+        let digits = s.strip_prefix(['+', '-']).unwrap_or(s);
+        if digits.is_empty() || digits.bytes().any(|c| digit_value(c, radix).is_none()) {
+            return Err(ParseMpzError {
+                kind: ParseMpzErrorKind::InvalidDigit,
+            });
+        }
+
+        // MPIR accepts a leading `-` but not `+`.
+        let c = CString::new(s.strip_prefix('+').unwrap_or(s)).expect("digits were validated");
+        let mut ok = 0;
+        let z = unsafe { Mpz::from_init(|x| ok = mpz_init_set_str(x, c.as_ptr(), radix)) };
+        match ok {
+            0 => Ok(z),
+            _ => Err(ParseMpzError {
+                kind: ParseMpzErrorKind::InvalidDigit,
+            }),
+        }
+    }
+}
+
+impl FromStr for Mpz {
+    type Err = ParseMpzError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Mpz::from_str_radix(s, 10)
+    }
+}
+
+macro_rules! format_radix {
+    ($Trait:ident $base:literal $prefix:literal) => {
+        impl fmt::$Trait for Mpz {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                let s = get_str(self, $base);
+                match s.strip_prefix('-') {
+                    Some(digits) => f.pad_integral(false, $prefix, digits),
+                    None => f.pad_integral(true, $prefix, &s),
+                }
+            }
+        }
+    };
+}
+
+format_radix! { Display 10 "" }
+format_radix! { LowerHex 16 "0x" }
+format_radix! { UpperHex -16 "0x" }
+format_radix! { Octal 8 "0o" }
+format_radix! { Binary 2 "0b" }
+
+impl fmt::Debug for Mpz {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(self, f)
+    }
+}
+
+/// Reason a string could not be parsed as an [`Mpz`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ParseMpzErrorKind {
+    /// The string had no digits.
+    Empty,
+    /// The string contained a character that is not a digit in the given base.
+    InvalidDigit,
+    /// The base was not in the range 2 to 62.
+    InvalidBase(i32),
+}
+
+/// Error returned when parsing an [`Mpz`] from a string fails.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ParseMpzError {
+    kind: ParseMpzErrorKind,
+}
+
+impl ParseMpzError {
+    /// The reason parsing failed.
+    pub fn kind(&self) -> &ParseMpzErrorKind {
+        &self.kind
+    }
+}
+
+impl fmt::Display for ParseMpzError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.kind {
+            ParseMpzErrorKind::Empty => f.write_str("cannot parse integer from empty string"),
+            ParseMpzErrorKind::InvalidDigit => f.write_str("invalid digit found in string"),
+            ParseMpzErrorKind::InvalidBase(radix) => MpirError::InvalidBase(radix).fmt(f),
+        }
+    }
+}
+
+impl Error for ParseMpzError {}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn int(v: i64) -> Mpz {
+        Mpz::new() + v
+    }
+
+    #[test]
+    fn display() {
+        assert_eq!(int(0).to_string(), "0");
+        assert_eq!(int(-1234).to_string(), "-1234");
+        assert_eq!(
+            (int(1) << 100u32).to_string(),
+            "1267650600228229401496703205376"
+        );
+        assert_eq!(format!("{:+}", int(5)), "+5");
+        assert_eq!(format!("{:>6}", int(-42)), "   -42");
+        assert_eq!(format!("{:*<5}", int(42)), "42***");
+        assert_eq!(format!("{:06}", int(-42)), "-00042");
+        assert_eq!(format!("{:?}", int(-7)), "-7");
+    }
+
+    #[test]
+    fn radix_formats() {
+        let x = int(-255);
+        assert_eq!(format!("{:x}", x), "-ff");
+        assert_eq!(format!("{:X}", x), "-FF");
+        assert_eq!(format!("{:#x}", x), "-0xff");
+        assert_eq!(format!("{:#010X}", int(255)), "0x000000FF");
+        assert_eq!(format!("{:o}", int(8)), "10");
+        assert_eq!(format!("{:#b}", int(5)), "0b101");
+        assert_eq!(format!("{:+#o}", int(8)), "+0o10");
+    }
+
+    #[test]
+    fn to_string_radix() {
+        let s = |v, radix| int(v).to_string_radix(radix).unwrap();
+        assert_eq!(s(35, 36), "z");
+        assert_eq!(s(-61, 62), "-z");
+        assert_eq!(s(36, 62), "a");
+        assert_eq!(s(10, 62), "A");
+        assert_eq!(s(5, 2), "101");
+    }
+
+    #[test]
+    fn from_str() {
+        let x: Mpz = "-1267650600228229401496703205376".parse().unwrap();
+        assert_eq!(x.to_string(), "-1267650600228229401496703205376");
+        assert_eq!("+17".parse::<Mpz>().unwrap().to_string(), "17");
+        assert_eq!(Mpz::from_str_radix("FF", 16).unwrap().to_string(), "255");
+        assert_eq!(Mpz::from_str_radix("ff", 16).unwrap().to_string(), "255");
+        assert_eq!(Mpz::from_str_radix("a", 62).unwrap().to_string(), "36");
+        assert_eq!(Mpz::from_str_radix("A", 62).unwrap().to_string(), "10");
+    }
+
+    #[test]
+    fn parse_errors() {
+        let kind = |s: &str, radix| *Mpz::from_str_radix(s, radix).unwrap_err().kind();
+        assert_eq!(kind("", 10), ParseMpzErrorKind::Empty);
+        assert_eq!(kind("-", 10), ParseMpzErrorKind::InvalidDigit);
+        assert_eq!(kind("12a", 10), ParseMpzErrorKind::InvalidDigit);
+        assert_eq!(kind("1 2", 10), ParseMpzErrorKind::InvalidDigit);
+        assert_eq!(kind("+-1", 10), ParseMpzErrorKind::InvalidDigit);
+        assert_eq!(kind("2", 2), ParseMpzErrorKind::InvalidDigit);
+        assert_eq!(kind("0x10", 16), ParseMpzErrorKind::InvalidDigit);
+        assert_eq!(kind("1\0", 10), ParseMpzErrorKind::InvalidDigit);
+        assert_eq!(kind("1", 63), ParseMpzErrorKind::InvalidBase(63));
+        assert_eq!(kind("", 1), ParseMpzErrorKind::InvalidBase(1));
+    }
+
+    #[test]
+    fn size_in_base() {
+        assert_eq!(int(0).size_in_base(10), 1);
+        assert_eq!(int(255).size_in_base(2), 8);
+        assert_eq!(int(-255).size_in_base(16), 2);
+    }
+
+    #[test]
+    fn bad_radix() {
+        assert_eq!(int(1).to_string_radix(63), Err(MpirError::InvalidBase(63)));
+        assert_eq!(int(1).to_string_radix(-16), Err(MpirError::InvalidBase(-16)));
+        assert_eq!(
+            Mpz::from_str_radix("1", 0).unwrap_err().to_string(),
+            "radix must be in the range 2..=62, got 0"
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "radix must be in the range 2..=62")]
+    fn bad_size_radix() {
+        int(1).size_in_base(1);
+    }
+}
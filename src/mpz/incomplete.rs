@@ -0,0 +1,467 @@
+//! Lazily evaluated expressions on borrowed [`Mpz`] values
+//!
+//! `&a + &b`, `&a - &b` and `&a * &b` (and the same with a primitive on either side) do not compute
+//! anything. They return an incomplete value holding references to their operands, which is only
+//! evaluated when it is assigned somewhere:
+//!
+//! - `x.assign(&a * &b)` writes the product straight into `x`'s existing allocation.
+//! - `x += &a * &b` and `x -= &a * &b` use `mpz_addmul` / `mpz_submul`.
+//! - `x.assign(&a * &b + &c)` copies `c` into `x` and then uses `mpz_addmul`, so no temporary is
+//!   created for the product.
+//! - `Mpz::from(expr)` evaluates into a fresh integer.
+//!
+//! Incomplete values can be combined further with `+`, `-` and `*`, in which case they are
+//! evaluated into a new `Mpz` first and the rest of the expression continues in place.
+
+use std::ops::{Add, AddAssign, Mul, Sub, SubAssign};
+
+use super::ops::{add_small, mul_small, small_sub, sub_small, Primitive, Small};
+use super::*;
+use crate::Assign;
+
+/// The second operand of an incomplete expression.
+enum Operand<'a> {
+    Mpz(&'a Mpz),
+    Small(Small),
+}
+
+/// Incomplete `&a + &b`, evaluated when assigned to an [`Mpz`].
+pub struct AddIncomplete<'a> {
+    lhs: &'a Mpz,
+    rhs: Operand<'a>,
+}
+
+/// Incomplete `&a - &b`, evaluated when assigned to an [`Mpz`].
+pub struct SubIncomplete<'a> {
+    lhs: &'a Mpz,
+    rhs: Operand<'a>,
+    /// Whether the operands were written the other way round, i.e. this is `rhs - lhs`.
+    reversed: bool,
+}
+
+/// Incomplete `&a * &b`, evaluated when assigned to an [`Mpz`].
+pub struct MulIncomplete<'a> {
+    lhs: &'a Mpz,
+    rhs: Operand<'a>,
+}
+
+/// Incomplete `&c + &a * &b`, evaluated when assigned to an [`Mpz`].
+pub struct AddMulIncomplete<'a> {
+    acc: &'a Mpz,
+    product: MulIncomplete<'a>,
+}
+
+/// Incomplete `&c - &a * &b`, evaluated when assigned to an [`Mpz`].
+pub struct SubMulIncomplete<'a> {
+    acc: &'a Mpz,
+    product: MulIncomplete<'a>,
+}
+
+// -------------------------------------------------------------------------------------------------
+// Evaluation
+
+impl AddIncomplete<'_> {
+    unsafe fn eval(&self, rop: mpz_ptr) {
+        match &self.rhs {
+            Operand::Mpz(b) => mpz_add(rop, self.lhs.as_raw(), b.as_raw()),
+            Operand::Small(s) => add_small(rop, self.lhs.as_raw(), s),
+        }
+    }
+}
+
+impl SubIncomplete<'_> {
+    unsafe fn eval(&self, rop: mpz_ptr) {
+        match (&self.rhs, self.reversed) {
+            (Operand::Mpz(b), false) => mpz_sub(rop, self.lhs.as_raw(), b.as_raw()),
+            (Operand::Mpz(b), true) => mpz_sub(rop, b.as_raw(), self.lhs.as_raw()),
+            (Operand::Small(s), false) => sub_small(rop, self.lhs.as_raw(), s),
+            (Operand::Small(s), true) => small_sub(rop, s, self.lhs.as_raw()),
+        }
+    }
+}
+
+impl MulIncomplete<'_> {
+    unsafe fn eval(&self, rop: mpz_ptr) {
+        match &self.rhs {
+            Operand::Mpz(b) => mpz_mul(rop, self.lhs.as_raw(), b.as_raw()),
+            Operand::Small(s) => mul_small(rop, self.lhs.as_raw(), s),
+        }
+    }
+
+    /// Add the product to `rop` in place.
+    unsafe fn add_to(&self, rop: mpz_ptr) {
+        let a = self.lhs.as_raw();
+        match &self.rhs {
+            Operand::Mpz(b) => mpz_addmul(rop, a, b.as_raw()),
+            Operand::Small(Small::Pos(u)) => mpz_addmul_ui(rop, a, *u),
+            Operand::Small(Small::Neg(u)) => mpz_submul_ui(rop, a, *u),
+            Operand::Small(Small::Big(z)) => mpz_addmul(rop, a, z.as_raw()),
+        }
+    }
+
+    /// Subtract the product from `rop` in place.
+    unsafe fn sub_from(&self, rop: mpz_ptr) {
+        let a = self.lhs.as_raw();
+        match &self.rhs {
+            Operand::Mpz(b) => mpz_submul(rop, a, b.as_raw()),
+            Operand::Small(Small::Pos(u)) => mpz_submul_ui(rop, a, *u),
+            Operand::Small(Small::Neg(u)) => mpz_addmul_ui(rop, a, *u),
+            Operand::Small(Small::Big(z)) => mpz_submul(rop, a, z.as_raw()),
+        }
+    }
+}
+
+impl AddMulIncomplete<'_> {
+    unsafe fn eval(&self, rop: mpz_ptr) {
+        mpz_set(rop, self.acc.as_raw());
+        self.product.add_to(rop);
+    }
+}
+
+impl SubMulIncomplete<'_> {
+    unsafe fn eval(&self, rop: mpz_ptr) {
+        mpz_set(rop, self.acc.as_raw());
+        self.product.sub_from(rop);
+    }
+}
+
+macro_rules! complete {
+    ($($Ty:ident)*) => {$(
+        impl Assign<$Ty<'_>> for Mpz {
+            fn assign(&mut self, src: $Ty<'_>) {
+                unsafe { src.eval(self.as_raw_mut()) }
+            }
+        }
+
+        impl From<$Ty<'_>> for Mpz {
+            fn from(src: $Ty<'_>) -> Self {
+                let mut r = Mpz::new();
+                r.assign(src);
+                r
+            }
+        }
+    )*};
+}
+
+complete! { AddIncomplete SubIncomplete MulIncomplete AddMulIncomplete SubMulIncomplete }
+
+// -------------------------------------------------------------------------------------------------
+// Assignment of complete values
+
+impl Assign<&Mpz> for Mpz {
+    fn assign(&mut self, src: &Mpz) {
+        unsafe { mpz_set(self.as_raw_mut(), src.as_raw()) }
+    }
+}
+
+impl Assign<Mpz> for Mpz {
+    fn assign(&mut self, mut src: Mpz) {
+        unsafe { mpz_swap(self.as_raw_mut(), src.as_raw_mut()) }
+    }
+}
+
+macro_rules! assign_prim {
+    ($($t:ty)*) => {$(
+        impl Assign<$t> for Mpz {
+            fn assign(&mut self, src: $t) {
+                let p = self.as_raw_mut();
+                match src.small() {
+                    Small::Pos(u) => unsafe { mpz_set_ui(p, u) },
+                    Small::Neg(u) => unsafe {
+                        mpz_set_ui(p, u);
+                        mpz_neg(p, p);
+                    },
+                    Small::Big(mut z) => unsafe { mpz_swap(p, z.as_raw_mut()) },
+                }
+            }
+        }
+    )*};
+}
+
+assign_prim! { u8 u16 u32 u64 u128 usize i8 i16 i32 i64 i128 isize }
+
+// -------------------------------------------------------------------------------------------------
+// Building incomplete values
+
+impl<'a> Add<&'a Mpz> for &'a Mpz {
+    type Output = AddIncomplete<'a>;
+    fn add(self, rhs: &'a Mpz) -> AddIncomplete<'a> {
+        AddIncomplete {
+            lhs: self,
+            rhs: Operand::Mpz(rhs),
+        }
+    }
+}
+
+impl<'a> Sub<&'a Mpz> for &'a Mpz {
+    type Output = SubIncomplete<'a>;
+    fn sub(self, rhs: &'a Mpz) -> SubIncomplete<'a> {
+        SubIncomplete {
+            lhs: self,
+            rhs: Operand::Mpz(rhs),
+            reversed: false,
+        }
+    }
+}
+
+impl<'a> Mul<&'a Mpz> for &'a Mpz {
+    type Output = MulIncomplete<'a>;
+    fn mul(self, rhs: &'a Mpz) -> MulIncomplete<'a> {
+        MulIncomplete {
+            lhs: self,
+            rhs: Operand::Mpz(rhs),
+        }
+    }
+}
+
+macro_rules! incomplete_prim {
+    ($($t:ty)*) => {$(
+        impl<'a> Add<$t> for &'a Mpz {
+            type Output = AddIncomplete<'a>;
+            fn add(self, rhs: $t) -> AddIncomplete<'a> {
+                AddIncomplete { lhs: self, rhs: Operand::Small(rhs.small()) }
+            }
+        }
+
+        impl<'a> Add<&'a Mpz> for $t {
+            type Output = AddIncomplete<'a>;
+            fn add(self, rhs: &'a Mpz) -> AddIncomplete<'a> {
+                AddIncomplete { lhs: rhs, rhs: Operand::Small(self.small()) }
+            }
+        }
+
+        impl<'a> Sub<$t> for &'a Mpz {
+            type Output = SubIncomplete<'a>;
+            fn sub(self, rhs: $t) -> SubIncomplete<'a> {
+                SubIncomplete { lhs: self, rhs: Operand::Small(rhs.small()), reversed: false }
+            }
+        }
+
+        impl<'a> Sub<&'a Mpz> for $t {
+            type Output = SubIncomplete<'a>;
+            fn sub(self, rhs: &'a Mpz) -> SubIncomplete<'a> {
+                SubIncomplete { lhs: rhs, rhs: Operand::Small(self.small()), reversed: true }
+            }
+        }
+
+        impl<'a> Mul<$t> for &'a Mpz {
+            type Output = MulIncomplete<'a>;
+            fn mul(self, rhs: $t) -> MulIncomplete<'a> {
+                MulIncomplete { lhs: self, rhs: Operand::Small(rhs.small()) }
+            }
+        }
+
+        impl<'a> Mul<&'a Mpz> for $t {
+            type Output = MulIncomplete<'a>;
+            fn mul(self, rhs: &'a Mpz) -> MulIncomplete<'a> {
+                MulIncomplete { lhs: rhs, rhs: Operand::Small(self.small()) }
+            }
+        }
+    )*};
+}
+
+incomplete_prim! { u8 u16 u32 u64 u128 usize i8 i16 i32 i64 i128 isize }
+
+// -------------------------------------------------------------------------------------------------
+// Fused multiply-add / multiply-subtract
+
+impl<'a> Add<MulIncomplete<'a>> for &'a Mpz {
+    type Output = AddMulIncomplete<'a>;
+    fn add(self, rhs: MulIncomplete<'a>) -> AddMulIncomplete<'a> {
+        AddMulIncomplete {
+            acc: self,
+            product: rhs,
+        }
+    }
+}
+
+impl<'a> Add<&'a Mpz> for MulIncomplete<'a> {
+    type Output = AddMulIncomplete<'a>;
+    fn add(self, rhs: &'a Mpz) -> AddMulIncomplete<'a> {
+        AddMulIncomplete {
+            acc: rhs,
+            product: self,
+        }
+    }
+}
+
+impl<'a> Sub<MulIncomplete<'a>> for &'a Mpz {
+    type Output = SubMulIncomplete<'a>;
+    fn sub(self, rhs: MulIncomplete<'a>) -> SubMulIncomplete<'a> {
+        SubMulIncomplete {
+            acc: self,
+            product: rhs,
+        }
+    }
+}
+
+impl AddAssign<MulIncomplete<'_>> for Mpz {
+    fn add_assign(&mut self, rhs: MulIncomplete<'_>) {
+        unsafe { rhs.add_to(self.as_raw_mut()) }
+    }
+}
+
+impl SubAssign<MulIncomplete<'_>> for Mpz {
+    fn sub_assign(&mut self, rhs: MulIncomplete<'_>) {
+        unsafe { rhs.sub_from(self.as_raw_mut()) }
+    }
+}
+
+impl Add<MulIncomplete<'_>> for Mpz {
+    type Output = Mpz;
+    fn add(mut self, rhs: MulIncomplete<'_>) -> Mpz {
+        self += rhs;
+        self
+    }
+}
+
+impl Add<Mpz> for MulIncomplete<'_> {
+    type Output = Mpz;
+    fn add(self, mut rhs: Mpz) -> Mpz {
+        rhs += self;
+        rhs
+    }
+}
+
+impl Sub<MulIncomplete<'_>> for Mpz {
+    type Output = Mpz;
+    fn sub(mut self, rhs: MulIncomplete<'_>) -> Mpz {
+        self -= rhs;
+        self
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+// Chaining
+//
+// Anything without a fused form is evaluated into a new `Mpz`, and the operation then continues in
+// place on that value.
+
+macro_rules! chain {
+    ($Ty:ident $Op:ident $op:ident; $($Rhs:ty)*) => {$(
+        impl $Op<$Rhs> for $Ty<'_> {
+            type Output = Mpz;
+            fn $op(self, rhs: $Rhs) -> Mpz {
+                Mpz::from(self).$op(rhs)
+            }
+        }
+    )*};
+}
+
+macro_rules! chain_prims {
+    ($($Ty:ident $Op:ident $op:ident;)*) => {$(
+        chain! { $Ty $Op $op; u8 u16 u32 u64 u128 usize i8 i16 i32 i64 i128 isize }
+    )*};
+}
+
+macro_rules! chain_all {
+    ($($Ty:ident $Op:ident $op:ident;)*) => {$(
+        chain! { $Ty $Op $op; Mpz &Mpz }
+        chain_prims! { $Ty $Op $op; }
+    )*};
+}
+
+chain_all! {
+    AddIncomplete Add add;
+    AddIncomplete Sub sub;
+    AddIncomplete Mul mul;
+    SubIncomplete Add add;
+    SubIncomplete Sub sub;
+    SubIncomplete Mul mul;
+    MulIncomplete Sub sub;
+    MulIncomplete Mul mul;
+    AddMulIncomplete Add add;
+    AddMulIncomplete Sub sub;
+    AddMulIncomplete Mul mul;
+    SubMulIncomplete Add add;
+    SubMulIncomplete Sub sub;
+    SubMulIncomplete Mul mul;
+}
+
+chain_prims! {
+    MulIncomplete Add add;
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn int(v: i64) -> Mpz {
+        Mpz::new() + v
+    }
+
+    fn is(x: &Mpz, v: i64) -> bool {
+        unsafe { mpz_cmp_si(x.as_raw(), v) == 0 }
+    }
+
+    #[test]
+    #[allow(clippy::useless_conversion)] // The chained operation is already an Mpz.
+    fn evaluate() {
+        let a = int(7);
+        let b = int(-3);
+        assert!(is(&Mpz::from(&a + &b), 4));
+        assert!(is(&Mpz::from(&a - &b), 10));
+        assert!(is(&Mpz::from(&a * &b), -21));
+        assert!(is(&Mpz::from(&a - 10u8), -3));
+        assert!(is(&Mpz::from(10u8 - &a), 3));
+        assert!(is(&Mpz::from(-2i32 * &a), -14));
+        assert!(is(&Mpz::from(&a + i128::MIN - i128::MIN), 7));
+    }
+
+    #[test]
+    fn fused() {
+        let a = int(7);
+        let b = int(-3);
+        let c = int(100);
+        assert!(is(&Mpz::from(&a * &b + &c), 79));
+        assert!(is(&Mpz::from(&c + &a * &b), 79));
+        assert!(is(&Mpz::from(&c - &a * &b), 121));
+        assert!(is(&Mpz::from(&c - &a * -5i32), 135));
+        assert!(is(&(c.clone() + &a * 2u8), 114));
+        assert!(is(&(c.clone() - &a * &b), 121));
+    }
+
+    #[test]
+    fn chained() {
+        let a = int(7);
+        let b = int(-3);
+        assert!(is(&(&a + &b + &a), 11));
+        assert!(is(&(&a * &b - &a), -28));
+        assert!(is(&(&a * &b * 2u8), -42));
+        assert!(is(&(&a - &b + 1i8), 11));
+    }
+
+    #[test]
+    fn assign_reuses_allocation() {
+        let a = int(1) << 100u32;
+        let b = int(3) << 90u32;
+        let c = int(5);
+        let mut x = Mpz::with_capacity_bits(512);
+        let limbs = x.0._mp_d;
+
+        x.assign(&a * &b + &c);
+        assert_eq!(x.0._mp_d, limbs);
+        x += &a * &b;
+        x -= &a * 3u32;
+        x.assign(&a - &b);
+        x.assign(-5i64);
+        assert_eq!(x.0._mp_d, limbs);
+        assert!(is(&x, -5));
+    }
+
+    #[test]
+    fn addmul_submul() {
+        let a = int(6);
+        let b = int(7);
+        let mut x = int(1);
+        x += &a * &b;
+        assert!(is(&x, 43));
+        x -= &a * -2i16;
+        assert!(is(&x, 55));
+        x -= &a * &b;
+        assert!(is(&x, 13));
+        x += &a * u128::MAX;
+        x -= &a * u128::MAX;
+        assert!(is(&x, 13));
+    }
+}
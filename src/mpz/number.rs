@@ -0,0 +1,331 @@
+//! Number theoretic functions for [`Mpz`]
+//!
+//! The greatest common divisor and least common multiple are never negative, whatever the signs of
+//! the operands.
+//!
+//! MPIR leaves the Jacobi and Legendre symbols undefined unless the lower argument is odd, so the
+//! safe methods check that it is odd and positive and return [`MpirError::InvalidModulus`] if not.
+//! The Kronecker symbol is defined for all arguments.
+
+use std::borrow::Borrow;
+use std::cmp::Ordering;
+
+use super::*;
+use crate::MpirError;
+
+/// Check the lower argument of a Jacobi or Legendre symbol.
+fn check_odd_positive(n: &Mpz) -> Result<(), MpirError> {
+    match n.is_positive() && unsafe { mpz_odd_p(n.as_raw()) } != 0 {
+        true => Ok(()),
+        false => Err(MpirError::InvalidModulus),
+    }
+}
+
+impl Mpz {
+    /// The greatest common divisor of `self` and `other`.
+    ///
+    /// The gcd of 0 and 0 is 0.
+    pub fn gcd(&self, other: &Mpz) -> Mpz {
+        let mut g = Mpz::new();
+        unsafe { mpz_gcd(g.as_raw_mut(), self.as_raw(), other.as_raw()) };
+        g
+    }
+
+    /// The greatest common divisor of `self` and `other`.
+    #[allow(clippy::unnecessary_fallible_conversions)]
+    pub fn gcd_u64(&self, other: u64) -> Mpz {
+        match c_ulong::try_from(other) {
+            Ok(u) => {
+                let mut g = Mpz::new();
+                unsafe { mpz_gcd_ui(g.as_raw_mut(), self.as_raw(), u) };
+                g
+            }
+            Err(_) => self.gcd(&Mpz::from(other)),
+        }
+    }
+
+    /// The greatest common divisor `g` of `self` and `other`, and coefficients `s` and `t` such
+    /// that `self * s + other * t == g`, as `(g, s, t)`.
+    ///
+    /// The coefficients are the smallest there are: usually `|s| < |other| / (2g)` and
+    /// `|t| < |self| / (2g)`.
+    pub fn gcd_ext(&self, other: &Mpz) -> (Mpz, Mpz, Mpz) {
+        let (mut g, mut s, mut t) = (Mpz::new(), Mpz::new(), Mpz::new());
+        unsafe {
+            mpz_gcdext(
+                g.as_raw_mut(),
+                s.as_raw_mut(),
+                t.as_raw_mut(),
+                self.as_raw(),
+                other.as_raw(),
+            )
+        };
+        (g, s, t)
+    }
+
+    /// The least common multiple of `self` and `other`, which is 0 if either is 0.
+    pub fn lcm(&self, other: &Mpz) -> Mpz {
+        let mut l = Mpz::new();
+        unsafe { mpz_lcm(l.as_raw_mut(), self.as_raw(), other.as_raw()) };
+        l
+    }
+
+    /// The least common multiple of `self` and `other`, which is 0 if either is 0.
+    #[allow(clippy::unnecessary_fallible_conversions)]
+    pub fn lcm_u64(&self, other: u64) -> Mpz {
+        match c_ulong::try_from(other) {
+            Ok(u) => {
+                let mut l = Mpz::new();
+                unsafe { mpz_lcm_ui(l.as_raw_mut(), self.as_raw(), u) };
+                l
+            }
+            Err(_) => self.lcm(&Mpz::from(other)),
+        }
+    }
+
+    /// The inverse of `self` modulo `m`, in the range `0..|m|`, or `None` if there is none.
+    ///
+    /// There is no inverse if `self` and `m` have a common factor, or if `m` is 0. Modulo ±1 every
+    /// value is congruent to 0, which is its own inverse.
+    pub fn invert(&self, m: &Mpz) -> Option<Mpz> {
+        if m.is_zero() {
+            return None;
+        }
+        if m.cmp_abs_u64(1) == Ordering::Equal {
+            return Some(Mpz::new());
+        }
+        let mut r = Mpz::new();
+        match unsafe { mpz_invert(r.as_raw_mut(), self.as_raw(), m.as_raw()) } {
+            0 => None,
+            _ => Some(r),
+        }
+    }
+
+    /// The greatest common divisor of all of `values`, or 0 if there are none.
+    ///
+    /// Stops early once the gcd is 1.
+    pub fn gcd_all<I>(values: I) -> Mpz
+    where
+        I: IntoIterator,
+        I::Item: Borrow<Mpz>,
+    {
+        let mut g = Mpz::new();
+        for x in values {
+            unsafe { mpz_gcd(g.as_raw_mut(), g.as_raw(), x.borrow().as_raw()) };
+            if g == 1 {
+                break;
+            }
+        }
+        g
+    }
+
+    /// Divide out every factor `f` from `self`, returning the quotient and the number of times `f`
+    /// divided `self`.
+    ///
+    /// Returns [`MpirError::DivisionByZero`] if `f` is 0. Dividing out ±1, or dividing 0, leaves
+    /// `self` as it is with a count of 0.
+    pub fn remove(&self, f: &Mpz) -> Result<(Mpz, u64), MpirError> {
+        if f.is_zero() {
+            return Err(MpirError::DivisionByZero);
+        }
+        if self.is_zero() || f.cmp_abs_u64(1) == Ordering::Equal {
+            return Ok((self.clone(), 0));
+        }
+        // Older versions of mpz_remove only accept a positive factor.
+        let abs;
+        let positive = if f.is_negative() {
+            abs = -f.clone();
+            &abs
+        } else {
+            f
+        };
+        let mut q = Mpz::new();
+        let count = unsafe { mpz_remove(q.as_raw_mut(), self.as_raw(), positive.as_raw()) };
+        if f.is_negative() && count % 2 == 1 {
+            q = -q;
+        }
+        Ok((q, count as u64))
+    }
+
+    /// The Jacobi symbol `(self/n)`, which is 0, 1 or -1.
+    ///
+    /// `n` must be odd and positive.
+    pub fn jacobi(&self, n: &Mpz) -> Result<i8, MpirError> {
+        check_odd_positive(n)?;
+        Ok(unsafe { mpz_jacobi(self.as_raw(), n.as_raw()) } as i8)
+    }
+
+    /// The Legendre symbol `(self/p)`: 0 if `p` divides `self`, 1 if `self` is a quadratic residue
+    /// modulo `p`, and -1 if it is not.
+    ///
+    /// `p` must be an odd prime. That it is odd and positive is checked, but not that it is prime;
+    /// for other `p` this is the Jacobi symbol.
+    pub fn legendre(&self, p: &Mpz) -> Result<i8, MpirError> {
+        check_odd_positive(p)?;
+        Ok(unsafe { mpz_legendre(self.as_raw(), p.as_raw()) } as i8)
+    }
+
+    /// The Kronecker symbol `(self/n)`, which is 0, 1 or -1.
+    ///
+    /// This extends the Jacobi symbol to all `n`, and is the same when `n` is odd and positive.
+    pub fn kronecker(&self, n: &Mpz) -> i8 {
+        unsafe { mpz_kronecker(self.as_raw(), n.as_raw()) as i8 }
+    }
+
+    /// The Kronecker symbol `(self/n)`.
+    #[allow(clippy::unnecessary_fallible_conversions)]
+    pub fn kronecker_i64(&self, n: i64) -> i8 {
+        match c_long::try_from(n) {
+            Ok(n) => unsafe { mpz_kronecker_si(self.as_raw(), n) as i8 },
+            Err(_) => self.kronecker(&Mpz::from(n)),
+        }
+    }
+
+    /// The Kronecker symbol `(self/n)`.
+    #[allow(clippy::unnecessary_fallible_conversions)]
+    pub fn kronecker_u64(&self, n: u64) -> i8 {
+        match c_ulong::try_from(n) {
+            Ok(n) => unsafe { mpz_kronecker_ui(self.as_raw(), n) as i8 },
+            Err(_) => self.kronecker(&Mpz::from(n)),
+        }
+    }
+
+    /// The Kronecker symbol `(a/n)`.
+    #[allow(clippy::unnecessary_fallible_conversions)]
+    pub fn i64_kronecker(a: i64, n: &Mpz) -> i8 {
+        match c_long::try_from(a) {
+            Ok(a) => unsafe { mpz_si_kronecker(a, n.as_raw()) as i8 },
+            Err(_) => Mpz::from(a).kronecker(n),
+        }
+    }
+
+    /// The Kronecker symbol `(a/n)`.
+    #[allow(clippy::unnecessary_fallible_conversions)]
+    pub fn u64_kronecker(a: u64, n: &Mpz) -> i8 {
+        match c_ulong::try_from(a) {
+            Ok(a) => unsafe { mpz_ui_kronecker(a, n.as_raw()) as i8 },
+            Err(_) => Mpz::from(a).kronecker(n),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn int(v: i64) -> Mpz {
+        Mpz::from(v)
+    }
+
+    #[test]
+    fn gcd_lcm() {
+        assert_eq!(int(-84).gcd(&int(36)), 12);
+        assert_eq!(int(0).gcd(&int(-5)), 5);
+        assert_eq!(int(0).gcd(&int(0)), 0);
+        assert_eq!(int(-84).gcd_u64(18), 6);
+        assert_eq!(int(-84).gcd_u64(0), 84);
+        let big = Mpz::from(u64::MAX) * 6u32;
+        assert_eq!(big.gcd_u64(u64::MAX), u64::MAX);
+
+        assert_eq!(int(-4).lcm(&int(6)), 12);
+        assert_eq!(int(4).lcm(&int(0)), 0);
+        assert_eq!(int(-4).lcm_u64(6), 12);
+        assert_eq!(int(2).lcm_u64(u64::MAX), Mpz::from(u64::MAX) * 2u32);
+    }
+
+    #[test]
+    fn gcd_ext() {
+        for (a, b) in [
+            (240, 46),
+            (-84, 36),
+            (7, 0),
+            (0, -7),
+            (0, 0),
+            (5, 5),
+            (-3, 17),
+        ] {
+            let (g, s, t) = int(a).gcd_ext(&int(b));
+            assert_eq!(g, int(a).gcd(&int(b)));
+            assert_eq!(int(a) * &s + int(b) * &t, g, "gcd_ext({}, {})", a, b);
+        }
+        let (g, s, t) = int(240).gcd_ext(&int(46));
+        assert_eq!((g, s, t), (int(2), int(-9), int(47)));
+    }
+
+    #[test]
+    fn invert() {
+        assert_eq!(int(3).invert(&int(13)), Some(int(9)));
+        assert_eq!(int(-3).invert(&int(13)), Some(int(4)));
+        assert_eq!(int(3).invert(&int(-13)), Some(int(9)));
+        assert_eq!(int(4).invert(&int(8)), None);
+        assert_eq!(int(4).invert(&int(0)), None);
+        assert_eq!(int(4).invert(&int(1)), Some(int(0)));
+        assert_eq!(int(0).invert(&int(-1)), Some(int(0)));
+        let p = (Mpz::from(1) << 127u32) - 1u32;
+        let x = int(123456789).invert(&p).unwrap();
+        assert_eq!((x * 123456789u32) % &p, 1);
+    }
+
+    #[test]
+    fn gcd_all() {
+        assert_eq!(Mpz::gcd_all([int(12), int(-18), int(30)]), 6);
+        assert_eq!(Mpz::gcd_all(&[int(0), int(-7)]), 7);
+        assert_eq!(Mpz::gcd_all(Vec::<Mpz>::new()), 0);
+        let v = [int(4), int(9), int(0)];
+        assert_eq!(Mpz::gcd_all(v.iter()), 1);
+        assert_eq!(Mpz::gcd_all(v.iter().skip(2)), 0);
+    }
+
+    #[test]
+    fn remove() {
+        assert_eq!(int(-720).remove(&int(2)), Ok((int(-45), 4)));
+        assert_eq!(int(720).remove(&int(-3)), Ok((int(80), 2)));
+        assert_eq!(int(720).remove(&int(7)), Ok((int(720), 0)));
+        assert_eq!(int(0).remove(&int(5)), Ok((int(0), 0)));
+        assert_eq!(int(720).remove(&int(-1)), Ok((int(720), 0)));
+        assert_eq!(int(720).remove(&int(0)), Err(MpirError::DivisionByZero));
+        let x = Mpz::from(3).pow(50) * 7u32;
+        assert_eq!(x.remove(&int(9)), Ok((int(7), 25)));
+    }
+
+    #[test]
+    fn jacobi_legendre() {
+        // The quadratic residues modulo 13 are 1, 3, 4, 9, 10 and 12.
+        let p = int(13);
+        let residues: Vec<i8> = (0..13).map(|a| int(a).legendre(&p).unwrap()).collect();
+        assert_eq!(residues, [0, 1, -1, 1, 1, -1, -1, -1, -1, 1, 1, -1, 1]);
+        assert_eq!(int(-1).legendre(&p), Ok(1));
+        assert_eq!(int(2).jacobi(&int(15)), Ok(1));
+        assert_eq!(int(7).jacobi(&int(15)), Ok(-1));
+        assert_eq!(int(5).jacobi(&int(15)), Ok(0));
+        assert_eq!(int(3).jacobi(&int(1)), Ok(1));
+        for n in [0, 2, 16, -15] {
+            assert_eq!(int(2).jacobi(&int(n)), Err(MpirError::InvalidModulus));
+            assert_eq!(int(2).legendre(&int(n)), Err(MpirError::InvalidModulus));
+        }
+    }
+
+    #[test]
+    fn kronecker() {
+        let cases = [
+            (15, -4, 1),
+            (-7, -2, -1),
+            (2, 6, 0),
+            (3, 2, -1),
+            (7, 2, 1),
+            (-1, 0, 1),
+        ];
+        for (a, n, k) in cases {
+            assert_eq!(int(a).kronecker(&int(n)), k, "({}/{})", a, n);
+            assert_eq!(int(a).kronecker_i64(n), k);
+            assert_eq!(Mpz::i64_kronecker(a, &int(n)), k);
+            if n >= 0 {
+                assert_eq!(int(a).kronecker_u64(n as u64), k);
+            }
+            if a >= 0 {
+                assert_eq!(Mpz::u64_kronecker(a as u64, &int(n)), k);
+            }
+        }
+        assert_eq!(int(3).kronecker(&int(15)), int(3).jacobi(&int(15)).unwrap());
+    }
+}
@@ -0,0 +1,636 @@
+//! Operator overloading for [`Mpz`]
+//!
+//! Operators are implemented for owned and borrowed integers, and for primitive integers on either
+//! side. Where an operand is a primitive that fits in an `mpir_ui` / `mpir_si`, the `_ui` / `_si`
+//! variant of the MPIR function is used instead of promoting it to an `Mpz`.
+//!
+//! `&Mpz + x`, `&Mpz - x` and `&Mpz * x` return incomplete values rather than allocating a new
+//! integer, see [`AddIncomplete`]. Every other combination computes its result straight away,
+//! reusing the allocation of an owned operand where there is one.
+//!
+//! Division and remainder truncate towards zero, matching the primitive integer types. Dividing by
+//! zero panics.
+
+use std::ops::{
+    Add, AddAssign, BitAnd, BitAndAssign, BitOr, BitOrAssign, BitXor, BitXorAssign, Div, DivAssign,
+    Mul, MulAssign, Neg, Not, Rem, RemAssign, Shl, ShlAssign, Shr, ShrAssign, Sub, SubAssign,
+};
+
+use super::*;
+use crate::ctype::{c_long, c_ulong, mp_bitcnt_t, mpz_ptr, mpz_srcptr};
+
+/// A primitive operand, narrowed to the cheapest form MPIR accepts.
+pub(crate) enum Small {
+    /// Non-negative value that fits in an `mpir_ui`.
+    Pos(c_ulong),
+    /// Negative value whose magnitude fits in an `mpir_ui`.
+    Neg(c_ulong),
+    /// Anything else, promoted to an `Mpz`.
+    Big(Mpz),
+}
+
+impl Small {
+    /// Promote the operand to an `Mpz`.
+    pub(crate) fn into_mpz(self) -> Mpz {
+        match self {
+            Small::Pos(u) => unsafe { Mpz::from_init(|x| mpz_init_set_ui(x, u)) },
+            Small::Neg(u) => unsafe {
+                Mpz::from_init(|x| {
+                    mpz_init_set_ui(x, u);
+                    mpz_neg(x, x);
+                })
+            },
+            Small::Big(z) => z,
+        }
+    }
+
+    /// Call `f` with the operand as an `mpz_srcptr`, promoting it to a temporary if needed.
+    pub(crate) fn with_raw<R, F: FnOnce(mpz_srcptr) -> R>(&self, f: F) -> R {
+        match self {
+            Small::Pos(u) => f(Small::Pos(*u).into_mpz().as_raw()),
+            Small::Neg(u) => f(Small::Neg(*u).into_mpz().as_raw()),
+            Small::Big(z) => f(z.as_raw()),
+        }
+    }
+}
+
+/// Primitive integer types that can be used as operands alongside `Mpz`.
+pub(crate) trait Primitive: Copy {
+    fn small(self) -> Small;
+}
+
+/// Build an `Mpz` from a `u128`, one `mpir_ui` chunk at a time from the most significant end.
+pub(crate) fn mpz_from_u128(v: u128) -> Mpz {
+    let mut z = Mpz::new();
+    let p = z.as_raw_mut();
+    let mut shift = u128::BITS;
+    while shift > 0 {
+        shift -= c_ulong::BITS;
+        unsafe {
+            mpz_mul_2exp(p, p, c_ulong::BITS as mp_bitcnt_t);
+            mpz_add_ui(p, p, (v >> shift) as c_ulong);
+        }
+    }
+    z
+}
+
+macro_rules! primitive_unsigned {
+    ($($t:ty)*) => {$(
+        impl Primitive for $t {
+            #[allow(clippy::unnecessary_fallible_conversions)]
+            fn small(self) -> Small {
+                match c_ulong::try_from(self) {
+                    Ok(u) => Small::Pos(u),
+                    Err(_) => Small::Big(mpz_from_u128(self as u128)),
+                }
+            }
+        }
+    )*};
+}
+
+macro_rules! primitive_signed {
+    ($($t:ty)*) => {$(
+        impl Primitive for $t {
+            #[allow(clippy::unnecessary_fallible_conversions)]
+            fn small(self) -> Small {
+                let abs = self.unsigned_abs();
+                match (self < 0, c_ulong::try_from(abs)) {
+                    (false, Ok(u)) => Small::Pos(u),
+                    (true, Ok(u)) => Small::Neg(u),
+                    (false, Err(_)) => Small::Big(mpz_from_u128(abs as u128)),
+                    (true, Err(_)) => Small::Big(-mpz_from_u128(abs as u128)),
+                }
+            }
+        }
+    )*};
+}
+
+primitive_unsigned! { u8 u16 u32 u64 u128 usize }
+primitive_signed! { i8 i16 i32 i64 i128 isize }
+
+// -------------------------------------------------------------------------------------------------
+// Raw operations
+//
+// Each of these writes its result to `rop`, which may alias any mpz operand.
+
+pub(super) fn check_divisor(d: mpz_srcptr) {
+    if unsafe { (*d)._mp_size } == 0 {
+        panic!("attempt to divide by zero");
+    }
+}
+
+unsafe fn div(rop: mpz_ptr, n: mpz_srcptr, d: mpz_srcptr) {
+    check_divisor(d);
+    mpz_tdiv_q(rop, n, d);
+}
+
+unsafe fn rem(rop: mpz_ptr, n: mpz_srcptr, d: mpz_srcptr) {
+    check_divisor(d);
+    mpz_tdiv_r(rop, n, d);
+}
+
+pub(super) unsafe fn add_small(rop: mpz_ptr, op1: mpz_srcptr, op2: &Small) {
+    match op2 {
+        Small::Pos(u) => mpz_add_ui(rop, op1, *u),
+        Small::Neg(u) => mpz_sub_ui(rop, op1, *u),
+        Small::Big(z) => mpz_add(rop, op1, z.as_raw()),
+    }
+}
+
+pub(super) unsafe fn small_add(rop: mpz_ptr, op1: &Small, op2: mpz_srcptr) {
+    add_small(rop, op2, op1)
+}
+
+pub(super) unsafe fn sub_small(rop: mpz_ptr, op1: mpz_srcptr, op2: &Small) {
+    match op2 {
+        Small::Pos(u) => mpz_sub_ui(rop, op1, *u),
+        Small::Neg(u) => mpz_add_ui(rop, op1, *u),
+        Small::Big(z) => mpz_sub(rop, op1, z.as_raw()),
+    }
+}
+
+pub(super) unsafe fn small_sub(rop: mpz_ptr, op1: &Small, op2: mpz_srcptr) {
+    match op1 {
+        Small::Pos(u) => mpz_ui_sub(rop, *u, op2),
+        Small::Neg(u) => {
+            mpz_add_ui(rop, op2, *u);
+            mpz_neg(rop, rop);
+        }
+        Small::Big(z) => mpz_sub(rop, z.as_raw(), op2),
+    }
+}
+
+pub(super) unsafe fn mul_small(rop: mpz_ptr, op1: mpz_srcptr, op2: &Small) {
+    match op2 {
+        Small::Pos(u) => mpz_mul_ui(rop, op1, *u),
+        Small::Neg(u) if *u <= c_long::MIN.unsigned_abs() as c_ulong => {
+            mpz_mul_si(rop, op1, (*u as c_long).wrapping_neg())
+        }
+        Small::Neg(u) => {
+            mpz_mul_ui(rop, op1, *u);
+            mpz_neg(rop, rop);
+        }
+        Small::Big(z) => mpz_mul(rop, op1, z.as_raw()),
+    }
+}
+
+unsafe fn small_mul(rop: mpz_ptr, op1: &Small, op2: mpz_srcptr) {
+    mul_small(rop, op2, op1)
+}
+
+unsafe fn div_small(rop: mpz_ptr, op1: mpz_srcptr, op2: &Small) {
+    match op2 {
+        Small::Pos(0) => panic!("attempt to divide by zero"),
+        Small::Pos(u) => {
+            mpz_tdiv_q_ui(rop, op1, *u);
+        }
+        Small::Neg(u) => {
+            mpz_tdiv_q_ui(rop, op1, *u);
+            mpz_neg(rop, rop);
+        }
+        Small::Big(z) => div(rop, op1, z.as_raw()),
+    }
+}
+
+unsafe fn rem_small(rop: mpz_ptr, op1: mpz_srcptr, op2: &Small) {
+    match op2 {
+        Small::Pos(0) => panic!("attempt to calculate the remainder with a divisor of zero"),
+        Small::Pos(u) | Small::Neg(u) => {
+            mpz_tdiv_r_ui(rop, op1, *u);
+        }
+        Small::Big(z) => rem(rop, op1, z.as_raw()),
+    }
+}
+
+unsafe fn small_div(rop: mpz_ptr, op1: &Small, op2: mpz_srcptr) {
+    op1.with_raw(|z| div(rop, z, op2))
+}
+
+unsafe fn small_rem(rop: mpz_ptr, op1: &Small, op2: mpz_srcptr) {
+    op1.with_raw(|z| rem(rop, z, op2))
+}
+
+// There are no mixed-type logical functions, so these always promote the primitive.
+
+unsafe fn and_small(rop: mpz_ptr, op1: mpz_srcptr, op2: &Small) {
+    op2.with_raw(|z| mpz_and(rop, op1, z))
+}
+
+unsafe fn small_and(rop: mpz_ptr, op1: &Small, op2: mpz_srcptr) {
+    and_small(rop, op2, op1)
+}
+
+unsafe fn ior_small(rop: mpz_ptr, op1: mpz_srcptr, op2: &Small) {
+    op2.with_raw(|z| mpz_ior(rop, op1, z))
+}
+
+unsafe fn small_ior(rop: mpz_ptr, op1: &Small, op2: mpz_srcptr) {
+    ior_small(rop, op2, op1)
+}
+
+unsafe fn xor_small(rop: mpz_ptr, op1: mpz_srcptr, op2: &Small) {
+    op2.with_raw(|z| mpz_xor(rop, op1, z))
+}
+
+unsafe fn small_xor(rop: mpz_ptr, op1: &Small, op2: mpz_srcptr) {
+    xor_small(rop, op2, op1)
+}
+
+// -------------------------------------------------------------------------------------------------
+// Mpz op Mpz
+
+macro_rules! arith_mpz {
+    ($Op:ident $op:ident $OpAssign:ident $op_assign:ident $func:ident) => {
+        impl $Op<Mpz> for Mpz {
+            type Output = Mpz;
+            fn $op(mut self, rhs: Mpz) -> Mpz {
+                self.$op_assign(&rhs);
+                self
+            }
+        }
+
+        impl $Op<&Mpz> for Mpz {
+            type Output = Mpz;
+            fn $op(mut self, rhs: &Mpz) -> Mpz {
+                self.$op_assign(rhs);
+                self
+            }
+        }
+
+        impl $Op<Mpz> for &Mpz {
+            type Output = Mpz;
+            fn $op(self, mut rhs: Mpz) -> Mpz {
+                let p = rhs.as_raw_mut();
+                unsafe { $func(p, self.as_raw(), p) };
+                rhs
+            }
+        }
+
+        impl $OpAssign<Mpz> for Mpz {
+            fn $op_assign(&mut self, rhs: Mpz) {
+                self.$op_assign(&rhs);
+            }
+        }
+
+        impl $OpAssign<&Mpz> for Mpz {
+            fn $op_assign(&mut self, rhs: &Mpz) {
+                let p = self.as_raw_mut();
+                unsafe { $func(p, p, rhs.as_raw()) };
+            }
+        }
+    };
+}
+
+/// `&Mpz op &Mpz` for the operators that are evaluated eagerly.
+///
+/// `+`, `-` and `*` instead return an incomplete value, see [`AddIncomplete`].
+macro_rules! arith_mpz_ref {
+    ($Op:ident $op:ident $func:ident) => {
+        impl $Op<&Mpz> for &Mpz {
+            type Output = Mpz;
+            fn $op(self, rhs: &Mpz) -> Mpz {
+                let mut r = Mpz::new();
+                unsafe { $func(r.as_raw_mut(), self.as_raw(), rhs.as_raw()) };
+                r
+            }
+        }
+    };
+}
+
+arith_mpz! { Add add AddAssign add_assign mpz_add }
+arith_mpz! { Sub sub SubAssign sub_assign mpz_sub }
+arith_mpz! { Mul mul MulAssign mul_assign mpz_mul }
+arith_mpz! { Div div DivAssign div_assign div }
+arith_mpz! { Rem rem RemAssign rem_assign rem }
+arith_mpz! { BitAnd bitand BitAndAssign bitand_assign mpz_and }
+arith_mpz! { BitOr bitor BitOrAssign bitor_assign mpz_ior }
+arith_mpz! { BitXor bitxor BitXorAssign bitxor_assign mpz_xor }
+
+arith_mpz_ref! { Div div div }
+arith_mpz_ref! { Rem rem rem }
+arith_mpz_ref! { BitAnd bitand mpz_and }
+arith_mpz_ref! { BitOr bitor mpz_ior }
+arith_mpz_ref! { BitXor bitxor mpz_xor }
+
+// -------------------------------------------------------------------------------------------------
+// Mpz op primitive, primitive op Mpz
+
+macro_rules! arith_prim {
+    ($Op:ident $op:ident $OpAssign:ident $op_assign:ident $fwd:ident $rev:ident; $($t:ty)*) => {$(
+        impl $Op<$t> for Mpz {
+            type Output = Mpz;
+            fn $op(mut self, rhs: $t) -> Mpz {
+                self.$op_assign(rhs);
+                self
+            }
+        }
+
+        impl $Op<Mpz> for $t {
+            type Output = Mpz;
+            fn $op(self, mut rhs: Mpz) -> Mpz {
+                let p = rhs.as_raw_mut();
+                unsafe { $rev(p, &self.small(), p) };
+                rhs
+            }
+        }
+
+        impl $OpAssign<$t> for Mpz {
+            fn $op_assign(&mut self, rhs: $t) {
+                let p = self.as_raw_mut();
+                unsafe { $fwd(p, p, &rhs.small()) };
+            }
+        }
+    )*};
+}
+
+/// `&Mpz op primitive` and `primitive op &Mpz` for the operators that are evaluated eagerly.
+macro_rules! arith_prim_ref {
+    ($Op:ident $op:ident $fwd:ident $rev:ident; $($t:ty)*) => {$(
+        impl $Op<$t> for &Mpz {
+            type Output = Mpz;
+            fn $op(self, rhs: $t) -> Mpz {
+                let mut r = Mpz::new();
+                unsafe { $fwd(r.as_raw_mut(), self.as_raw(), &rhs.small()) };
+                r
+            }
+        }
+
+        impl $Op<&Mpz> for $t {
+            type Output = Mpz;
+            fn $op(self, rhs: &Mpz) -> Mpz {
+                let mut r = Mpz::new();
+                unsafe { $rev(r.as_raw_mut(), &self.small(), rhs.as_raw()) };
+                r
+            }
+        }
+    )*};
+}
+
+macro_rules! arith_prims {
+    ($($Op:ident $op:ident $OpAssign:ident $op_assign:ident $fwd:ident $rev:ident;)*) => {$(
+        arith_prim! {
+            $Op $op $OpAssign $op_assign $fwd $rev;
+            u8 u16 u32 u64 u128 usize i8 i16 i32 i64 i128 isize
+        }
+    )*};
+}
+
+macro_rules! arith_prims_ref {
+    ($($Op:ident $op:ident $fwd:ident $rev:ident;)*) => {$(
+        arith_prim_ref! {
+            $Op $op $fwd $rev;
+            u8 u16 u32 u64 u128 usize i8 i16 i32 i64 i128 isize
+        }
+    )*};
+}
+
+arith_prims! {
+    Add add AddAssign add_assign add_small small_add;
+    Sub sub SubAssign sub_assign sub_small small_sub;
+    Mul mul MulAssign mul_assign mul_small small_mul;
+    Div div DivAssign div_assign div_small small_div;
+    Rem rem RemAssign rem_assign rem_small small_rem;
+    BitAnd bitand BitAndAssign bitand_assign and_small small_and;
+    BitOr bitor BitOrAssign bitor_assign ior_small small_ior;
+    BitXor bitxor BitXorAssign bitxor_assign xor_small small_xor;
+}
+
+arith_prims_ref! {
+    Div div div_small small_div;
+    Rem rem rem_small small_rem;
+    BitAnd bitand and_small small_and;
+    BitOr bitor ior_small small_ior;
+    BitXor bitxor xor_small small_xor;
+}
+
+// -------------------------------------------------------------------------------------------------
+// Unary operators
+
+macro_rules! unary {
+    ($Op:ident $op:ident $func:ident) => {
+        impl $Op for Mpz {
+            type Output = Mpz;
+            fn $op(mut self) -> Mpz {
+                let p = self.as_raw_mut();
+                unsafe { $func(p, p) };
+                self
+            }
+        }
+
+        impl $Op for &Mpz {
+            type Output = Mpz;
+            fn $op(self) -> Mpz {
+                let mut r = Mpz::new();
+                unsafe { $func(r.as_raw_mut(), self.as_raw()) };
+                r
+            }
+        }
+    };
+}
+
+unary! { Neg neg mpz_neg }
+unary! { Not not mpz_com }
+
+// -------------------------------------------------------------------------------------------------
+// Shifts
+//
+// `<<` multiplies by a power of two and `>>` floor-divides by one, so right shifts of negative
+// values round towards −∞ like the primitive signed types. A negative shift amount shifts the
+// other way.
+
+unsafe fn shl(rop: mpz_ptr, op: mpz_srcptr, bits: mp_bitcnt_t) {
+    mpz_mul_2exp(rop, op, bits)
+}
+
+unsafe fn shr(rop: mpz_ptr, op: mpz_srcptr, bits: mp_bitcnt_t) {
+    mpz_fdiv_q_2exp(rop, op, bits)
+}
+
+/// Split a shift amount into its direction (true if reversed) and magnitude.
+trait ShiftAmount: Copy {
+    fn split(self) -> (bool, mp_bitcnt_t);
+}
+
+macro_rules! shift_amount {
+    ($($t:ty)*) => {$(
+        impl ShiftAmount for $t {
+            fn split(self) -> (bool, mp_bitcnt_t) {
+                let bits = mp_bitcnt_t::try_from(self.unsigned_abs()).expect("shift amount too large");
+                (self < 0, bits)
+            }
+        }
+    )*};
+}
+
+shift_amount! { i8 i16 i32 i64 isize }
+
+macro_rules! shift_amount_unsigned {
+    ($($t:ty)*) => {$(
+        impl ShiftAmount for $t {
+            #[allow(clippy::unnecessary_fallible_conversions)]
+            fn split(self) -> (bool, mp_bitcnt_t) {
+                (false, mp_bitcnt_t::try_from(self).expect("shift amount too large"))
+            }
+        }
+    )*};
+}
+
+shift_amount_unsigned! { u8 u16 u32 u64 usize }
+
+macro_rules! shift {
+    ($Op:ident $op:ident $OpAssign:ident $op_assign:ident $fwd:ident $rev:ident; $($t:ty)*) => {$(
+        impl $Op<$t> for Mpz {
+            type Output = Mpz;
+            fn $op(mut self, rhs: $t) -> Mpz {
+                self.$op_assign(rhs);
+                self
+            }
+        }
+
+        impl $Op<$t> for &Mpz {
+            type Output = Mpz;
+            fn $op(self, rhs: $t) -> Mpz {
+                let mut r = Mpz::new();
+                let (reversed, bits) = rhs.split();
+                let f = if reversed { $rev } else { $fwd };
+                unsafe { f(r.as_raw_mut(), self.as_raw(), bits) };
+                r
+            }
+        }
+
+        impl $OpAssign<$t> for Mpz {
+            fn $op_assign(&mut self, rhs: $t) {
+                let p = self.as_raw_mut();
+                let (reversed, bits) = rhs.split();
+                let f = if reversed { $rev } else { $fwd };
+                unsafe { f(p, p, bits) };
+            }
+        }
+    )*};
+}
+
+shift! { Shl shl ShlAssign shl_assign shl shr; u8 u16 u32 u64 usize i8 i16 i32 i64 isize }
+shift! { Shr shr ShrAssign shr_assign shr shl; u8 u16 u32 u64 usize i8 i16 i32 i64 isize }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn int(v: i64) -> Mpz {
+        Mpz::new() + v
+    }
+
+    fn is(x: &Mpz, v: i64) -> bool {
+        unsafe { mpz_cmp_si(x.as_raw(), v) == 0 }
+    }
+
+    #[test]
+    fn mpz_mpz() {
+        let a = int(20);
+        let b = int(-6);
+        assert!(is(&Mpz::from(&a + &b), 14));
+        assert!(is(&Mpz::from(&a - &b), 26));
+        assert!(is(&Mpz::from(&a * &b), -120));
+        assert!(is(&(&a / &b), -3));
+        assert!(is(&(&a % &b), 2));
+        assert!(is(&(&a & &b), 16));
+        assert!(is(&(&a | &b), -2));
+        assert!(is(&(&a ^ &b), -18));
+        assert!(is(&(a.clone() - b.clone()), 26));
+        assert!(is(&(&a - b.clone()), 26));
+        assert!(is(&(a.clone() - &b), 26));
+    }
+
+    #[test]
+    fn mpz_primitive() {
+        let a = int(-7);
+        assert!(is(&Mpz::from(&a + 10u8), 3));
+        assert!(is(&Mpz::from(&a + -10i32), -17));
+        assert!(is(&Mpz::from(&a - 3u64), -10));
+        assert!(is(&Mpz::from(&a - -3i64), -4));
+        assert!(is(&Mpz::from(&a * -3i16), 21));
+        assert!(is(&(Mpz::from(&a * i64::MIN) / i64::MIN), -7));
+        assert!(is(&(&a / 2u32), -3));
+        assert!(is(&(&a / -2i32), 3));
+        assert!(is(&(&a % 2u32), -1));
+        assert!(is(&(&a % -2i32), -1));
+        assert!(is(&(&a & 0xffu16), 0xf9));
+        assert!(is(&(&a | 1i8), -7));
+        assert!(is(&(&a ^ -1i64), 6));
+    }
+
+    #[test]
+    fn primitive_mpz() {
+        let a = int(-7);
+        assert!(is(&Mpz::from(10u8 + &a), 3));
+        assert!(is(&Mpz::from(3u64 - &a), 10));
+        assert!(is(&Mpz::from(-3i64 - &a), 4));
+        assert!(is(&Mpz::from(-3i16 * &a), 21));
+        assert!(is(&(100u32 / &a), -14));
+        assert!(is(&(100i32 % a.clone()), 2));
+        assert!(is(&(0xffu16 & a), 0xf9));
+    }
+
+    #[test]
+    fn wide_primitive() {
+        let big = u128::MAX;
+        let a = Mpz::new() + big;
+        let b = a - big;
+        assert!(is(&b, 0));
+        let c = (Mpz::new() + i128::MIN) - i128::MIN;
+        assert!(is(&c, 0));
+        let d = (Mpz::new() + i128::MIN) / (i128::MIN / 2);
+        assert!(is(&d, 2));
+        let f = (Mpz::new() + u64::MAX) * u64::MAX - (u64::MAX as u128 * u64::MAX as u128);
+        assert!(is(&f, 0));
+    }
+
+    #[test]
+    fn assign() {
+        let mut a = int(5);
+        a += 3u8;
+        a -= int(10);
+        a *= &int(-4);
+        a /= 3i32;
+        a %= 2u64;
+        assert!(is(&a, 0));
+        let mut b = int(0b1100);
+        b &= 0b1010u8;
+        b |= 1i8;
+        b ^= int(0b1111);
+        assert!(is(&b, 0b0110));
+    }
+
+    #[test]
+    fn unary() {
+        let a = int(42);
+        assert!(is(&-&a, -42));
+        assert!(is(&!&a, -43));
+        assert!(is(&-a, -42));
+    }
+
+    #[test]
+    fn shifts() {
+        let a = int(-5);
+        assert!(is(&(&a << 3u32), -40));
+        assert!(is(&(&a >> 1u8), -3));
+        assert!(is(&(&a << -1i32), -3));
+        assert!(is(&(&a >> -2i64), -20));
+        let mut b = int(1);
+        b <<= 70usize;
+        b >>= 69u64;
+        assert!(is(&b, 2));
+    }
+
+    #[test]
+    #[should_panic(expected = "divide by zero")]
+    fn div_zero() {
+        let _ = int(1) / int(0);
+    }
+
+    #[test]
+    #[should_panic(expected = "divisor of zero")]
+    fn rem_zero() {
+        let _ = int(1) % 0u8;
+    }
+}
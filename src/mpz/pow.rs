@@ -0,0 +1,117 @@
+//! Exponentiation for [`Mpz`]
+//!
+//! MPIR divides by zero, raising `SIGFPE`, when the modulus is zero or a negative exponent has no
+//! inverse to use, so these are checked first and returned as errors.
+
+use std::cmp::Ordering;
+
+use super::*;
+use crate::MpirError;
+
+impl Mpz {
+    /// Raise to the power `exp`.
+    pub fn pow(&self, exp: u32) -> Mpz {
+        let mut r = Mpz::new();
+        unsafe { mpz_pow_ui(r.as_raw_mut(), self.as_raw(), exp as c_ulong) };
+        r
+    }
+
+    /// Raise to the power `exp`, modulo `m`. The result is in the range `0..|m|`.
+    ///
+    /// A negative `exp` is allowed if `self` has an inverse modulo `m`, and returns
+    /// [`MpirError::NotInvertible`] otherwise. Modulo ±1 the result is always 0, as for
+    /// [`Mpz::invert`].
+    pub fn pow_mod(&self, exp: &Mpz, m: &Mpz) -> Result<Mpz, MpirError> {
+        if m.is_zero() {
+            return Err(MpirError::DivisionByZero);
+        }
+        if m.cmp_abs_u64(1) == Ordering::Equal {
+            return Ok(Mpz::new());
+        }
+        let mut r = Mpz::new();
+        unsafe {
+            if exp.is_negative() && mpz_invert(r.as_raw_mut(), self.as_raw(), m.as_raw()) == 0 {
+                return Err(MpirError::NotInvertible);
+            }
+            mpz_powm(r.as_raw_mut(), self.as_raw(), exp.as_raw(), m.as_raw());
+        }
+        Ok(r)
+    }
+
+    /// Raise to the power `exp`, modulo `m`. The result is in the range `0..|m|`, so modulo ±1 it
+    /// is always 0, even for `exp` 0.
+    #[allow(clippy::unnecessary_fallible_conversions)]
+    pub fn pow_mod_u64(&self, exp: u64, m: &Mpz) -> Result<Mpz, MpirError> {
+        if m.is_zero() {
+            return Err(MpirError::DivisionByZero);
+        }
+        if m.cmp_abs_u64(1) == Ordering::Equal {
+            return Ok(Mpz::new());
+        }
+        match c_ulong::try_from(exp) {
+            Ok(e) => {
+                let mut r = Mpz::new();
+                unsafe { mpz_powm_ui(r.as_raw_mut(), self.as_raw(), e, m.as_raw()) };
+                Ok(r)
+            }
+            Err(_) => self.pow_mod(&Mpz::from(exp), m),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn pow() {
+        assert_eq!(Mpz::from(-2).pow(5), -32);
+        assert_eq!(Mpz::from(3).pow(0), 1);
+        assert_eq!(Mpz::from(2).pow(100), Mpz::from(1) << 100u32);
+    }
+
+    #[test]
+    fn pow_mod() {
+        let m = Mpz::from(13);
+        assert_eq!(Mpz::from(3).pow_mod(&Mpz::from(4), &m), Ok(Mpz::from(3)));
+        assert_eq!(Mpz::from(-3).pow_mod(&Mpz::from(3), &m), Ok(Mpz::from(12)));
+        // 3 * 9 = 27 = 1 mod 13
+        assert_eq!(Mpz::from(3).pow_mod(&Mpz::from(-1), &m), Ok(Mpz::from(9)));
+        assert_eq!(
+            Mpz::from(3).pow_mod_u64(u64::MAX, &m),
+            Ok(Mpz::from(3).pow_mod(&Mpz::from(u64::MAX), &m).unwrap())
+        );
+        assert_eq!(
+            Mpz::from(2).pow_mod_u64(10, &Mpz::from(1000)),
+            Ok(Mpz::from(24))
+        );
+    }
+
+    #[test]
+    fn pow_mod_errors() {
+        let zero = Mpz::new();
+        assert_eq!(
+            Mpz::from(3).pow_mod(&Mpz::from(2), &zero),
+            Err(MpirError::DivisionByZero)
+        );
+        assert_eq!(
+            Mpz::from(3).pow_mod_u64(2, &zero),
+            Err(MpirError::DivisionByZero)
+        );
+        let r = Mpz::from(4).pow_mod(&Mpz::from(-1), &Mpz::from(8));
+        assert_eq!(r, Err(MpirError::NotInvertible));
+    }
+
+    #[test]
+    fn pow_mod_one() {
+        for m in [Mpz::from(1), Mpz::from(-1)] {
+            assert_eq!(Mpz::from(4).pow_mod(&Mpz::from(-3), &m), Ok(Mpz::new()));
+            assert_eq!(Mpz::new().pow_mod(&Mpz::from(-1), &m), Ok(Mpz::new()));
+            assert_eq!(Mpz::from(4).pow_mod(&Mpz::from(3), &m), Ok(Mpz::new()));
+            assert_eq!(Mpz::from(4).pow_mod_u64(3, &m), Ok(Mpz::new()));
+            assert_eq!(Mpz::from(4).pow_mod(&Mpz::new(), &m), Ok(Mpz::new()));
+            assert_eq!(Mpz::from(4).pow_mod_u64(0, &m), Ok(Mpz::new()));
+        }
+        assert_eq!(Mpz::new().pow_mod_u64(0, &Mpz::from(7)), Ok(Mpz::from(1)));
+    }
+}
@@ -0,0 +1,348 @@
+//! Primality testing and prime generation for [`Mpz`]
+//!
+//! Below 2^64, primality is decided by the Baillie-PSW test, which no composite in that range
+//! passes, so [`Mpz::is_probably_prime`], [`Mpz::next_prime`] and [`Mpz::prev_prime`] are exact
+//! there. Above, the Baillie-PSW test is followed by rounds of Miller-Rabin with random bases. The
+//! bases come from a generator seeded once per process from the standard library's random hash
+//! keys, so they cannot be predicted, and a composite cannot be built to pass them.
+//!
+//! MPIR finds larger primes with `mpz_next_prime_candidate`, which can return composites, so every
+//! candidate is tested again before it is returned. GMP has no such function and uses
+//! `mpz_nextprime` instead.
+
+use std::cmp::Ordering;
+use std::collections::hash_map::RandomState;
+use std::hash::BuildHasher;
+use std::iter::FusedIterator;
+use std::sync::{Mutex, OnceLock, PoisonError};
+
+use super::fib::lucas_mod;
+use super::*;
+use crate::rand::RandState;
+
+/// Rounds of Miller-Rabin for testing primes above 2^64 found by [`Mpz::next_prime`] and
+/// [`Mpz::prev_prime`].
+const REPS: u32 = 25;
+
+/// Primes to trial divide by before the Baillie-PSW test.
+const SMALL_PRIMES: [u32; 15] = [2, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37, 41, 43, 47];
+
+impl Mpz {
+    /// Return `true` if the integer is probably prime, and `false` if it is certainly composite.
+    ///
+    /// Below 2^64 the answer is exact. Above, a composite that passes the Baillie-PSW test is then
+    /// called prime with a probability of at most 4^-`reps`; 25 is a common choice. Integers less
+    /// than 2 are not prime.
+    pub fn is_probably_prime(&self, reps: u32) -> bool {
+        if *self < 2 {
+            false
+        } else if *self <= u64::MAX {
+            self.is_prime_bpsw()
+        } else {
+            probable_prime(self, reps)
+        }
+    }
+
+    /// The Baillie-PSW probable prime test: a strong Fermat test to base 2 and a strong Lucas test.
+    ///
+    /// This is deterministic. It is exact for all integers below 2^64, and although composites
+    /// above are expected to pass, none is known.
+    pub fn is_prime_bpsw(&self) -> bool {
+        if *self < 2 {
+            return false;
+        }
+        for p in SMALL_PRIMES {
+            if unsafe { mpz_divisible_ui_p(self.as_raw(), p as c_ulong) } != 0 {
+                return *self == p;
+            }
+        }
+        strong_fermat(self, &Mpz::from(2)) && strong_lucas(self)
+    }
+
+    /// The smallest prime greater than `self`.
+    pub fn next_prime(&self) -> Mpz {
+        if *self < 2 {
+            return Mpz::from(2);
+        }
+        if *self >= u64::MAX {
+            let mut p = next_candidate(self);
+            while !p.is_probably_prime(REPS) {
+                p = next_candidate(&p);
+            }
+            return p;
+        }
+        let mut p = Mpz::from(self + 1u32);
+        if unsafe { mpz_even_p(p.as_raw()) } != 0 {
+            p += 1u32;
+        }
+        while !p.is_probably_prime(REPS) {
+            p += 2u32;
+        }
+        p
+    }
+
+    /// The largest prime less than `self`, or `None` if `self` is 2 or less.
+    pub fn prev_prime(&self) -> Option<Mpz> {
+        if *self <= 2 {
+            return None;
+        }
+        if *self == 3 {
+            return Some(Mpz::from(2));
+        }
+        let mut p = Mpz::from(self - 1u32);
+        if unsafe { mpz_even_p(p.as_raw()) } != 0 {
+            p -= 1u32;
+        }
+        while !p.is_probably_prime(REPS) {
+            p -= 2u32;
+        }
+        Some(p)
+    }
+}
+
+/// Call `f` with the random state for this module, which is seeded on first use.
+fn with_state<R>(f: impl FnOnce(&mut RandState) -> R) -> R {
+    static STATE: OnceLock<Mutex<RandState>> = OnceLock::new();
+    let state = STATE.get_or_init(|| {
+        // Each RandomState has new keys, which the first on each thread takes from the OS.
+        let keys = RandomState::new();
+        let mut seed = Mpz::new();
+        for i in 0..4u8 {
+            seed <<= 64u32;
+            seed += keys.hash_one(i);
+        }
+        let mut state = RandState::new();
+        state.seed(&seed);
+        Mutex::new(state)
+    });
+    f(&mut state.lock().unwrap_or_else(PoisonError::into_inner))
+}
+
+/// Test `n > 2^64` with the Baillie-PSW test and `reps` rounds of Miller-Rabin.
+fn probable_prime(n: &Mpz, reps: u32) -> bool {
+    if !n.is_prime_bpsw() {
+        return false;
+    }
+    // Bases in 2..n-1.
+    let range = Mpz::from(n - 3u32);
+    (0..reps).all(|_| {
+        let base = with_state(|state| Mpz::random_below(state, &range).unwrap()) + 2u32;
+        strong_fermat(n, &base)
+    })
+}
+
+/// A number greater than `n` with no small factors, and no primes between them.
+#[cfg(not(feature = "backend-gmp"))]
+fn next_candidate(n: &Mpz) -> Mpz {
+    let mut r = Mpz::new();
+    with_state(|state| unsafe {
+        mpz_next_prime_candidate(r.as_raw_mut(), n.as_raw(), state.as_raw_mut())
+    });
+    r
+}
+
+/// A number greater than `n` with no small factors, and no primes between them.
+#[cfg(feature = "backend-gmp")]
+fn next_candidate(n: &Mpz) -> Mpz {
+    let mut r = Mpz::new();
+    unsafe { mpz_nextprime(r.as_raw_mut(), n.as_raw()) };
+    r
+}
+
+/// Split `n` into `(d, s)` with `n = d * 2^s` and `d` odd. `n` must be positive.
+fn split_pow2(n: &Mpz) -> (Mpz, mp_bitcnt_t) {
+    let s = unsafe { mpz_scan1(n.as_raw(), 0) };
+    (n >> s, s)
+}
+
+/// The strong Fermat, or Miller-Rabin, test to `base`, for odd `n`.
+fn strong_fermat(n: &Mpz, base: &Mpz) -> bool {
+    let n1 = Mpz::from(n - 1u32);
+    let (d, s) = split_pow2(&n1);
+    let mut x = base.pow_mod(&d, n).unwrap();
+    if x == 1 || x == n1 {
+        return true;
+    }
+    for _ in 1..s {
+        x = Mpz::from(&x * &x) % n;
+        if x == n1 {
+            return true;
+        }
+    }
+    false
+}
+
+/// The strong Lucas test with the parameters of Selfridge's method A, for odd `n` with no factor
+/// below 50.
+fn strong_lucas(n: &Mpz) -> bool {
+    // No D would be found for a square.
+    if unsafe { mpz_perfect_square_p(n.as_raw()) } != 0 {
+        return false;
+    }
+    // The first D in 5, -7, 9, -11, ... with (D/n) = -1.
+    let mut d = 5i64;
+    loop {
+        match Mpz::from(d).jacobi(n).unwrap() {
+            -1 => break,
+            0 if n.cmp_abs_u64(d.unsigned_abs()) != Ordering::Equal => return false,
+            _ => d = if d > 0 { -d - 2 } else { -d + 2 },
+        }
+    }
+    let (k, s) = split_pow2(&Mpz::from(n + 1u32));
+    let q = Mpz::from((1 - d) / 4);
+    let (u, mut v, mut qk) = lucas_mod(&Mpz::from(1), &q, &k, n);
+    if u.is_zero() || v.is_zero() {
+        return true;
+    }
+    for _ in 1..s {
+        v = Mpz::from(&v * &v);
+        v -= &qk * 2u32;
+        v = v.rem_euclid(n);
+        if v.is_zero() {
+            return true;
+        }
+        qk = Mpz::from(&qk * &qk) % n;
+    }
+    false
+}
+
+/// An endless iterator over the primes from a starting value, in increasing order.
+///
+/// Primes below 2^64 are exact, larger ones are tested with 25 rounds of Miller-Rabin.
+#[derive(Clone, Debug)]
+pub struct PrimeIter {
+    from: Mpz,
+}
+
+impl PrimeIter {
+    /// Iterate over the primes greater than or equal to `start`.
+    pub fn new(start: Mpz) -> Self {
+        PrimeIter { from: start }
+    }
+}
+
+impl Iterator for PrimeIter {
+    type Item = Mpz;
+
+    fn next(&mut self) -> Option<Mpz> {
+        let p = match self.from.is_probably_prime(REPS) {
+            true => std::mem::take(&mut self.from),
+            false => self.from.next_prime(),
+        };
+        self.from = Mpz::from(&p + 1u32);
+        Some(p)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (usize::MAX, None)
+    }
+}
+
+impl FusedIterator for PrimeIter {}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    /// Primes by trial division.
+    fn is_prime(n: u64) -> bool {
+        n >= 2
+            && (2..)
+                .take_while(|d| d * d <= n)
+                .all(|d| n % d != 0)
+    }
+
+    #[test]
+    fn small() {
+        for n in -5i64..5000 {
+            let z = Mpz::from(n);
+            let expected = n >= 0 && is_prime(n as u64);
+            assert_eq!(z.is_probably_prime(0), expected, "{}", n);
+            assert_eq!(z.is_prime_bpsw(), expected, "{}", n);
+        }
+    }
+
+    #[test]
+    fn pseudoprimes() {
+        // Strong pseudoprimes to base 2, Lucas pseudoprimes, Carmichael numbers and squares.
+        let composites = [
+            2047u64,
+            3215031751,
+            2152302898747,
+            3825123056546413051,
+            5459,
+            5777,
+            10877,
+            16109,
+            18971,
+            561,
+            1105,
+            41041,
+            3 * 3,
+            53 * 53,
+            4294967291 * 4294967291,
+            4294967291 * 4294967279,
+        ];
+        for n in composites {
+            assert!(!Mpz::from(n).is_probably_prime(0), "{}", n);
+        }
+        let primes = [53u64, 65537, 4294967291, 18446744073709551557];
+        for p in primes {
+            assert!(Mpz::from(p).is_probably_prime(0), "{}", p);
+        }
+    }
+
+    #[test]
+    fn large() {
+        let m127 = (Mpz::from(1) << 127u32) - 1u32;
+        assert!(m127.is_probably_prime(25));
+        assert!(m127.is_prime_bpsw());
+        assert!(!(m127.clone() * &m127).is_probably_prime(25));
+        let m128 = (Mpz::from(1) << 128u32) - 1u32;
+        assert!(!m128.is_probably_prime(25));
+        // With no rounds of Miller-Rabin, only the Baillie-PSW test is run.
+        assert!(m127.is_probably_prime(0));
+        assert!(!(Mpz::from(u64::MAX) * 3u32).is_probably_prime(0));
+    }
+
+    #[test]
+    fn random_bases() {
+        // The shared state is not left with MPIR's default seed.
+        let first = with_state(|state| state.bits(64));
+        assert_ne!(first, RandState::new().bits(64));
+        assert_ne!(first, with_state(|state| state.bits(64)));
+    }
+
+    #[test]
+    fn next_prev() {
+        assert_eq!(Mpz::from(-10).next_prime(), 2);
+        assert_eq!(Mpz::from(2).next_prime(), 3);
+        assert_eq!(Mpz::from(3).next_prime(), 5);
+        assert_eq!(Mpz::from(24).next_prime(), 29);
+        assert_eq!(Mpz::from(2).prev_prime(), None);
+        assert_eq!(Mpz::from(3).prev_prime(), Some(Mpz::from(2)));
+        assert_eq!(Mpz::from(29).prev_prime(), Some(Mpz::from(23)));
+        assert_eq!(Mpz::from(30).prev_prime(), Some(Mpz::from(29)));
+
+        // Around 2^64, and 2^128 where the candidates come from MPIR.
+        let top = Mpz::from(18446744073709551557u64);
+        assert_eq!(Mpz::from(u64::MAX - 60).next_prime(), top);
+        assert_eq!(Mpz::from(u64::MAX).prev_prime(), Some(top.clone()));
+        assert_eq!(top.next_prime(), (Mpz::from(1) << 64u32) + 13u32);
+        let p = (Mpz::from(1) << 128u32).next_prime();
+        assert_eq!(p, (Mpz::from(1) << 128u32) + 51u32);
+        assert_eq!(p.prev_prime(), Some(Mpz::from(u128::MAX) - 158u32));
+    }
+
+    #[test]
+    fn prime_iter() {
+        let primes: Vec<Mpz> = PrimeIter::new(Mpz::from(-3)).take(6).collect();
+        assert_eq!(primes, [2, 3, 5, 7, 11, 13].map(Mpz::from));
+        let primes: Vec<Mpz> = PrimeIter::new(Mpz::from(89)).take(3).collect();
+        assert_eq!(primes, [89, 97, 101].map(Mpz::from));
+        let count = PrimeIter::new(Mpz::new())
+            .take_while(|p| *p < 10_000)
+            .count();
+        assert_eq!(count, 1229);
+    }
+}
@@ -0,0 +1,98 @@
+//! Random numbers for [`Mpz`]
+//!
+//! The numbers are drawn from a [`RandState`], so they are not suitable for cryptography.
+
+use super::*;
+use crate::rand::RandState;
+use crate::MpirError;
+
+impl Mpz {
+    /// A uniformly distributed random integer in the range `0..2^bits`.
+    pub fn random_bits(state: &mut RandState, bits: mp_bitcnt_t) -> Mpz {
+        let mut r = Mpz::new();
+        unsafe { mpz_urandomb(r.as_raw_mut(), state.as_raw_mut(), bits) };
+        r
+    }
+
+    /// A uniformly distributed random integer in the range `0..|n|`.
+    ///
+    /// MPIR divides by zero when `n` is 0, so this returns [`MpirError::DivisionByZero`] instead.
+    pub fn random_below(state: &mut RandState, n: &Mpz) -> Result<Mpz, MpirError> {
+        if n.is_zero() {
+            return Err(MpirError::DivisionByZero);
+        }
+        let mut r = Mpz::new();
+        unsafe { mpz_urandomm(r.as_raw_mut(), state.as_raw_mut(), n.as_raw()) };
+        Ok(r)
+    }
+
+    /// A random integer in the range `0..2^bits` with long runs of zeros and ones in binary.
+    ///
+    /// These are not uniformly distributed, but are more likely than uniform integers to trigger
+    /// corner cases, so are useful for testing.
+    pub fn random_bits_sparse(state: &mut RandState, bits: mp_bitcnt_t) -> Mpz {
+        let mut r = Mpz::new();
+        unsafe { mpz_rrandomb(r.as_raw_mut(), state.as_raw_mut(), bits) };
+        r
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn random_bits() {
+        let mut state = RandState::new();
+        assert_eq!(Mpz::random_bits(&mut state, 0), 0);
+        let limit = Mpz::from(1) << 200u32;
+        let mut top = false;
+        for _ in 0..100 {
+            let x = Mpz::random_bits(&mut state, 200);
+            assert!(x >= 0 && x < limit);
+            top |= x.size_in_base(2) == 200;
+        }
+        assert!(top);
+    }
+
+    #[test]
+    fn random_below() {
+        let mut state = RandState::new();
+        let n = (Mpz::from(1) << 100u32) + 1u32;
+        for _ in 0..100 {
+            let x = Mpz::random_below(&mut state, &n).unwrap();
+            assert!(x >= 0 && x < n);
+        }
+        let mut seen = [false; 6];
+        for _ in 0..200 {
+            let x = Mpz::random_below(&mut state, &Mpz::from(-6)).unwrap();
+            seen[usize::try_from(&x).unwrap()] = true;
+        }
+        assert_eq!(seen, [true; 6]);
+        assert_eq!(
+            Mpz::random_below(&mut state, &Mpz::new()),
+            Err(MpirError::DivisionByZero)
+        );
+    }
+
+    #[test]
+    fn random_bits_sparse() {
+        let mut state = RandState::new();
+        assert_eq!(Mpz::random_bits_sparse(&mut state, 0), 0);
+        let limit = Mpz::from(1) << 500u32;
+        for _ in 0..100 {
+            let x = Mpz::random_bits_sparse(&mut state, 500);
+            assert!(x >= 0 && x < limit);
+        }
+    }
+
+    #[test]
+    fn seeded() {
+        let (mut a, mut b) = (RandState::new(), RandState::new());
+        a.seed_u64(1);
+        b.seed_u64(1);
+        let x = Mpz::random_bits(&mut a, 1000);
+        assert_eq!(x, Mpz::random_bits(&mut b, 1000));
+        assert_ne!(x, Mpz::random_bits(&mut a, 1000));
+    }
+}
@@ -0,0 +1,105 @@
+//! Wiping [`Mpz`] values that hold secrets
+//!
+//! [`Zeroize`] overwrites every allocated limb, not only those in use, as a value that has shrunk
+//! still holds its old high limbs. It cannot reach copies that MPIR made while computing, or blocks
+//! it has since reallocated, so with the `zeroize` feature the memory functions in [`crate::alloc`]
+//! also wipe every block before it is freed.
+
+use std::fmt;
+use std::ops::{Deref, DerefMut};
+
+use zeroize::{Zeroize, ZeroizeOnDrop};
+
+use super::*;
+
+impl Zeroize for Mpz {
+    /// Overwrite all of the allocated limbs with zeros, leaving the value 0.
+    fn zeroize(&mut self) {
+        let alloc = self.0._mp_alloc.max(0) as usize;
+        if alloc > 0 {
+            unsafe { std::slice::from_raw_parts_mut(self.0._mp_d as *mut mp_limb_t, alloc) }
+                .zeroize();
+        }
+        self.0._mp_size = 0;
+    }
+}
+
+/// An [`Mpz`] that is wiped with [`Zeroize`] when it is dropped.
+///
+/// It dereferences to the `Mpz`, and its `Debug` output does not show the value.
+#[derive(Clone, Default)]
+pub struct SecretMpz(Mpz);
+
+impl SecretMpz {
+    /// Take ownership of `value`.
+    pub fn new(value: Mpz) -> Self {
+        SecretMpz(value)
+    }
+}
+
+impl From<Mpz> for SecretMpz {
+    fn from(value: Mpz) -> Self {
+        SecretMpz(value)
+    }
+}
+
+impl Deref for SecretMpz {
+    type Target = Mpz;
+
+    fn deref(&self) -> &Mpz {
+        &self.0
+    }
+}
+
+impl DerefMut for SecretMpz {
+    fn deref_mut(&mut self) -> &mut Mpz {
+        &mut self.0
+    }
+}
+
+impl Drop for SecretMpz {
+    fn drop(&mut self) {
+        self.0.zeroize();
+    }
+}
+
+impl ZeroizeOnDrop for SecretMpz {}
+
+impl fmt::Debug for SecretMpz {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("SecretMpz(..)")
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn allocated(x: &Mpz) -> &[mp_limb_t] {
+        unsafe { std::slice::from_raw_parts(x.0._mp_d as *const mp_limb_t, x.0._mp_alloc as usize) }
+    }
+
+    #[test]
+    fn zeroize() {
+        let mut x = Mpz::from(u128::MAX) << 200u32;
+        // Shrink the value, leaving the old limbs allocated beyond the ones in use.
+        x >>= 300u32;
+        assert!(allocated(&x).iter().any(|&l| l != 0));
+        x.zeroize();
+        assert!(x.is_zero());
+        assert!(allocated(&x).iter().all(|&l| l == 0));
+        // The value is still usable.
+        x += 7;
+        assert_eq!(x, 7);
+    }
+
+    #[test]
+    fn secret() {
+        let mut s = SecretMpz::from(Mpz::from(12345));
+        *s += 1;
+        assert_eq!(*s, 12346);
+        assert_eq!(s.to_string(), "12346");
+        assert_eq!(format!("{:?}", s), "SecretMpz(..)");
+        assert_eq!(*s.clone(), 12346);
+    }
+}
@@ -0,0 +1,157 @@
+//! Uniform sampling of [`Mpz`] from a [`rand::RngCore`]
+//!
+//! Integers are drawn a limb at a time, with the top limb masked to the bit length of the bound,
+//! and rejected if they are not below it. Fewer than half are rejected, and the result is exactly
+//! uniform, so with a cryptographically secure generator it is suitable for keys and nonces.
+
+use ::rand::distributions::uniform::{SampleBorrow, SampleUniform, UniformSampler};
+use ::rand::{Rng, RngCore};
+
+use super::*;
+use crate::rand::fill_limbs;
+use crate::MpirError;
+
+impl Mpz {
+    /// A uniformly distributed random integer in the range `0..|bound|`, drawn from `rng`.
+    ///
+    /// Returns [`MpirError::DivisionByZero`] if `bound` is 0, as [`Mpz::random_below`] does.
+    pub fn random_below_with<R: RngCore + ?Sized>(
+        rng: &mut R,
+        bound: &Mpz,
+    ) -> Result<Mpz, MpirError> {
+        if bound.is_zero() {
+            return Err(MpirError::DivisionByZero);
+        }
+        let limb_bits = mp_limb_t::BITS as usize;
+        let bits = bound.size_in_base(2);
+        let n = bits.div_ceil(limb_bits);
+        let mut r = Mpz::new();
+        unsafe { mpz_realloc2(r.as_raw_mut(), (n * limb_bits) as mp_bitcnt_t) };
+        loop {
+            let limbs = unsafe { std::slice::from_raw_parts_mut(r.0._mp_d as *mut mp_limb_t, n) };
+            fill_limbs(rng, limbs);
+            if bits % limb_bits != 0 {
+                limbs[n - 1] &= (1 << (bits % limb_bits)) - 1;
+            }
+            let size = limbs.iter().rposition(|&l| l != 0).map_or(0, |i| i + 1);
+            r.0._mp_size = size as c_int;
+            if r.cmp_abs(bound) == std::cmp::Ordering::Less {
+                return Ok(r);
+            }
+        }
+    }
+}
+
+/// Samples [`Mpz`] uniformly from a range, so that `rng.gen_range(a..b)` and
+/// `rand::distributions::Uniform` work with `Mpz`.
+#[derive(Clone, Debug)]
+pub struct UniformMpz {
+    low: Mpz,
+    range: Mpz,
+}
+
+impl SampleUniform for Mpz {
+    type Sampler = UniformMpz;
+}
+
+impl UniformSampler for UniformMpz {
+    type X = Mpz;
+
+    fn new<B1, B2>(low: B1, high: B2) -> Self
+    where
+        B1: SampleBorrow<Mpz> + Sized,
+        B2: SampleBorrow<Mpz> + Sized,
+    {
+        let (low, high) = (low.borrow(), high.borrow());
+        assert!(low < high, "UniformMpz::new called with `low >= high`");
+        UniformMpz {
+            low: low.clone(),
+            range: Mpz::from(high - low),
+        }
+    }
+
+    fn new_inclusive<B1, B2>(low: B1, high: B2) -> Self
+    where
+        B1: SampleBorrow<Mpz> + Sized,
+        B2: SampleBorrow<Mpz> + Sized,
+    {
+        let (low, high) = (low.borrow(), high.borrow());
+        assert!(
+            low <= high,
+            "UniformMpz::new_inclusive called with `low > high`"
+        );
+        UniformMpz {
+            low: low.clone(),
+            range: Mpz::from(high - low) + 1u32,
+        }
+    }
+
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> Mpz {
+        Mpz::random_below_with(rng, &self.range).unwrap() + &self.low
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use ::rand::distributions::{Distribution, Uniform};
+    use ::rand::rngs::mock::StepRng;
+    use ::rand::rngs::StdRng;
+    use ::rand::SeedableRng;
+
+    #[test]
+    fn random_below_with() {
+        let mut rng = StdRng::seed_from_u64(1);
+        let bound = (Mpz::from(1) << 200u32) + 1u32;
+        for _ in 0..100 {
+            let x = Mpz::random_below_with(&mut rng, &bound).unwrap();
+            assert!(x >= 0 && x < bound);
+        }
+        let mut seen = [false; 5];
+        for _ in 0..100 {
+            let x = Mpz::random_below_with(&mut rng, &Mpz::from(-5)).unwrap();
+            seen[usize::try_from(&x).unwrap()] = true;
+        }
+        assert_eq!(seen, [true; 5]);
+        assert_eq!(
+            Mpz::random_below_with(&mut rng, &Mpz::new()),
+            Err(MpirError::DivisionByZero)
+        );
+    }
+
+    #[test]
+    fn rejection() {
+        // 6 and 7 are masked to 3 bits and rejected, leaving 0 from the next limb.
+        let mut rng = StepRng::new(6, 1);
+        assert_eq!(Mpz::random_below_with(&mut rng, &Mpz::from(5)).unwrap(), 0);
+        let bound = Mpz::from(u128::MAX);
+        let x = Mpz::random_below_with(&mut StepRng::new(7, 0), &bound).unwrap();
+        assert_eq!(x, (Mpz::from(7) << 64u32) + 7u32);
+    }
+
+    #[test]
+    fn gen_range() {
+        let mut rng = StdRng::seed_from_u64(2);
+        let (low, high) = (Mpz::from(-3), Mpz::from(3));
+        let mut seen = [false; 7];
+        for _ in 0..200 {
+            let x = rng.gen_range(low.clone()..=high.clone());
+            seen[usize::try_from(x + 3u32).unwrap()] = true;
+        }
+        assert_eq!(seen, [true; 7]);
+        let big = Mpz::from(1) << 100u32;
+        let x = rng.gen_range(big.clone()..Mpz::from(&big * 2u32));
+        assert!(x >= big && x < Mpz::from(&big * 2u32));
+        let uniform = Uniform::new(Mpz::from(10), Mpz::from(12));
+        assert!(uniform
+            .sample_iter(rng)
+            .take(50)
+            .all(|x| x == 10 || x == 11));
+    }
+
+    #[test]
+    #[should_panic(expected = "low >= high")]
+    fn uniform_empty() {
+        Uniform::new(Mpz::from(1), Mpz::from(1));
+    }
+}
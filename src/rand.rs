@@ -0,0 +1,314 @@
+//! Random Number Generation
+//!
+//! [MPIR 3.0.0 - C documentation](https://mpir.org/mpir-3.0.0.pdf)
+//!
+//! A [`RandState`] holds the algorithm and state of a pseudo-random number generator, and is passed
+//! to the functions that draw random numbers, such as [`Mpz::random_bits`]. None of the algorithms
+//! are suitable for cryptography.
+//!
+//! Each new state starts from the same default seed, so the numbers are the same on every run
+//! unless it is reseeded, for example from the system time or `/dev/urandom`.
+//!
+//! With the `rand` feature, `RandState::from_rng` makes a state that draws from any
+//! `rand::RngCore` instead, including the cryptographically secure ones.
+
+use std::fmt;
+use std::mem::MaybeUninit;
+
+use crate::ctype::{
+    c_int, c_ulong, gmp_randstate_ptr, gmp_randstate_srcptr, gmp_randstate_struct, mp_bitcnt_t,
+    mpz_srcptr,
+};
+use crate::{MpirError, Mpz};
+
+#[cfg(feature = "rand")]
+mod adapter;
+
+#[cfg(feature = "rand")]
+pub(crate) use adapter::fill_limbs;
+
+/* As for mpz, the symbols are named with link_name, and the bindings use the C ABI. */
+extern "C" {
+
+    // ---------------------------------------------------------------------------------------------
+    // Random State Initialization
+
+    /// Initialize state with a default algorithm. This will be a compromise between speed and
+    /// randomness, and is recommended for applications with no special requirements. Currently
+    /// this is gmp_randinit_mt.
+    #[link_name = "__gmp_randinit_default"]
+    pub fn gmp_randinit_default(state: gmp_randstate_ptr);
+
+    /// Initialize state for a Mersenne Twister algorithm. This algorithm is fast and has good
+    /// randomness properties.
+    #[link_name = "__gmp_randinit_mt"]
+    pub fn gmp_randinit_mt(state: gmp_randstate_ptr);
+
+    /// Initialize state with a linear congruential algorithm X = (aX + c) mod 2^m2exp.
+    ///
+    /// The low bits of X in this algorithm are not very random. The least significant bit will
+    /// have a period no more than 2, and the second bit no more than 4, etc. For this reason only
+    /// the high half of each X is actually used.
+    ///
+    /// When a random number of more than m2exp/2 bits is to be generated, multiple iterations of
+    /// the recurrence are used and the results concatenated.
+    #[link_name = "__gmp_randinit_lc_2exp"]
+    pub fn gmp_randinit_lc_2exp(
+        state: gmp_randstate_ptr,
+        a: mpz_srcptr,
+        c: c_ulong,
+        m2exp: mp_bitcnt_t,
+    );
+
+    /// Initialize state for a linear congruential algorithm as per gmp_randinit_lc_2exp. a, c and
+    /// m2exp are selected from a table, chosen so that size bits (or more) of each X will be used,
+    /// ie. m2exp/2 >= size.
+    ///
+    /// If successful the return value is non-zero. If size is bigger than the table data provides
+    /// then the return value is zero. The maximum size currently supported is 128.
+    #[link_name = "__gmp_randinit_lc_2exp_size"]
+    pub fn gmp_randinit_lc_2exp_size(state: gmp_randstate_ptr, size: mp_bitcnt_t) -> c_int;
+
+    /// Initialize rop with a copy of the algorithm and state from op.
+    #[link_name = "__gmp_randinit_set"]
+    pub fn gmp_randinit_set(rop: gmp_randstate_ptr, op: gmp_randstate_srcptr);
+
+    /// Free all memory occupied by state.
+    #[link_name = "__gmp_randclear"]
+    pub fn gmp_randclear(state: gmp_randstate_ptr);
+
+    // ---------------------------------------------------------------------------------------------
+    // Random State Seeding
+
+    /// Set an initial seed value into state.
+    ///
+    /// The size of a seed determines how many different sequences of random numbers that it's
+    /// possible to generate. The "quality" of the seed is the randomness of a given seed compared
+    /// to the previous seed used, and this affects the randomness of separate number sequences.
+    #[link_name = "__gmp_randseed"]
+    pub fn gmp_randseed(state: gmp_randstate_ptr, seed: mpz_srcptr);
+
+    /// Set an initial seed value into state.
+    #[link_name = "__gmp_randseed_ui"]
+    pub fn gmp_randseed_ui(state: gmp_randstate_ptr, seed: c_ulong);
+
+    // ---------------------------------------------------------------------------------------------
+    // Random State Miscellaneous
+
+    /// Return a uniformly distributed random number of n bits, ie. in the range 0 to 2^n − 1
+    /// inclusive. n must be less than or equal to the number of bits in an unsigned long.
+    #[link_name = "__gmp_urandomb_ui"]
+    pub fn gmp_urandomb_ui(state: gmp_randstate_ptr, n: c_ulong) -> c_ulong;
+
+    /// Return a uniformly distributed random number in the range 0 to n − 1, inclusive.
+    #[link_name = "__gmp_urandomm_ui"]
+    pub fn gmp_urandomm_ui(state: gmp_randstate_ptr, n: c_ulong) -> c_ulong;
+}
+
+/// The state of a pseudo-random number generator, which is cleared when the value is dropped.
+///
+/// Cloning copies the algorithm and its current state, so the clone produces the same numbers as
+/// the original from then on.
+pub struct RandState(gmp_randstate_struct);
+
+unsafe impl Send for RandState {}
+unsafe impl Sync for RandState {}
+
+impl RandState {
+    /// Create a state with the default algorithm, currently the Mersenne Twister.
+    pub fn new() -> Self {
+        unsafe { Self::from_init(|s| gmp_randinit_default(s)) }
+    }
+
+    /// Create a state with the Mersenne Twister algorithm, which is fast and has good randomness
+    /// properties.
+    pub fn new_mt() -> Self {
+        unsafe { Self::from_init(|s| gmp_randinit_mt(s)) }
+    }
+
+    /// Create a state with the linear congruential algorithm `X = (a X + c) mod 2^m2exp`.
+    ///
+    /// Only the high half of each `X` is used, as the low bits are not very random.
+    ///
+    /// # Panics
+    ///
+    /// If `m2exp` is 0, which MPIR would abort on.
+    pub fn new_lc_2exp(a: &Mpz, c: c_ulong, m2exp: mp_bitcnt_t) -> Self {
+        assert!(m2exp > 0, "linear congruential modulus of 2^0");
+        unsafe { Self::from_init(|s| gmp_randinit_lc_2exp(s, a.as_raw(), c, m2exp)) }
+    }
+
+    /// Create a state with a linear congruential algorithm that uses at least `size` bits of each
+    /// `X`, with parameters from a table. Returns `None` if `size` is greater than 128, the largest
+    /// in the table.
+    pub fn new_lc_2exp_size(size: mp_bitcnt_t) -> Option<Self> {
+        crate::alloc::init();
+        let mut state = MaybeUninit::<gmp_randstate_struct>::uninit();
+        unsafe {
+            match gmp_randinit_lc_2exp_size(state.as_mut_ptr(), size) {
+                0 => None,
+                _ => Some(RandState(state.assume_init())),
+            }
+        }
+    }
+
+    /// Seed the generator, which restarts its sequence.
+    pub fn seed(&mut self, seed: &Mpz) {
+        unsafe { gmp_randseed(self.as_raw_mut(), seed.as_raw()) }
+    }
+
+    /// Seed the generator, which restarts its sequence.
+    #[allow(clippy::unnecessary_fallible_conversions)]
+    pub fn seed_u64(&mut self, seed: u64) {
+        match c_ulong::try_from(seed) {
+            Ok(s) => unsafe { gmp_randseed_ui(self.as_raw_mut(), s) },
+            Err(_) => self.seed(&Mpz::from(seed)),
+        }
+    }
+
+    /// A uniformly distributed random number of `n` bits, in the range `0..2^n`.
+    ///
+    /// # Panics
+    ///
+    /// If `n` is greater than the number of bits in a C `unsigned long`, which is 32 on Windows and
+    /// 64 elsewhere.
+    pub fn bits(&mut self, n: u32) -> u64 {
+        assert!(n <= c_ulong::BITS, "at most {} random bits", c_ulong::BITS);
+        unsafe { gmp_urandomb_ui(self.as_raw_mut(), n as c_ulong) as u64 }
+    }
+
+    /// A uniformly distributed random number in the range `0..n`.
+    ///
+    /// MPIR divides by zero when `n` is 0, so this returns [`MpirError::DivisionByZero`] instead.
+    #[allow(clippy::unnecessary_fallible_conversions)]
+    pub fn below(&mut self, n: u64) -> Result<u64, MpirError> {
+        if n == 0 {
+            return Err(MpirError::DivisionByZero);
+        }
+        match c_ulong::try_from(n) {
+            Ok(n) => Ok(unsafe { gmp_urandomm_ui(self.as_raw_mut(), n) as u64 }),
+            Err(_) => Ok(u64::try_from(Mpz::random_below(self, &Mpz::from(n))?).unwrap()),
+        }
+    }
+
+    /// Pointer to the underlying `gmp_randstate_struct`, for passing to MPIR.
+    pub fn as_raw(&self) -> gmp_randstate_srcptr {
+        &self.0
+    }
+
+    /// Mutable pointer to the underlying `gmp_randstate_struct`, for passing to MPIR.
+    pub fn as_raw_mut(&mut self) -> gmp_randstate_ptr {
+        &mut self.0
+    }
+
+    /// Initialise a state with `init`, which must call one of the `gmp_randinit` functions.
+    unsafe fn from_init<F: FnOnce(gmp_randstate_ptr)>(init: F) -> Self {
+        crate::alloc::init();
+        let mut state = MaybeUninit::<gmp_randstate_struct>::uninit();
+        init(state.as_mut_ptr());
+        RandState(state.assume_init())
+    }
+}
+
+impl Default for RandState {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Clone for RandState {
+    fn clone(&self) -> Self {
+        unsafe { Self::from_init(|s| gmp_randinit_set(s, self.as_raw())) }
+    }
+}
+
+impl fmt::Debug for RandState {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("RandState(..)")
+    }
+}
+
+impl Drop for RandState {
+    fn drop(&mut self) {
+        unsafe { gmp_randclear(self.as_raw_mut()) }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn sample(state: &mut RandState) -> Vec<u64> {
+        (0..20).map(|_| state.bits(32)).collect()
+    }
+
+    #[test]
+    fn algorithms() {
+        let mut states = [
+            RandState::new(),
+            RandState::new_mt(),
+            RandState::new_lc_2exp(&Mpz::from(1103515245), 12345, 64),
+            RandState::new_lc_2exp_size(128).unwrap(),
+        ];
+        for state in &mut states {
+            let xs = sample(state);
+            assert!(xs.iter().all(|&x| x < 1 << 32));
+            assert!(xs.iter().any(|&x| x != xs[0]));
+        }
+        assert!(RandState::new_lc_2exp_size(129).is_none());
+        assert_eq!(
+            sample(&mut RandState::new()),
+            sample(&mut RandState::new_mt())
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "modulus of 2^0")]
+    fn lc_2exp_zero() {
+        RandState::new_lc_2exp(&Mpz::from(3), 1, 0);
+    }
+
+    #[test]
+    fn seed() {
+        let (mut a, mut b) = (RandState::new(), RandState::new());
+        a.seed_u64(42);
+        b.seed(&Mpz::from(42));
+        assert_eq!(sample(&mut a), sample(&mut b));
+        let first = sample(&mut a);
+        a.seed_u64(42);
+        sample(&mut a);
+        assert_eq!(sample(&mut a), first);
+        b.seed_u64(43);
+        assert_ne!(sample(&mut b), first);
+    }
+
+    #[test]
+    fn clone() {
+        let mut a = RandState::new();
+        a.seed_u64(7);
+        sample(&mut a);
+        let mut b = a.clone();
+        assert_eq!(sample(&mut a), sample(&mut b));
+        drop(a);
+        sample(&mut b);
+    }
+
+    #[test]
+    fn bits_below() {
+        let mut state = RandState::new();
+        assert_eq!(state.bits(0), 0);
+        for _ in 0..100 {
+            assert!(state.bits(3) < 8);
+            assert!(state.below(5).unwrap() < 5);
+        }
+        assert_eq!(state.below(1), Ok(0));
+        assert_eq!(state.below(0), Err(MpirError::DivisionByZero));
+        state.bits(c_ulong::BITS);
+    }
+
+    #[test]
+    #[should_panic(expected = "random bits")]
+    fn bits_too_many() {
+        RandState::new().bits(c_ulong::BITS + 1);
+    }
+}
@@ -0,0 +1,150 @@
+//! Random states that draw from a [`rand::RngCore`]
+//!
+//! MPIR and GMP keep a table of functions for each algorithm in `_mp_algdata`, `gmp_randfnptr_t`
+//! in `gmp-impl.h`, and only ever call a state's algorithm through it. A state made by
+//! [`RandState::from_rng`] points it at the functions below, and keeps the boxed generator in the
+//! limb pointer of `_mp_seed`, which is otherwise only read by the built-in algorithms.
+
+use std::panic::{self, AssertUnwindSafe};
+use std::{process, slice};
+
+use ::rand::RngCore;
+
+use super::RandState;
+use crate::ctype::{
+    c_ulong, c_void, gmp_randstate_ptr, gmp_randstate_srcptr, gmp_randstate_struct, mp_limb_t,
+    mpz_srcptr, mpz_struct,
+};
+
+/// `gmp_randfnptr_t`. MPIR is not exception-safe, so nothing may unwind out of these functions.
+#[repr(C)]
+struct RandFns {
+    seed: unsafe extern "C" fn(gmp_randstate_ptr, mpz_srcptr),
+    get: unsafe extern "C" fn(gmp_randstate_ptr, *mut mp_limb_t, c_ulong),
+    clear: unsafe extern "C" fn(gmp_randstate_ptr),
+    iset: unsafe extern "C" fn(gmp_randstate_ptr, gmp_randstate_srcptr),
+}
+
+static RNG_FNS: RandFns = RandFns {
+    seed,
+    get,
+    clear,
+    iset,
+};
+
+/// A generator that can be cloned behind a `dyn`, for `gmp_randinit_set`.
+trait Rng: RngCore + Send + Sync {
+    fn box_clone(&self) -> Box<dyn Rng>;
+}
+
+impl<R: RngCore + Clone + Send + Sync + 'static> Rng for R {
+    fn box_clone(&self) -> Box<dyn Rng> {
+        Box::new(self.clone())
+    }
+}
+
+impl RandState {
+    /// Create a state that draws its random numbers from `rng`, so that MPIR functions taking a
+    /// state, such as `mpz_next_prime_candidate`, can use a Rust generator.
+    ///
+    /// Cloning the state clones the generator. Seeding it has no effect; seed `rng` before instead.
+    ///
+    /// `rng` must not panic. A panic can't unwind through MPIR, so it aborts the process.
+    pub fn from_rng<R: RngCore + Clone + Send + Sync + 'static>(rng: R) -> Self {
+        crate::alloc::init();
+        RandState(state(Box::new(rng)))
+    }
+}
+
+fn state(rng: Box<dyn Rng>) -> gmp_randstate_struct {
+    gmp_randstate_struct {
+        _mp_seed: mpz_struct {
+            _mp_alloc: 0,
+            _mp_size: 0,
+            _mp_d: Box::into_raw(Box::new(rng)) as *mut c_void,
+        },
+        _mp_alg: 0,
+        _mp_algdata: &RNG_FNS as *const RandFns as *mut c_void,
+    }
+}
+
+unsafe fn rng<'a>(state: gmp_randstate_srcptr) -> &'a mut Box<dyn Rng> {
+    &mut *((*state)._mp_seed._mp_d as *mut Box<dyn Rng>)
+}
+
+/// Fill `limbs` with random bits from `rng`.
+pub(crate) fn fill_limbs<R: RngCore + ?Sized>(rng: &mut R, limbs: &mut [mp_limb_t]) {
+    for limb in limbs {
+        *limb = rng.next_u64() as mp_limb_t;
+    }
+}
+
+unsafe extern "C" fn seed(_: gmp_randstate_ptr, _: mpz_srcptr) {}
+
+/// Write `nbits` random bits to `rp`, with the rest of the last limb zero. Aborts if the generator
+/// panics.
+unsafe extern "C" fn get(state: gmp_randstate_ptr, rp: *mut mp_limb_t, nbits: c_ulong) {
+    let limb_bits = mp_limb_t::BITS as usize;
+    let nbits = nbits as usize;
+    if nbits == 0 {
+        return;
+    }
+    let limbs = slice::from_raw_parts_mut(rp, nbits.div_ceil(limb_bits));
+    let rng = rng(state);
+    if panic::catch_unwind(AssertUnwindSafe(|| fill_limbs(rng.as_mut(), limbs))).is_err() {
+        process::abort();
+    }
+    if nbits % limb_bits != 0 {
+        *limbs.last_mut().unwrap() &= (1 << (nbits % limb_bits)) - 1;
+    }
+}
+
+unsafe extern "C" fn clear(state: gmp_randstate_ptr) {
+    drop(Box::from_raw((*state)._mp_seed._mp_d as *mut Box<dyn Rng>));
+}
+
+unsafe extern "C" fn iset(dst: gmp_randstate_ptr, src: gmp_randstate_srcptr) {
+    dst.write(state(rng(src).box_clone()));
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::Mpz;
+    use ::rand::rngs::mock::StepRng;
+    use ::rand::rngs::StdRng;
+    use ::rand::SeedableRng;
+
+    #[test]
+    fn from_rng() {
+        let mut state = RandState::from_rng(StepRng::new(0x0123_4567_89ab_cdef, 1));
+        assert_eq!(Mpz::random_bits(&mut state, 64), 0x0123_4567_89ab_cdefu64);
+        assert_eq!(Mpz::random_bits(&mut state, 8), 0xf0);
+        assert_eq!(Mpz::random_bits(&mut state, 0), 0);
+        assert_eq!(state.bits(4), 1);
+        state.seed_u64(0);
+        assert_eq!(state.bits(64), 0x0123_4567_89ab_cdf2);
+    }
+
+    #[test]
+    fn from_rng_clone() {
+        let mut a = RandState::from_rng(StdRng::seed_from_u64(5));
+        let mut b = a.clone();
+        let x = Mpz::random_bits(&mut a, 300);
+        assert_eq!(x, Mpz::random_bits(&mut b, 300));
+        drop(a);
+        let below = Mpz::random_below(&mut b, &x).unwrap();
+        assert!(below < x);
+    }
+
+    #[test]
+    #[cfg(not(feature = "backend-gmp"))]
+    fn from_rng_prime_candidate() {
+        use crate::mpz::mpz_next_prime_candidate;
+        let mut state = RandState::from_rng(StdRng::seed_from_u64(5));
+        let mut c = Mpz::new();
+        let n = Mpz::from(1) << 127u32;
+        unsafe { mpz_next_prime_candidate(c.as_raw_mut(), n.as_raw(), state.as_raw_mut()) };
+        assert!(c > n && c < n + 1000u32);
+    }
+}
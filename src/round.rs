@@ -0,0 +1,15 @@
+//! Rounding modes for integer division
+
+/// How the quotient of an integer division is rounded, which also decides the sign of the
+/// remainder.
+#[derive(PartialEq, Eq, Clone, Copy, Hash, Debug)]
+pub enum Round {
+    /// Round towards positive infinity. The remainder has the opposite sign to the divisor.
+    Ceil,
+    /// Round towards negative infinity. The remainder has the same sign as the divisor.
+    Floor,
+    /// Round towards zero. The remainder has the same sign as the dividend.
+    Trunc,
+    /// Round so that the remainder is never negative.
+    Euclid,
+}
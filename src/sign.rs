@@ -1,8 +1,48 @@
 //! For pos/neg checks
 
+use std::cmp::Ordering;
+
+/// The sign of a number, ordered from `Negative` to `Positive`.
 #[derive(PartialEq, Eq, PartialOrd, Ord, Clone, Copy, Hash, Debug)]
 pub enum Sign {
     Negative,
     Zero,
     Positive,
 }
+
+/// `Less` is `Negative`, `Equal` is `Zero` and `Greater` is `Positive`, so that the sign of `x` is
+/// `x.cmp(&0)`.
+impl From<Ordering> for Sign {
+    fn from(ord: Ordering) -> Self {
+        match ord {
+            Ordering::Less => Sign::Negative,
+            Ordering::Equal => Sign::Zero,
+            Ordering::Greater => Sign::Positive,
+        }
+    }
+}
+
+impl From<Sign> for Ordering {
+    fn from(sign: Sign) -> Self {
+        match sign {
+            Sign::Negative => Ordering::Less,
+            Sign::Zero => Ordering::Equal,
+            Sign::Positive => Ordering::Greater,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn ordering() {
+        for v in [-3, 0, 3] {
+            let ord = v.cmp(&0);
+            assert_eq!(Ordering::from(Sign::from(ord)), ord);
+        }
+        assert_eq!(Sign::from(Ordering::Less), Sign::Negative);
+        assert!(Sign::Negative < Sign::Zero && Sign::Zero < Sign::Positive);
+    }
+}
@@ -0,0 +1,57 @@
+//! MPIR's memory goes through the global allocator after `use_rust_allocator`.
+//!
+//! This replaces the global allocator and MPIR's memory functions for the whole process, so it is
+//! an integration test with a single test function.
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use mpir::Mpz;
+
+/// Counts the bytes currently allocated.
+struct Counting;
+
+static ALLOCATED: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for Counting {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATED.fetch_add(layout.size(), Ordering::SeqCst);
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        ALLOCATED.fetch_sub(layout.size(), Ordering::SeqCst);
+        System.dealloc(ptr, layout)
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        ALLOCATED.fetch_add(new_size, Ordering::SeqCst);
+        ALLOCATED.fetch_sub(layout.size(), Ordering::SeqCst);
+        System.realloc(ptr, layout, new_size)
+    }
+}
+
+#[global_allocator]
+static GLOBAL: Counting = Counting;
+
+#[test]
+fn limbs_use_global_allocator() {
+    mpir::alloc::use_rust_allocator();
+
+    let before = ALLOCATED.load(Ordering::SeqCst);
+    let mut x = Mpz::from(1) << 80_000u32;
+    assert!(ALLOCATED.load(Ordering::SeqCst) >= before + 10_000);
+
+    // Growing goes through realloc, and the string from mpz_get_str is freed by the global
+    // allocator too.
+    x <<= 80_000u32;
+    x += 1;
+    assert_eq!(x.to_string_radix(32).unwrap().len(), 32_001);
+    assert!(ALLOCATED.load(Ordering::SeqCst) >= before + 20_000);
+
+    drop(x);
+    assert!(ALLOCATED.load(Ordering::SeqCst) < before + 1_000);
+
+    // The allocator cannot be changed once MPIR has allocated with it.
+    assert!(std::panic::catch_unwind(mpir::alloc::use_rust_allocator).is_err());
+}